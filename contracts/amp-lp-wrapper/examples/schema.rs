@@ -1,6 +1,6 @@
 use wrapper_implementations::steak::SteakInitMsg;
 
-use cw20_base::msg::{ExecuteMsg, QueryMsg};
+use basset::wrapper::{ExecuteMsg, QueryMsg};
 
 use cosmwasm_schema::write_api;
 
@@ -12,12 +12,14 @@ use cw20::BalanceResponse;
 use cw20::Cw20ExecuteMsg;
 use cw20::Cw20QueryMsg;
 
-use cw20_base::msg::QueryMsg;
+use basset::dex_router::AssetInfo;
+use basset::wrapper::QueryMsg;
+use cw2::set_contract_version;
 use cw20_base::ContractError;
 
 use basset::wrapper::ExecuteMsg;
 
-use cavern_lsd_wrapper_token_with_limit::msg::TokenInitMsg;
+use cavern_lsd_wrapper_token_with_limit::msg::{MigrateMsg, TokenInitMsg};
 use cavern_lsd_wrapper_token_with_limit::trait_def::LSDHub;
 
 #[cw_serde]
@@ -72,6 +74,16 @@ impl LSDHub<ContractsRaw> for AmphHub {
         Ok(balance.balance)
     }
 
+    fn cw20_token(&self) -> Option<Addr> {
+        Some(self.contracts.token.clone())
+    }
+
+    fn lsd_asset_info(&self) -> AssetInfo {
+        AssetInfo::Token {
+            contract_addr: self.contracts.token.clone(),
+        }
+    }
+
     fn deposit_funds(
         &self,
         _deps: Deps,
@@ -107,20 +119,49 @@ impl LSDHub<ContractsRaw> for AmphHub {
             funds: vec![],
         })])
     }
+
+    fn send_funds_with_msg(
+        &self,
+        _deps: Deps,
+        _env: Env,
+        amount: Uint128,
+        contract: Addr,
+        msg: Binary,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.contracts.token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: contract.to_string(),
+                amount,
+                msg,
+            })?,
+            funds: vec![],
+        })])
+    }
 }
 
 pub type SpectrumInitMsg = TokenInitMsg<ContractsRaw>;
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: SpectrumInitMsg,
 ) -> StdResult<Response> {
-    cavern_lsd_wrapper_token_with_limit::contract::instantiate::<ContractsRaw, AmphHub>(
-        deps, env, info, msg,
-    )
+    let res =
+        cavern_lsd_wrapper_token_with_limit::contract::instantiate::<ContractsRaw, AmphHub>(
+            deps.branch(),
+            env,
+            info,
+            msg,
+        )?;
+    // cw20-base records its own contract name on instantiation, we replace it with the wrapper's
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -141,3 +182,14 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         deps, env, msg,
     )
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    cavern_lsd_wrapper_token_with_limit::contract::migrate::<ContractsRaw, AmphHub>(
+        deps,
+        env,
+        msg,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+    )
+}
@@ -5,11 +5,13 @@ use cosmwasm_std::SubMsg;
 use basset::external::{
     CTokenStateResponse, SpectrumQueryMsg, UserInfoResponse,
 };
+use basset::eris_lp::StateResponse;
 use basset::hub::Config;
 use cosmwasm_std::WasmMsg;
 use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
 use cosmwasm_std::Empty;
+use cosmwasm_std::{Addr, Decimal};
 use cosmwasm_std::{
     from_binary, from_slice, to_binary, AllBalanceResponse, Api, BalanceResponse, BankQuery,
     CanonicalAddr, Coin, ContractResult, OwnedDeps, Querier, QuerierResult, QueryRequest,
@@ -24,6 +26,7 @@ use std::marker::PhantomData;
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
 
 pub const MOCK_GENERATOR_ADDR: &str = "spectrum-generator";
+pub const MOCK_AMP_HUB_ADDR: &str = "astroport-hub";
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let custom_querier: WasmMockQuerier = WasmMockQuerier::new(MockQuerier::new(&[]));
@@ -177,6 +180,21 @@ impl WasmMockQuerier {
                     _ => panic!("Not unimplemented! for tests")
                 } }
 
+                // The amp hub answers the eris state query
+                if contract_addr.as_str() == MOCK_AMP_HUB_ADDR {
+                    return SystemResult::Ok(ContractResult::from(to_binary(&StateResponse {
+                        total_lp: self.bond_share_querier.amount,
+                        total_amp_lp: self.bond_share_querier.share,
+                        exchange_rate: Decimal::from_ratio(
+                            self.bond_share_querier.amount,
+                            self.bond_share_querier.share,
+                        ),
+                        pair_contract: Addr::unchecked("pair"),
+                        locked_assets: vec![],
+                        user_info: None,
+                    })));
+                }
+
                 // If we don't recognize it, we simply assume it's a cw20 query
                 match from_binary(msg).unwrap() {
                     Cw20QueryMsg::TokenInfo {} => {
@@ -212,13 +230,13 @@ impl WasmMockQuerier {
                             match self.token_querier.balances.get(contract_addr) {
                                 Some(balances) => balances,
                                 None => {
-                                    return SystemResult::Err(SystemError::InvalidRequest {
-                                        error: format!(
-                                            "No balance info exists for the contract {}",
-                                            contract_addr
-                                        ),
-                                        request: msg.as_slice().into(),
-                                    })
+                                    // No balance registered for this token yet
+                                    return SystemResult::Ok(ContractResult::Ok(
+                                        to_binary(&Cw20BalanceResponse {
+                                            balance: Uint128::zero(),
+                                        })
+                                        .unwrap(),
+                                    ));
                                 }
                             };
 
@@ -65,6 +65,10 @@ fn init_env(
 
         hub_contract: "hub".to_string(),
 
+        guardian: None,
+
+        admin: None,
+
         lsd_config: ContractsRaw {
             token: MOCK_SPECTRUM_TOKEN.to_string(),
             hub: "astroport-hub".to_string(),
@@ -109,6 +113,7 @@ fn test_deposit_funds() {
         ExecuteMsg::MintWith {
             recipient: "depositor".to_string(),
             lsd_amount: 1_000_000u128.into(),
+            min_received: None,
         },
     )
     .unwrap();
@@ -128,7 +133,7 @@ fn test_deposit_funds() {
         &query(
             deps.as_ref(),
             mock_env(),
-            cw20_base::msg::QueryMsg::Balance {
+            basset::wrapper::QueryMsg::Balance {
                 address: "depositor".to_string(),
             },
         )
@@ -151,6 +156,7 @@ fn test_deposit_funds_share() {
         ExecuteMsg::MintWith {
             recipient: "depositor".to_string(),
             lsd_amount: 1_000_000u128.into(),
+            min_received: None,
         },
     )
     .unwrap();
@@ -170,7 +176,7 @@ fn test_deposit_funds_share() {
         &query(
             deps.as_ref(),
             mock_env(),
-            cw20_base::msg::QueryMsg::Balance {
+            basset::wrapper::QueryMsg::Balance {
                 address: "depositor".to_string(),
             },
         )
@@ -193,6 +199,7 @@ fn test_decompound_no_limit() {
         ExecuteMsg::MintWith {
             recipient: "depositor".to_string(),
             lsd_amount: 1_000_000u128.into(),
+            min_received: None,
         },
     )
     .unwrap();
@@ -228,14 +235,14 @@ fn test_decompound_no_limit() {
     // OLD value : 1_000_000 of lsd deposited at 1.vs.1 exchange rate
     // NEW value : Now 1 unit of LSD equals 4 units of underlying asset.
     // SO the tokens are worth 4_000_000, only 250_000 tokens are needed to get a 1_000_000 value
-    // 750_000 tokens need to be decompounded. Because we limit the compute errors, 1 is substracted
+    // 750_000 tokens need to be decompounded.
 
     assert_eq!(
         res.messages,
         vec![get_transfer_msg(
             MOCK_SPECTRUM_TOKEN,
             "hub",
-            750_000u128 - 1u128
+            750_000u128
         )]
     );
 
@@ -268,6 +275,7 @@ fn test_decompound_same_time() {
         ExecuteMsg::MintWith {
             recipient: "depositor".to_string(),
             lsd_amount: 1_000_000u128.into(),
+            min_received: None,
         },
     )
     .unwrap();
@@ -305,6 +313,7 @@ fn test_decompound_with_limit() {
         ExecuteMsg::MintWith {
             recipient: "depositor".to_string(),
             lsd_amount: 1_000_000u128.into(),
+            min_received: None,
         },
     )
     .unwrap();
@@ -333,14 +342,13 @@ fn test_decompound_with_limit() {
     // NEW value : Now 1 unit of LSD equals 4 units of underlying asset.
     // BUT we limit the decompounding to 10%/year
     // So only 27 tokens may be decompounded per day
-    // Always the -1 to account for computation errors
 
     assert_eq!(
         res.messages,
         vec![get_transfer_msg(
             MOCK_SPECTRUM_TOKEN,
             "hub",
-            273u128 - 1u128
+            273u128
         )]
     );
 
@@ -363,7 +371,7 @@ fn test_decompound_with_limit() {
         vec![get_transfer_msg(
             MOCK_SPECTRUM_TOKEN,
             "hub",
-            275u128 - 1u128
+            274u128
         )]
     );
 
@@ -386,7 +394,7 @@ fn test_decompound_with_limit() {
         vec![get_transfer_msg(
             MOCK_SPECTRUM_TOKEN,
             "hub",
-            274u128 - 1u128
+            273u128
         )]
     );
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use cw20_base::msg::{ExecuteMsg, QueryMsg};
+use basset::wrapper::{ExecuteMsg, QueryMsg};
 use wrapper_implementations::steak::SteakInitMsg;
 
 use cosmwasm_schema::write_api;
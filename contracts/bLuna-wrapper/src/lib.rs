@@ -8,12 +8,14 @@ use cosmwasm_std::Deps;
 use cosmwasm_std::DepsMut;
 use cosmwasm_std::Env;
 use cosmwasm_std::MessageInfo;
+use cosmwasm_std::Reply;
 use cosmwasm_std::Response;
 use cosmwasm_std::StdResult;
 use cosmwasm_std::Uint128;
 use cosmwasm_std::{entry_point, Coin};
 
-use cw20_base::msg::QueryMsg;
+use basset::wrapper::QueryMsg;
+use cw2::set_contract_version;
 use cw20_base::ContractError;
 use wrapper_implementations::steak;
 
@@ -31,14 +33,20 @@ impl LSDStateResponseTrait for BLunaStateResponse {
     }
 }
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: steak::SteakInitMsg,
 ) -> StdResult<Response> {
-    steak::instantiate::<BLunaStateResponse>(deps, env, info, msg)
+    let res = steak::instantiate::<BLunaStateResponse>(deps.branch(), env, info, msg)?;
+    // cw20-base records its own contract name on instantiation, we replace it with the wrapper's
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -55,3 +63,13 @@ pub fn execute(
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     steak::query::<BLunaStateResponse>(deps, env, msg)
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    steak::reply::<BLunaStateResponse>(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: steak::MigrateMsg) -> Result<Response, ContractError> {
+    steak::migrate::<BLunaStateResponse>(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
+}
@@ -0,0 +1,14 @@
+use cosmwasm_schema::write_api;
+
+use basket_wrapper_token::msg::InstantiateMsg;
+use basket_wrapper_token::msg::MigrateMsg;
+use basset::basket::ExecuteMsg;
+use basset::basket::QueryMsg;
+fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+        execute: ExecuteMsg,
+        migrate: MigrateMsg,
+    };
+}
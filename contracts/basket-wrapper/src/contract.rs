@@ -0,0 +1,286 @@
+use crate::handler::*;
+use crate::member::BasketLSD;
+use crate::msg::{InstantiateMsg, MigrateMsg};
+use crate::querier::query_basket_state;
+use crate::state::{BasketMember, ADMIN, HUB_CONTRACT, MEMBERS, MEMBER_LEDGERS};
+
+use basset::basket::{
+    BasketBurnResponse, BasketConfigResponse, BasketStateResponse, ExecuteMsg, MemberAmount,
+    MemberStateResponse, QueryMsg,
+};
+use basset::migrate::assert_can_migrate;
+use basset::wrapper::SimulationResponse;
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::MinterResponse;
+use cw20_base::allowances::{
+    execute_decrease_allowance, execute_increase_allowance, execute_send_from,
+    execute_transfer_from,
+};
+use cw20_base::contract::query as cw20_query;
+use cw20_base::contract::{
+    execute_send, execute_transfer, execute_update_marketing, execute_upload_logo,
+    instantiate as cw20_init,
+};
+use cw20_base::msg::{InstantiateMsg as Cw20InstantiateMsg, QueryMsg as Cw20QueryMsg};
+use cw20_base::ContractError;
+
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    let members = msg
+        .members
+        .into_iter()
+        .map(|member| {
+            Ok(BasketMember {
+                id: member.id,
+                lsd: BasketLSD::instantiate_config(deps.as_ref(), member.lsd_config)?,
+                min_weight: member.min_weight,
+                max_weight: member.max_weight,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    validate_members(&members)?;
+    MEMBERS.save(deps.storage, &members)?;
+    for member in &members {
+        MEMBER_LEDGERS.save(deps.storage, &member.id, &Uint128::zero())?;
+    }
+
+    HUB_CONTRACT.save(deps.storage, &deps.api.addr_validate(&msg.hub_contract)?)?;
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender.clone(),
+    };
+    ADMIN.save(deps.storage, &admin)?;
+
+    cw20_init(
+        deps.branch(),
+        env.clone(),
+        info,
+        Cw20InstantiateMsg {
+            name: msg.name,
+            symbol: msg.symbol,
+            decimals: msg.decimals,
+            // Every wrapper token has to be backed by the basket
+            initial_balances: vec![],
+            mint: Some(MinterResponse {
+                // Only this contract can mint new tokens in exchange of the basket lsds
+                minter: env.contract.address.to_string(),
+                cap: None,
+            }),
+            marketing: None,
+        },
+    )
+    .map_err(|_| StdError::generic_err("CW20 Token init error"))?;
+
+    // cw20-base records its own contract name on instantiation, we replace it with the basket's
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Transfer { recipient, amount } => {
+            execute_transfer(deps, env, info, recipient, amount)
+        }
+        ExecuteMsg::Burn {
+            amount,
+            member,
+            min_received,
+        } => execute_burn(deps, env, info, amount, member, min_received),
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => execute_send(deps, env, info, contract, amount, msg),
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => execute_increase_allowance(deps, env, info, spender, amount, expires),
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => execute_decrease_allowance(deps, env, info, spender, amount, expires),
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => execute_transfer_from(deps, env, info, owner, recipient, amount),
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => execute_send_from(deps, env, info, owner, contract, amount, msg),
+        ExecuteMsg::BurnFrom {
+            owner,
+            amount,
+            member,
+            min_received,
+        } => execute_burn_from(deps, env, info, owner, amount, member, min_received),
+        ExecuteMsg::MintWith {
+            member,
+            recipient,
+            lsd_amount,
+            min_received,
+        } => execute_mint_with(deps, env, info, member, recipient, lsd_amount, min_received),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::UpdateMarketing {
+            project,
+            description,
+            marketing,
+        } => execute_update_marketing(deps, env, info, project, description, marketing),
+        ExecuteMsg::UploadLogo(logo) => execute_upload_logo(deps, env, info, logo),
+        ExecuteMsg::Decompound { recipient } => execute_decompound(deps, env, info, recipient),
+        ExecuteMsg::UpdateWeights { weights } => execute_update_weights(deps, info, weights),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::BasketState {} => {
+            let members = MEMBERS.load(deps.storage)?;
+            let state = query_basket_state(deps, env, &members)?;
+            to_binary(&BasketStateResponse {
+                members: members
+                    .iter()
+                    .zip(state.members.iter())
+                    .enumerate()
+                    .map(|(index, (member, member_state))| MemberStateResponse {
+                        member: member.id.clone(),
+                        lsd_exchange_rate: member_state.lsd_exchange_rate,
+                        lsd_balance: member_state.lsd_balance,
+                        backing_luna: Decimal::from_ratio(member_state.backing_luna, 1u128),
+                        weight: state.weight(index),
+                        min_weight: member.min_weight,
+                        max_weight: member.max_weight,
+                    })
+                    .collect(),
+                backing_luna: Decimal::from_ratio(state.backing_luna, 1u128),
+                wlsd_supply: state.wlsd_supply,
+                exchange_rate: state.exchange_rate(),
+            })
+        }
+        QueryMsg::SimulateMintWith { member, lsd_amount } => {
+            let members = MEMBERS.load(deps.storage)?;
+            let index = members.iter().position(|m| m.id == member).ok_or_else(|| {
+                StdError::generic_err(format!("Unknown basket member: {}", member))
+            })?;
+            let state = query_basket_state(deps, env, &members)?;
+            to_binary(&SimulationResponse {
+                lsd_amount,
+                wrapper_amount: compute_mint_with_amount(&state, index, lsd_amount)?,
+            })
+        }
+        QueryMsg::SimulateBurn { amount, member } => {
+            let members = MEMBERS.load(deps.storage)?;
+            let state = query_basket_state(deps, env, &members)?;
+            let (payouts, luna_value) =
+                compute_burn_payouts(&members, &state, amount, member.as_deref())
+                    .map_err(|err| StdError::generic_err(err.to_string()))?;
+            to_binary(&BasketBurnResponse {
+                payouts: payouts
+                    .into_iter()
+                    .map(|(index, lsd_amount)| MemberAmount {
+                        member: members[index].id.clone(),
+                        lsd_amount,
+                    })
+                    .collect(),
+                luna_value,
+            })
+        }
+        QueryMsg::Config {} => to_binary(&BasketConfigResponse {
+            admin: ADMIN.load(deps.storage)?.to_string(),
+            hub_contract: HUB_CONTRACT.load(deps.storage)?.to_string(),
+        }),
+        QueryMsg::Balance { address } => cw20_query(deps, env, Cw20QueryMsg::Balance { address }),
+        QueryMsg::TokenInfo {} => cw20_query(deps, env, Cw20QueryMsg::TokenInfo {}),
+        QueryMsg::Minter {} => cw20_query(deps, env, Cw20QueryMsg::Minter {}),
+        QueryMsg::Allowance { owner, spender } => {
+            cw20_query(deps, env, Cw20QueryMsg::Allowance { owner, spender })
+        }
+        QueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => cw20_query(
+            deps,
+            env,
+            Cw20QueryMsg::AllAllowances {
+                owner,
+                start_after,
+                limit,
+            },
+        ),
+        QueryMsg::AllSpenderAllowances {
+            spender,
+            start_after,
+            limit,
+        } => cw20_query(
+            deps,
+            env,
+            Cw20QueryMsg::AllSpenderAllowances {
+                spender,
+                start_after,
+                limit,
+            },
+        ),
+        QueryMsg::AllAccounts { start_after, limit } => {
+            cw20_query(deps, env, Cw20QueryMsg::AllAccounts { start_after, limit })
+        }
+        QueryMsg::MarketingInfo {} => cw20_query(deps, env, Cw20QueryMsg::MarketingInfo {}),
+        QueryMsg::DownloadLogo {} => cw20_query(deps, env, Cw20QueryMsg::DownloadLogo {}),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let previous = get_contract_version(deps.storage)?;
+    assert_can_migrate(
+        &previous.contract,
+        &previous.version,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?;
+
+    // Baskets migrated from a version without ledgers account for the whole balance of their members
+    let members = MEMBERS.load(deps.storage)?;
+    for member in &members {
+        if MEMBER_LEDGERS.may_load(deps.storage, &member.id)?.is_none() {
+            let lsd_balance =
+                member
+                    .lsd
+                    .get_balance(deps.as_ref(), env.clone(), env.contract.address.clone())?;
+            MEMBER_LEDGERS.save(deps.storage, &member.id, &lsd_balance)?;
+        }
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr("previous_contract", previous.contract),
+        attr("previous_version", previous.version),
+        attr("contract_version", CONTRACT_VERSION),
+    ]))
+}
@@ -0,0 +1,474 @@
+use crate::querier::{lsd_to_luna, luna_to_lsd, query_basket_state};
+use crate::state::{
+    decrease_member_ledger, increase_member_ledger, BasketMember, BasketState, ADMIN,
+    HUB_CONTRACT, MEMBERS,
+};
+use basset::basket::{Cw20HookMsg, MemberWeight};
+use basset::math::{multiply_ratio, Rounding};
+use cosmwasm_std::{
+    attr, from_binary, Addr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128,
+};
+use cw20::Cw20ReceiveMsg;
+use cw20_base::allowances::execute_burn_from as cw20_burn_from;
+use cw20_base::contract::{execute_burn as cw20_burn, execute_mint as cw20_mint};
+use cw20_base::ContractError;
+use std::collections::HashSet;
+
+/// Makes sure the caller receives at least `min_received` tokens out of the operation
+fn assert_min_received(
+    received: Uint128,
+    min_received: Option<Uint128>,
+) -> Result<(), ContractError> {
+    if let Some(min_received) = min_received {
+        if received < min_received {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Slippage exceeded: received {}, expected at least {}",
+                received, min_received
+            ))));
+        }
+    }
+    Ok(())
+}
+
+fn assert_admin(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    if ADMIN.load(deps.storage)? != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+/// Makes sure the basket members are unique and their weight bands can all be satisfied at once
+pub fn validate_members(members: &[BasketMember]) -> StdResult<()> {
+    if members.is_empty() {
+        return Err(StdError::generic_err(
+            "The basket needs at least one member",
+        ));
+    }
+
+    let mut ids = HashSet::new();
+    let mut lsds = HashSet::new();
+    for member in members {
+        if !ids.insert(member.id.as_str()) {
+            return Err(StdError::generic_err(format!(
+                "Duplicate basket member: {}",
+                member.id
+            )));
+        }
+        let lsd = member
+            .lsd
+            .cw20_token()
+            .map(|token| token.to_string())
+            .or_else(|| member.lsd.denom().map(|denom| denom.to_string()))
+            .unwrap_or_default();
+        if !lsds.insert(lsd.clone()) {
+            return Err(StdError::generic_err(format!(
+                "Lsd {} is already in the basket",
+                lsd
+            )));
+        }
+        if member.min_weight > member.max_weight || member.max_weight > Decimal::one() {
+            return Err(StdError::generic_err(format!(
+                "Invalid weight band for {}: min {}, max {}",
+                member.id, member.min_weight, member.max_weight
+            )));
+        }
+    }
+
+    let min_weights = members
+        .iter()
+        .fold(Decimal::zero(), |acc, member| acc + member.min_weight);
+    let max_weights = members
+        .iter()
+        .fold(Decimal::zero(), |acc, member| acc + member.max_weight);
+    if min_weights > Decimal::one() || max_weights < Decimal::one() {
+        return Err(StdError::generic_err(
+            "Weight bands can't be satisfied: minimum weights must sum to at most 1 and maximum weights to at least 1",
+        ));
+    }
+
+    Ok(())
+}
+
+fn find_member(members: &[BasketMember], id: &str) -> Result<usize, ContractError> {
+    members
+        .iter()
+        .position(|member| member.id == id)
+        .ok_or_else(|| {
+            ContractError::Std(StdError::generic_err(format!(
+                "Unknown basket member: {}",
+                id
+            )))
+        })
+}
+
+/// Computes the amount of wrapper tokens minted in exchange of `lsd_amount` tokens of the member at `index`
+/// Like single lsd wrappers, a wrapper token is minted for each underlying token deposited, rounded down
+pub fn compute_mint_with_amount(
+    state: &BasketState,
+    index: usize,
+    lsd_amount: Uint128,
+) -> StdResult<Uint128> {
+    lsd_to_luna(
+        lsd_amount,
+        state.members[index].lsd_exchange_rate,
+        Rounding::Down,
+    )
+}
+
+/// Computes the lsd tokens sent back when burning `amount` wrapper tokens, along with the underlying value of the payouts.
+/// If no member is specified, every member pays out its share of the basket so that the weights don't change.
+/// Payouts are rounded down, in favor of the basket
+pub fn compute_burn_payouts(
+    members: &[BasketMember],
+    state: &BasketState,
+    amount: Uint128,
+    member: Option<&str>,
+) -> Result<(Vec<(usize, Uint128)>, Uint128), ContractError> {
+    // If the basket lost value, burners share the loss
+    let backing = state.backing_luna.max(state.wlsd_supply);
+    if backing.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The basket is empty",
+        )));
+    }
+
+    let payouts = match member {
+        None => state
+            .members
+            .iter()
+            .enumerate()
+            .map(|(index, member_state)| {
+                Ok((
+                    index,
+                    multiply_ratio(member_state.lsd_balance, amount, backing, Rounding::Down)?,
+                ))
+            })
+            .collect::<StdResult<Vec<_>>>()?,
+        Some(member) => {
+            let index = find_member(members, member)?;
+            let member_state = &state.members[index];
+            let value = multiply_ratio(amount, state.backing_luna, backing, Rounding::Down)?;
+            let lsd_amount = luna_to_lsd(value, member_state.lsd_exchange_rate, Rounding::Down)?;
+            if lsd_amount > member_state.lsd_balance {
+                return Err(ContractError::Std(StdError::generic_err(format!(
+                    "Not enough {} in the basket",
+                    member
+                ))));
+            }
+
+            // The member can only be burned alone as long as it stays above its minimum weight
+            let paid_value = lsd_to_luna(lsd_amount, member_state.lsd_exchange_rate, Rounding::Up)?;
+            let remaining_backing = state.backing_luna.saturating_sub(paid_value);
+            if !remaining_backing.is_zero() {
+                let new_weight = Decimal::from_ratio(
+                    member_state.backing_luna.saturating_sub(paid_value),
+                    remaining_backing,
+                );
+                if new_weight < members[index].min_weight {
+                    return Err(ContractError::Std(StdError::generic_err(format!(
+                        "Burn would bring {} to {} of the basket, below its minimum weight of {}",
+                        member, new_weight, members[index].min_weight
+                    ))));
+                }
+            }
+            vec![(index, lsd_amount)]
+        }
+    };
+
+    let luna_value = payouts_value(state, &payouts)?;
+
+    Ok((payouts, luna_value))
+}
+
+/// Underlying value of lsd payouts, rounded down
+fn payouts_value(state: &BasketState, payouts: &[(usize, Uint128)]) -> StdResult<Uint128> {
+    payouts
+        .iter()
+        .try_fold(Uint128::zero(), |acc, (index, lsd_amount)| {
+            Ok(acc
+                + lsd_to_luna(
+                    *lsd_amount,
+                    state.members[*index].lsd_exchange_rate,
+                    Rounding::Down,
+                )?)
+        })
+}
+
+/// Takes the payouts out of the member ledgers and sends them to `to`
+fn payout_messages(
+    deps: DepsMut,
+    env: Env,
+    members: &[BasketMember],
+    payouts: Vec<(usize, Uint128)>,
+    to: Addr,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages = vec![];
+    for (index, lsd_amount) in payouts {
+        if !lsd_amount.is_zero() {
+            decrease_member_ledger(deps.storage, &members[index].id, lsd_amount)?;
+            messages.extend(members[index].lsd.send_funds(
+                deps.as_ref(),
+                env.clone(),
+                lsd_amount,
+                to.clone(),
+            )?);
+        }
+    }
+    Ok(messages)
+}
+
+fn _before_burn(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    member: Option<String>,
+    min_received: Option<Uint128>,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let members = MEMBERS.load(deps.storage)?;
+    let state = query_basket_state(deps.as_ref(), env.clone(), &members)?;
+    let (payouts, luna_value) = compute_burn_payouts(&members, &state, amount, member.as_deref())?;
+    assert_min_received(luna_value, min_received)?;
+
+    Ok(payout_messages(deps, env, &members, payouts, info.sender)?)
+}
+
+pub fn execute_burn(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    member: Option<String>,
+    min_received: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let transfer_messages = _before_burn(
+        deps.branch(),
+        env.clone(),
+        info.clone(),
+        amount,
+        member,
+        min_received,
+    )?;
+
+    let res = cw20_burn(deps, env, info, amount)?;
+
+    Ok(res.add_messages(transfer_messages))
+}
+
+pub fn execute_burn_from(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    amount: Uint128,
+    member: Option<String>,
+    min_received: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let transfer_messages = _before_burn(
+        deps.branch(),
+        env.clone(),
+        info.clone(),
+        amount,
+        member,
+        min_received,
+    )?;
+
+    let res = cw20_burn_from(deps, env, info, owner, amount)?;
+
+    Ok(res.add_messages(transfer_messages))
+}
+
+/// Mints wrapper tokens in exchange of `lsd_amount` tokens of the member at `index` and adds them to its ledger.
+/// The state comes from the ledgers, so tokens already transferred along with the deposit don't count before it
+#[allow(clippy::too_many_arguments)]
+fn _mint(
+    mut deps: DepsMut,
+    env: Env,
+    mut info: MessageInfo,
+    members: &[BasketMember],
+    index: usize,
+    recipient: String,
+    lsd_amount: Uint128,
+    min_received: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let state = query_basket_state(deps.as_ref(), env.clone(), members)?;
+
+    let mint_amount = compute_mint_with_amount(&state, index, lsd_amount)?;
+    assert_min_received(mint_amount, min_received)?;
+
+    // The first deposit can't respect any band, after that a member can only be deposited up to its maximum weight
+    if !state.backing_luna.is_zero() {
+        // The deposit is valued like the wrapper tokens it mints
+        let new_weight = Decimal::from_ratio(
+            state.members[index].backing_luna + mint_amount,
+            state.backing_luna + mint_amount,
+        );
+        if new_weight > members[index].max_weight {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Deposit would bring {} to {} of the basket, above its maximum weight of {}",
+                members[index].id, new_weight, members[index].max_weight
+            ))));
+        }
+    }
+
+    increase_member_ledger(deps.storage, &members[index].id, lsd_amount)?;
+
+    info.sender = env.contract.address.clone();
+    let res = cw20_mint(deps.branch(), env, info, recipient, mint_amount)?;
+
+    Ok(res.add_attribute("member", members[index].id.clone()))
+}
+
+pub fn execute_mint_with(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    member: String,
+    recipient: String,
+    lsd_amount: Uint128,
+    min_received: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let members = MEMBERS.load(deps.storage)?;
+    let index = find_member(&members, &member)?;
+    let lsd = &members[index].lsd;
+
+    let messages = lsd.deposit_funds(
+        deps.as_ref(),
+        env.clone(),
+        info.clone(),
+        lsd_amount,
+        info.sender.clone(),
+    )?;
+    let res = _mint(
+        deps,
+        env,
+        info,
+        &members,
+        index,
+        recipient,
+        lsd_amount,
+        min_received,
+    )?;
+
+    Ok(res.add_messages(messages))
+}
+
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // Only the tokens of the basket members can trigger this hook
+    let members = MEMBERS.load(deps.storage)?;
+    let index = members
+        .iter()
+        .position(|member| member.lsd.cw20_token() == Some(info.sender.clone()))
+        .ok_or(ContractError::Unauthorized {})?;
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Mint {
+            recipient,
+            min_received,
+        } => {
+            let recipient = recipient.unwrap_or(cw20_msg.sender);
+            _mint(
+                deps,
+                env,
+                info,
+                &members,
+                index,
+                recipient,
+                cw20_msg.amount,
+                min_received,
+            )
+        }
+    }
+}
+
+/// Computes the lsd tokens backing more than 1 underlying token per wrapper token.
+/// They are taken pro-rata from all the members so that the weights don't change.
+/// Each member keeps its share of the supply rounded up, so that the rewards never eat into the backing
+pub fn compute_accrued_rewards(state: &BasketState) -> StdResult<(Vec<(usize, Uint128)>, Uint128)> {
+    // If the exchange rate is lower than 1, we have just had a slashing event or something else
+    // There is nothing to decompound
+    if state.backing_luna <= state.wlsd_supply {
+        return Ok((vec![], Uint128::zero()));
+    }
+
+    let rewards = state
+        .members
+        .iter()
+        .enumerate()
+        .map(|(index, member_state)| {
+            let kept = multiply_ratio(
+                member_state.lsd_balance,
+                state.wlsd_supply,
+                state.backing_luna,
+                Rounding::Up,
+            )?;
+            Ok((index, member_state.lsd_balance - kept))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let luna_rewards = payouts_value(state, &rewards)?;
+
+    Ok((rewards, luna_rewards))
+}
+
+pub fn execute_decompound(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let hub_contract = HUB_CONTRACT.load(deps.storage)?;
+    if info.sender != hub_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient = recipient
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?
+        .unwrap_or(info.sender);
+
+    let members = MEMBERS.load(deps.storage)?;
+    let state = query_basket_state(deps.as_ref(), env.clone(), &members)?;
+    let (rewards, luna_rewards) = compute_accrued_rewards(&state)?;
+    let messages = payout_messages(deps, env, &members, rewards, recipient)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "execute_decompound"),
+            attr("total_luna_rewards", luna_rewards.to_string()),
+        ])
+        .add_messages(messages))
+}
+
+pub fn execute_update_weights(
+    deps: DepsMut,
+    info: MessageInfo,
+    weights: Vec<MemberWeight>,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut members = MEMBERS.load(deps.storage)?;
+    let updated = weights
+        .iter()
+        .map(|weight| weight.member.as_str())
+        .collect::<HashSet<_>>();
+    if weights.len() != members.len() || updated.len() != members.len() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The weights of all the basket members need to be specified once",
+        )));
+    }
+    for weight in &weights {
+        let index = find_member(&members, &weight.member)?;
+        members[index].min_weight = weight.min_weight;
+        members[index].max_weight = weight.max_weight;
+    }
+    validate_members(&members)?;
+    MEMBERS.save(deps.storage, &members)?;
+
+    Ok(Response::new().add_attribute("action", "update_weights"))
+}
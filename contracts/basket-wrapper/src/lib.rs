@@ -0,0 +1,10 @@
+pub mod contract;
+pub mod member;
+pub mod msg;
+pub mod state;
+
+mod handler;
+mod querier;
+
+#[cfg(test)]
+mod testing;
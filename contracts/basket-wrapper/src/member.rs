@@ -0,0 +1,104 @@
+use basset::external::LSDStateResponseTrait;
+use cavern_lsd_wrapper_token::trait_def::LSDHub;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Deps, Env, MessageInfo, StdResult, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use wrapper_implementations::coin::{StrideLSDConfig, StrideLSDConfigRaw};
+use wrapper_implementations::steak::{LsdContractsRaw, SteakLSDHub};
+
+/// Steak based hubs (bLuna, ampLuna...) don't all return the same state
+/// The basket only needs their exchange rate, so the other fields are ignored
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SteakExchangeRate {
+    pub exchange_rate: Decimal,
+}
+
+impl LSDStateResponseTrait for SteakExchangeRate {
+    fn exchange_rate(&self) -> Decimal {
+        self.exchange_rate
+    }
+}
+
+/// Lsd config of a basket member, in the same format as the one of the corresponding single lsd wrapper
+#[cw_serde]
+pub enum BasketLSDRaw {
+    Steak(LsdContractsRaw),
+    Stride(StrideLSDConfigRaw),
+}
+
+/// Each basket member is priced and moved around by the adapter of its lsd
+#[cw_serde]
+pub enum BasketLSD {
+    Steak(SteakLSDHub<SteakExchangeRate>),
+    Stride(StrideLSDConfig),
+}
+
+impl BasketLSD {
+    pub fn instantiate_config(deps: Deps, config: BasketLSDRaw) -> StdResult<Self> {
+        Ok(match config {
+            BasketLSDRaw::Steak(config) => {
+                BasketLSD::Steak(SteakLSDHub::instantiate_config(deps, config)?)
+            }
+            BasketLSDRaw::Stride(config) => {
+                BasketLSD::Stride(StrideLSDConfig::instantiate_config(deps, config)?)
+            }
+        })
+    }
+
+    pub fn query_exchange_rate(&self, deps: Deps, env: Env) -> StdResult<Decimal> {
+        match self {
+            BasketLSD::Steak(hub) => hub.query_exchange_rate(deps, env),
+            BasketLSD::Stride(hub) => hub.query_exchange_rate(deps, env),
+        }
+    }
+
+    pub fn get_balance(&self, deps: Deps, env: Env, address: Addr) -> StdResult<Uint128> {
+        match self {
+            BasketLSD::Steak(hub) => hub.get_balance(deps, env, address),
+            BasketLSD::Stride(hub) => hub.get_balance(deps, env, address),
+        }
+    }
+
+    pub fn cw20_token(&self) -> Option<Addr> {
+        match self {
+            BasketLSD::Steak(hub) => hub.cw20_token(),
+            BasketLSD::Stride(hub) => hub.cw20_token(),
+        }
+    }
+
+    /// Native denom of the lsd, if the lsd is a native token
+    pub fn denom(&self) -> Option<&str> {
+        match self {
+            BasketLSD::Steak(_) => None,
+            BasketLSD::Stride(hub) => Some(&hub.denom),
+        }
+    }
+
+    pub fn deposit_funds(
+        &self,
+        deps: Deps,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+        from: Addr,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        match self {
+            BasketLSD::Steak(hub) => hub.deposit_funds(deps, env, info, amount, from),
+            BasketLSD::Stride(hub) => hub.deposit_funds(deps, env, info, amount, from),
+        }
+    }
+
+    pub fn send_funds(
+        &self,
+        deps: Deps,
+        env: Env,
+        amount: Uint128,
+        to: Addr,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        match self {
+            BasketLSD::Steak(hub) => hub.send_funds(deps, env, amount, to),
+            BasketLSD::Stride(hub) => hub.send_funds(deps, env, amount, to),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use crate::member::BasketLSDRaw;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Decimal;
+
+#[cw_serde]
+pub struct MemberInitMsg {
+    pub id: String,
+    pub lsd_config: BasketLSDRaw,
+    pub min_weight: Decimal,
+    pub max_weight: Decimal,
+}
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+
+    // only hub contract can call decompound
+    pub hub_contract: String,
+
+    // can update the weight bands, defaults to the instantiator
+    pub admin: Option<String>,
+
+    pub members: Vec<MemberInitMsg>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
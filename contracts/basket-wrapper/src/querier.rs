@@ -0,0 +1,61 @@
+use crate::state::{BasketMember, BasketState, MemberState, MEMBER_LEDGERS};
+use basset::math::{multiply_ratio, Rounding};
+use cosmwasm_std::{Decimal, Deps, Env, StdResult, Uint128};
+use cw20_base::state::TOKEN_INFO;
+
+/// Values the ledger of every basket member with the exchange rate of its own adapter.
+/// The backing is rounded down, so that it never counts more than the basket holds
+pub fn query_basket_state(
+    deps: Deps,
+    env: Env,
+    members: &[BasketMember],
+) -> StdResult<BasketState> {
+    let members = members
+        .iter()
+        .map(|member| {
+            let lsd_exchange_rate = member.lsd.query_exchange_rate(deps, env.clone())?;
+            let lsd_balance = MEMBER_LEDGERS.load(deps.storage, &member.id)?;
+            Ok(MemberState {
+                lsd_exchange_rate,
+                lsd_balance,
+                backing_luna: lsd_to_luna(lsd_balance, lsd_exchange_rate, Rounding::Down)?,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BasketState {
+        backing_luna: members
+            .iter()
+            .fold(Uint128::zero(), |acc, member| acc + member.backing_luna),
+        members,
+        wlsd_supply: TOKEN_INFO.load(deps.storage)?.total_supply,
+    })
+}
+
+/// Underlying value of `lsd_amount` tokens at `lsd_exchange_rate`
+pub fn lsd_to_luna(
+    lsd_amount: Uint128,
+    lsd_exchange_rate: Decimal,
+    rounding: Rounding,
+) -> StdResult<Uint128> {
+    multiply_ratio(
+        lsd_amount,
+        lsd_exchange_rate.atomics(),
+        Decimal::one().atomics(),
+        rounding,
+    )
+}
+
+/// Amount of lsd tokens worth `luna_amount` underlying tokens at `lsd_exchange_rate`
+pub fn luna_to_lsd(
+    luna_amount: Uint128,
+    lsd_exchange_rate: Decimal,
+    rounding: Rounding,
+) -> StdResult<Uint128> {
+    multiply_ratio(
+        luna_amount,
+        Decimal::one().atomics(),
+        lsd_exchange_rate.atomics(),
+        rounding,
+    )
+}
@@ -0,0 +1,75 @@
+use crate::member::BasketLSD;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+
+pub const HUB_CONTRACT: Item<Addr> = Item::new("hub_contract");
+// The admin can update the weight bands of the basket members
+pub const ADMIN: Item<Addr> = Item::new("admin");
+pub const MEMBERS: Item<Vec<BasketMember>> = Item::new("members");
+// Lsd tokens of each member backing the wrapper tokens, by member id
+// Tokens sent to the basket outside of mints are not counted
+pub const MEMBER_LEDGERS: Map<&str, Uint128> = Map::new("member_ledgers");
+
+#[cw_serde]
+pub struct BasketMember {
+    pub id: String,
+    pub lsd: BasketLSD,
+    // Bands of the share of the basket backing held in this member
+    pub min_weight: Decimal,
+    pub max_weight: Decimal,
+}
+
+#[cw_serde]
+pub struct MemberState {
+    pub lsd_exchange_rate: Decimal,
+    pub lsd_balance: Uint128,
+    pub backing_luna: Uint128,
+}
+
+#[cw_serde]
+pub struct BasketState {
+    // In the same order as the stored members
+    pub members: Vec<MemberState>,
+    pub backing_luna: Uint128,
+    pub wlsd_supply: Uint128,
+}
+
+impl BasketState {
+    pub fn exchange_rate(&self) -> Decimal {
+        if self.wlsd_supply.is_zero() {
+            Decimal::one()
+        } else {
+            Decimal::from_ratio(self.backing_luna, self.wlsd_supply)
+        }
+    }
+
+    /// Share of the basket backing held in the member at `index`
+    pub fn weight(&self, index: usize) -> Decimal {
+        if self.backing_luna.is_zero() {
+            Decimal::zero()
+        } else {
+            Decimal::from_ratio(self.members[index].backing_luna, self.backing_luna)
+        }
+    }
+}
+
+pub fn increase_member_ledger(
+    storage: &mut dyn Storage,
+    member: &str,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    MEMBER_LEDGERS.update(storage, member, |ledger| -> StdResult<_> {
+        Ok(ledger.unwrap_or_default().checked_add(amount)?)
+    })
+}
+
+pub fn decrease_member_ledger(
+    storage: &mut dyn Storage,
+    member: &str,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    MEMBER_LEDGERS.update(storage, member, |ledger| -> StdResult<_> {
+        Ok(ledger.unwrap_or_default().checked_sub(amount)?)
+    })
+}
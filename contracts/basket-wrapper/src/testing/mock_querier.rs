@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Coin, ContractResult, Decimal, Decimal256, Empty,
+    OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg};
+
+use basset::external::{LSDQueryMsg, LSDStateResponse};
+use basset::oracle::PriceResponse;
+use basset::oracle::QueryMsg as OracleQueryMsg;
+
+pub const MOCK_HUB_CONTRACT_ADDR: &str = "hub";
+
+pub const MOCK_BLUNA_HUB_CONTRACT_ADDR: &str = "bluna_hub";
+pub const MOCK_BLUNA_TOKEN_CONTRACT_ADDR: &str = "bluna_token";
+pub const MOCK_AMPLUNA_HUB_CONTRACT_ADDR: &str = "ampluna_hub";
+pub const MOCK_AMPLUNA_TOKEN_CONTRACT_ADDR: &str = "ampluna_token";
+
+pub const MOCK_STLUNA_DENOM: &str = "stuluna";
+pub const MOCK_UNDERLYING_DENOM: &str = "uluna";
+pub const MOCK_ORACLE_CONTRACT_ADDR: &str = "oracle";
+
+pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: WasmMockQuerier::new(MockQuerier::new(&[])),
+        custom_query_type: PhantomData,
+    }
+}
+
+/// ampLuna hubs return more than the steak state
+#[derive(serde::Serialize)]
+struct AmpLunaStateResponse {
+    total_ustake: Uint128,
+    total_uluna: Uint128,
+    exchange_rate: Decimal,
+    unlocked_coins: Vec<Coin>,
+    unbonding: Uint128,
+    available: Uint128,
+    tvl_uluna: Uint128,
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    // exchange rates of the steak based hubs
+    lsd_exchange_rates: HashMap<String, Decimal>,
+    oracle_price: Option<Decimal256>,
+    // cw20 balances of the basket
+    token_balances: HashMap<String, Uint128>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                if let Some(exchange_rate) = self.lsd_exchange_rates.get(contract_addr) {
+                    let LSDQueryMsg::State {} = from_binary(msg).unwrap();
+                    let state_response = if contract_addr == MOCK_AMPLUNA_HUB_CONTRACT_ADDR {
+                        to_binary(&AmpLunaStateResponse {
+                            total_ustake: Uint128::zero(),
+                            total_uluna: Uint128::zero(),
+                            exchange_rate: *exchange_rate,
+                            unlocked_coins: vec![],
+                            unbonding: Uint128::zero(),
+                            available: Uint128::zero(),
+                            tvl_uluna: Uint128::zero(),
+                        })
+                    } else {
+                        to_binary(&LSDStateResponse {
+                            total_usteak: Uint128::zero(),
+                            total_uluna: Uint128::zero(),
+                            exchange_rate: *exchange_rate,
+                            unlocked_coins: vec![],
+                        })
+                    };
+                    SystemResult::Ok(ContractResult::from(state_response))
+                } else if contract_addr == MOCK_ORACLE_CONTRACT_ADDR {
+                    match from_binary(msg).unwrap() {
+                        OracleQueryMsg::Price { base, quote } => {
+                            if base != MOCK_STLUNA_DENOM || quote != MOCK_UNDERLYING_DENOM {
+                                return SystemResult::Err(SystemError::InvalidRequest {
+                                    error: "interpreting tokens".to_string(),
+                                    request: msg.clone(),
+                                });
+                            }
+                            SystemResult::Ok(ContractResult::from(to_binary(&PriceResponse {
+                                rate: self.oracle_price.unwrap(),
+                                last_updated_base: 0,
+                                last_updated_quote: 0,
+                            })))
+                        }
+                        _ => unimplemented!(),
+                    }
+                } else {
+                    match from_binary(msg).unwrap() {
+                        Cw20QueryMsg::Balance { address } => {
+                            assert_eq!(address, MOCK_CONTRACT_ADDR);
+                            let balance = self
+                                .token_balances
+                                .get(contract_addr)
+                                .cloned()
+                                .unwrap_or_default();
+                            SystemResult::Ok(ContractResult::from(to_binary(&BalanceResponse {
+                                balance,
+                            })))
+                        }
+                        _ => unimplemented!(),
+                    }
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            lsd_exchange_rates: HashMap::new(),
+            oracle_price: None,
+            token_balances: HashMap::new(),
+        }
+    }
+
+    // configure the exchange rate of a steak based hub
+    pub fn with_lsd_exchange_rate(&mut self, hub: &str, exchange_rate: Decimal) {
+        self.lsd_exchange_rates
+            .insert(hub.to_string(), exchange_rate);
+    }
+
+    // configure the stride oracle price
+    pub fn with_oracle_price(&mut self, price: Decimal256) {
+        self.oracle_price = Some(price);
+    }
+
+    // configure the cw20 balance of the basket
+    pub fn with_token_balance(&mut self, token: &str, balance: Uint128) {
+        self.token_balances.insert(token.to_string(), balance);
+    }
+
+    // configure the native balance of the basket
+    pub fn with_native_balance(&mut self, balance: &[Coin]) {
+        self.base
+            .update_balance(MOCK_CONTRACT_ADDR, balance.to_vec());
+    }
+}
@@ -0,0 +1,730 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, Decimal256, OwnedDeps,
+    StdError, SubMsg, Uint128, Uint256, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, TokenInfoResponse};
+use cw20_base::ContractError;
+
+use basset::basket::{
+    BasketBurnResponse, BasketStateResponse, Cw20HookMsg, ExecuteMsg, MemberAmount, MemberWeight,
+    QueryMsg,
+};
+use basset::wrapper::SimulationResponse;
+use wrapper_implementations::coin::StrideLSDConfigRaw;
+use wrapper_implementations::steak::LsdContractsRaw;
+
+use crate::contract::{execute, instantiate, query};
+use crate::member::BasketLSDRaw;
+use crate::msg::{InstantiateMsg, MemberInitMsg};
+
+use super::mock_querier::{
+    mock_dependencies, WasmMockQuerier, MOCK_AMPLUNA_HUB_CONTRACT_ADDR,
+    MOCK_AMPLUNA_TOKEN_CONTRACT_ADDR, MOCK_BLUNA_HUB_CONTRACT_ADDR, MOCK_BLUNA_TOKEN_CONTRACT_ADDR,
+    MOCK_HUB_CONTRACT_ADDR, MOCK_ORACLE_CONTRACT_ADDR, MOCK_STLUNA_DENOM, MOCK_UNDERLYING_DENOM,
+};
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, WasmMockQuerier>;
+
+fn member_init(id: &str, lsd_config: BasketLSDRaw) -> MemberInitMsg {
+    MemberInitMsg {
+        id: id.to_string(),
+        lsd_config,
+        min_weight: Decimal::percent(20),
+        max_weight: Decimal::percent(50),
+    }
+}
+
+fn init_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        name: "basket luna".to_string(),
+        symbol: "BLUNA".to_string(),
+        decimals: 6,
+        hub_contract: MOCK_HUB_CONTRACT_ADDR.to_string(),
+        admin: None,
+        members: vec![
+            member_init(
+                "bluna",
+                BasketLSDRaw::Steak(LsdContractsRaw {
+                    hub: MOCK_BLUNA_HUB_CONTRACT_ADDR.to_string(),
+                    token: MOCK_BLUNA_TOKEN_CONTRACT_ADDR.to_string(),
+                }),
+            ),
+            member_init(
+                "ampluna",
+                BasketLSDRaw::Steak(LsdContractsRaw {
+                    hub: MOCK_AMPLUNA_HUB_CONTRACT_ADDR.to_string(),
+                    token: MOCK_AMPLUNA_TOKEN_CONTRACT_ADDR.to_string(),
+                }),
+            ),
+            member_init(
+                "stluna",
+                BasketLSDRaw::Stride(StrideLSDConfigRaw {
+                    denom: MOCK_STLUNA_DENOM.to_string(),
+                    underlying_token_denom: MOCK_UNDERLYING_DENOM.to_string(),
+                    oracle_contract: MOCK_ORACLE_CONTRACT_ADDR.to_string(),
+                    swap_router: None,
+                    max_spread: None,
+                }),
+            ),
+        ],
+    }
+}
+
+fn init_basket() -> MockDeps {
+    let mut deps = mock_dependencies();
+    deps.querier
+        .with_lsd_exchange_rate(MOCK_BLUNA_HUB_CONTRACT_ADDR, Decimal::one());
+    deps.querier
+        .with_lsd_exchange_rate(MOCK_AMPLUNA_HUB_CONTRACT_ADDR, Decimal::percent(120));
+    deps.querier.with_oracle_price(Decimal256::percent(150));
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        init_msg(),
+    )
+    .unwrap();
+    deps
+}
+
+fn receive_msg(sender: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::Mint {
+            recipient: None,
+            min_received: None,
+        })
+        .unwrap(),
+    })
+}
+
+/// Deposits 1000 bLuna, 500 ampLuna and 400 stLuna, worth 2200 Luna
+fn fill_basket(deps: &mut MockDeps) {
+    deps.querier
+        .with_token_balance(MOCK_BLUNA_TOKEN_CONTRACT_ADDR, Uint128::from(1000u128));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_BLUNA_TOKEN_CONTRACT_ADDR, &[]),
+        receive_msg("addr0000", 1000),
+    )
+    .unwrap();
+
+    deps.querier
+        .with_token_balance(MOCK_AMPLUNA_TOKEN_CONTRACT_ADDR, Uint128::from(500u128));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_AMPLUNA_TOKEN_CONTRACT_ADDR, &[]),
+        receive_msg("addr0000", 500),
+    )
+    .unwrap();
+
+    let funds = coins(400, MOCK_STLUNA_DENOM);
+    deps.querier.with_native_balance(&funds);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &funds),
+        ExecuteMsg::MintWith {
+            member: "stluna".to_string(),
+            recipient: "addr0000".to_string(),
+            lsd_amount: Uint128::from(400u128),
+            min_received: None,
+        },
+    )
+    .unwrap();
+}
+
+fn basket_state(deps: &MockDeps) -> BasketStateResponse {
+    from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::BasketState {}).unwrap()).unwrap()
+}
+
+fn cw20_transfer(token: &str, recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+fn bank_send(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: vec![Coin {
+            denom: MOCK_STLUNA_DENOM.to_string(),
+            amount: Uint128::from(amount),
+        }],
+    }))
+}
+
+#[test]
+fn invalid_members() {
+    let mut deps = mock_dependencies();
+
+    let mut msg = init_msg();
+    msg.members[1].id = "bluna".to_string();
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(err, StdError::generic_err("Duplicate basket member: bluna"));
+
+    let mut msg = init_msg();
+    msg.members[0].min_weight = Decimal::percent(60);
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Invalid weight band for bluna: min 0.6, max 0.5")
+    );
+
+    let mut msg = init_msg();
+    for member in msg.members.iter_mut() {
+        member.max_weight = Decimal::percent(30);
+    }
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Weight bands can't be satisfied: minimum weights must sum to at most 1 and maximum weights to at least 1")
+    );
+
+    let mut msg = init_msg();
+    msg.members = vec![];
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("The basket needs at least one member")
+    );
+}
+
+#[test]
+fn mint_with_members() {
+    let mut deps = init_basket();
+    fill_basket(&mut deps);
+
+    // Each member is priced with its own exchange rate
+    let state = basket_state(&deps);
+    assert_eq!(state.wlsd_supply, Uint128::from(2200u128));
+    assert_eq!(state.backing_luna, Decimal::from_ratio(2200u128, 1u128));
+    assert_eq!(state.exchange_rate, Decimal::one());
+    assert_eq!(
+        state
+            .members
+            .iter()
+            .map(|member| (member.member.as_str(), member.backing_luna))
+            .collect::<Vec<_>>(),
+        vec![
+            ("bluna", Decimal::from_ratio(1000u128, 1u128)),
+            ("ampluna", Decimal::from_ratio(600u128, 1u128)),
+            ("stluna", Decimal::from_ratio(600u128, 1u128)),
+        ]
+    );
+    assert_eq!(
+        state.members[0].weight,
+        Decimal::from_ratio(1000u128, 2200u128)
+    );
+
+    let simulation: SimulationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateMintWith {
+                member: "ampluna".to_string(),
+                lsd_amount: Uint128::from(100u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        simulation,
+        SimulationResponse {
+            lsd_amount: Uint128::from(100u128),
+            wrapper_amount: Uint128::from(120u128),
+        }
+    );
+
+    // bLuna would go above its maximum weight of 50%
+    let mint_msg = ExecuteMsg::MintWith {
+        member: "bluna".to_string(),
+        recipient: "addr0000".to_string(),
+        lsd_amount: Uint128::from(500u128),
+        min_received: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        mint_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Deposit would bring bluna to 0.555555555555555555 of the basket, above its maximum weight of 0.5"
+        ))
+    );
+
+    // Within its band, bLuna is pulled from the allowance of the sender
+    let mint_msg = ExecuteMsg::MintWith {
+        member: "bluna".to_string(),
+        recipient: "addr0001".to_string(),
+        lsd_amount: Uint128::from(100u128),
+        min_received: Some(Uint128::from(100u128)),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        mint_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_BLUNA_TOKEN_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "addr0000".to_string(),
+                recipient: "cosmos2contract".to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // Native members need the funds along with the message
+    let mint_msg = ExecuteMsg::MintWith {
+        member: "stluna".to_string(),
+        recipient: "addr0000".to_string(),
+        lsd_amount: Uint128::from(100u128),
+        min_received: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(10, MOCK_STLUNA_DENOM)),
+        mint_msg,
+    )
+    .unwrap_err();
+
+    // Only the tokens of the members can mint through the cw20 hook
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("other_token", &[]),
+        receive_msg("addr0000", 100),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let token_info: TokenInfoResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+    assert_eq!(token_info.total_supply, Uint128::from(2300u128));
+}
+
+#[test]
+fn burn_pro_rata_and_single_member() {
+    let mut deps = init_basket();
+    fill_basket(&mut deps);
+    // bLuna accrued 10% of rewards, the basket is now worth 2300 Luna
+    deps.querier
+        .with_lsd_exchange_rate(MOCK_BLUNA_HUB_CONTRACT_ADDR, Decimal::percent(110));
+
+    let simulation: BasketBurnResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateBurn {
+                amount: Uint128::from(230u128),
+                member: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        simulation,
+        BasketBurnResponse {
+            payouts: vec![
+                MemberAmount {
+                    member: "bluna".to_string(),
+                    lsd_amount: Uint128::from(100u128),
+                },
+                MemberAmount {
+                    member: "ampluna".to_string(),
+                    lsd_amount: Uint128::from(50u128),
+                },
+                MemberAmount {
+                    member: "stluna".to_string(),
+                    lsd_amount: Uint128::from(40u128),
+                },
+            ],
+            luna_value: Uint128::from(230u128),
+        }
+    );
+
+    let burn_msg = ExecuteMsg::Burn {
+        amount: Uint128::from(230u128),
+        member: None,
+        min_received: Some(Uint128::from(231u128)),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        burn_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Slippage exceeded: received 230, expected at least 231"
+        ))
+    );
+
+    let burn_msg = ExecuteMsg::Burn {
+        amount: Uint128::from(230u128),
+        member: None,
+        min_received: Some(Uint128::from(230u128)),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        burn_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            cw20_transfer(MOCK_BLUNA_TOKEN_CONTRACT_ADDR, "addr0000", 100),
+            cw20_transfer(MOCK_AMPLUNA_TOKEN_CONTRACT_ADDR, "addr0000", 50),
+            bank_send("addr0000", 40),
+        ]
+    );
+
+    // The ledgers were updated by the burn, the supply is now 1970 for 2070 Luna
+    // A burn in a single member pays out 1 Luna per wrapper token
+    let burn_msg = ExecuteMsg::Burn {
+        amount: Uint128::from(120u128),
+        member: Some("ampluna".to_string()),
+        min_received: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        burn_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![cw20_transfer(
+            MOCK_AMPLUNA_TOKEN_CONTRACT_ADDR,
+            "addr0000",
+            100
+        )]
+    );
+
+    // ampLuna would go below its minimum weight of 20%
+    let burn_msg = ExecuteMsg::Burn {
+        amount: Uint128::from(150u128),
+        member: Some("ampluna".to_string()),
+        min_received: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        burn_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Burn would bring ampluna to 0.15 of the basket, below its minimum weight of 0.2"
+        ))
+    );
+
+    let burn_msg = ExecuteMsg::Burn {
+        amount: Uint128::from(100u128),
+        member: Some("unknown".to_string()),
+        min_received: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        burn_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Unknown basket member: unknown"))
+    );
+}
+
+#[test]
+fn burn_after_slashing() {
+    let mut deps = init_basket();
+    fill_basket(&mut deps);
+    // bLuna got slashed, the basket is only worth 2000 Luna for 2200 wrapper tokens
+    deps.querier
+        .with_lsd_exchange_rate(MOCK_BLUNA_HUB_CONTRACT_ADDR, Decimal::percent(80));
+
+    // There are no rewards to decompound
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_HUB_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+
+    let burn_msg = ExecuteMsg::Burn {
+        amount: Uint128::from(220u128),
+        member: Some("bluna".to_string()),
+        min_received: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        burn_msg,
+    )
+    .unwrap();
+    // 220 wrapper tokens are worth 200 Luna, paid with 250 bLuna
+    assert_eq!(
+        res.messages,
+        vec![cw20_transfer(
+            MOCK_BLUNA_TOKEN_CONTRACT_ADDR,
+            "addr0000",
+            250
+        )]
+    );
+}
+
+#[test]
+fn decompound_basket() {
+    let mut deps = init_basket();
+    fill_basket(&mut deps);
+    deps.querier
+        .with_lsd_exchange_rate(MOCK_BLUNA_HUB_CONTRACT_ADDR, Decimal::percent(110));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The 100 Luna of rewards are taken pro-rata from all the members
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_HUB_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Decompound {
+            recipient: Some("reward".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            cw20_transfer(MOCK_BLUNA_TOKEN_CONTRACT_ADDR, "reward", 43),
+            cw20_transfer(MOCK_AMPLUNA_TOKEN_CONTRACT_ADDR, "reward", 21),
+            bank_send("reward", 17),
+        ]
+    );
+    assert_eq!(res.attributes[1].value, "97");
+
+    // Each member keeps its share of the supply, rounded up
+    let state = basket_state(&deps);
+    assert_eq!(
+        state
+            .members
+            .iter()
+            .map(|member| member.lsd_balance.u128())
+            .collect::<Vec<_>>(),
+        vec![957, 479, 383]
+    );
+    assert_eq!(state.wlsd_supply, Uint128::from(2200u128));
+    assert!(state.backing_luna >= Decimal::from_ratio(state.wlsd_supply, 1u128));
+
+    // Tokens sent to the basket outside of mints don't back the wrapper tokens
+    deps.querier
+        .with_token_balance(MOCK_BLUNA_TOKEN_CONTRACT_ADDR, Uint128::from(5000u128));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_HUB_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(basket_state(&deps).members[0].lsd_balance, Uint128::from(957u128));
+}
+
+#[test]
+fn backing_covers_supply_after_random_operations() {
+    let mut deps = init_basket();
+    fill_basket(&mut deps);
+    let addr = "addr0000";
+
+    // Deterministic pseudo-random sequence, so that failures can be reproduced
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    let mut next = |max: u128| -> u128 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed as u128 % max
+    };
+
+    let members = ["bluna", "ampluna", "stluna"];
+    let mut bluna_rate = Decimal::one();
+    let mut ampluna_rate = Decimal::percent(120);
+    for _ in 0..500 {
+        let (sender, funds, msg) = match next(5) {
+            0 => {
+                let (token, amount) = if next(2) == 0 {
+                    (MOCK_BLUNA_TOKEN_CONTRACT_ADDR, next(1_000_000) + 1)
+                } else {
+                    (MOCK_AMPLUNA_TOKEN_CONTRACT_ADDR, next(1_000_000) + 1)
+                };
+                (token, vec![], receive_msg(addr, amount))
+            }
+            1 => {
+                let funds = coins(next(1_000_000) + 1, MOCK_STLUNA_DENOM);
+                let msg = ExecuteMsg::MintWith {
+                    member: "stluna".to_string(),
+                    recipient: addr.to_string(),
+                    lsd_amount: funds[0].amount,
+                    min_received: None,
+                };
+                (addr, funds, msg)
+            }
+            2 => {
+                let balance: cw20::BalanceResponse = from_binary(
+                    &query(
+                        deps.as_ref(),
+                        mock_env(),
+                        QueryMsg::Balance {
+                            address: addr.to_string(),
+                        },
+                    )
+                    .unwrap(),
+                )
+                .unwrap();
+                if balance.balance.is_zero() {
+                    continue;
+                }
+                let member = match next(4) as usize {
+                    3 => None,
+                    index => Some(members[index].to_string()),
+                };
+                let msg = ExecuteMsg::Burn {
+                    amount: Uint128::new(next(balance.balance.u128() / 10 + 1) + 1),
+                    member,
+                    min_received: None,
+                };
+                (addr, vec![], msg)
+            }
+            _ => {
+                // The lsds only accrue rewards
+                bluna_rate += Decimal::from_ratio(next(1_000_000), 1_000_000_000_000u128);
+                ampluna_rate += Decimal::from_ratio(next(1_000_000), 1_000_000_000_000u128);
+                deps.querier
+                    .with_lsd_exchange_rate(MOCK_BLUNA_HUB_CONTRACT_ADDR, bluna_rate);
+                deps.querier
+                    .with_lsd_exchange_rate(MOCK_AMPLUNA_HUB_CONTRACT_ADDR, ampluna_rate);
+                let msg = ExecuteMsg::Decompound { recipient: None };
+                (MOCK_HUB_CONTRACT_ADDR, vec![], msg)
+            }
+        };
+        // Operations breaking the weight bands are rejected before changing any state
+        let _ = execute(deps.as_mut(), mock_env(), mock_info(sender, &funds), msg);
+
+        let state = basket_state(&deps);
+        let backing = state.members.iter().fold(Uint256::zero(), |acc, member| {
+            acc + Uint256::from(member.lsd_balance) * Uint256::from(member.lsd_exchange_rate.atomics())
+        });
+        assert!(
+            backing >= Uint256::from(state.wlsd_supply) * Uint256::from(Decimal::one().atomics()),
+            "{:?} don't back {} wrapper tokens",
+            state.members,
+            state.wlsd_supply
+        );
+    }
+}
+
+#[test]
+fn update_weights() {
+    let mut deps = init_basket();
+
+    let weights = vec![
+        MemberWeight {
+            member: "bluna".to_string(),
+            min_weight: Decimal::percent(10),
+            max_weight: Decimal::percent(80),
+        },
+        MemberWeight {
+            member: "ampluna".to_string(),
+            min_weight: Decimal::percent(10),
+            max_weight: Decimal::percent(50),
+        },
+        MemberWeight {
+            member: "stluna".to_string(),
+            min_weight: Decimal::zero(),
+            max_weight: Decimal::percent(30),
+        },
+    ];
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateWeights {
+            weights: weights.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let mut duplicated = weights.clone();
+    duplicated[2].member = "bluna".to_string();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateWeights {
+            weights: duplicated,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The weights of all the basket members need to be specified once"
+        ))
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateWeights { weights },
+    )
+    .unwrap();
+
+    let state = basket_state(&deps);
+    assert_eq!(state.members[0].max_weight, Decimal::percent(80));
+    assert_eq!(state.members[2].min_weight, Decimal::zero());
+}
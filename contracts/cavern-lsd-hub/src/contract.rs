@@ -13,10 +13,15 @@ use crate::state::{CONFIG, PARAMETERS, STATE};
 use basset::hub::{
     Config, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, State, StateResponse,
 };
+use basset::migrate::assert_can_migrate;
 use basset::reward::ExecuteMsg::SwapToRewardDenom;
+use cw2::{set_contract_version, CONTRACT};
 
 use basset::wrapper::ExecuteMsg as LSDWrapperExecuteMsg;
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -48,6 +53,8 @@ pub fn instantiate(
 
     PARAMETERS.save(deps.storage, &params)?;
 
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::new())
 }
 
@@ -157,6 +164,26 @@ fn query_params(deps: Deps) -> StdResult<Parameters> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+    // Contracts instantiated before they recorded their version don't have any
+    let previous = CONTRACT.may_load(deps.storage)?;
+    if let Some(previous) = &previous {
+        assert_can_migrate(
+            &previous.contract,
+            &previous.version,
+            CONTRACT_NAME,
+            CONTRACT_VERSION,
+            &[],
+        )?;
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr(
+            "previous_version",
+            previous.map(|previous| previous.version).unwrap_or_default(),
+        ),
+        attr("contract_version", CONTRACT_VERSION),
+    ]))
 }
@@ -11,16 +11,21 @@ use crate::state::{
 };
 use crate::user::{execute_claim_rewards, query_accrued_rewards};
 use cosmwasm_std::{
-    to_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    attr, to_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
 };
 
+use basset::migrate::assert_can_migrate;
 use basset::reward::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use cw2::{set_contract_version, CONTRACT};
 
 fn has_unique_elements(list: &[String]) -> bool {
     let mut uniq = HashSet::new();
     list.iter().all(move |x| uniq.insert(x))
 }
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -66,6 +71,8 @@ pub fn instantiate(
         },
     )?;
 
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::default())
 }
 
@@ -166,6 +173,26 @@ fn query_config(deps: Deps) -> StdResult<Config> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    // Contracts instantiated before they recorded their version don't have any
+    let previous = CONTRACT.may_load(deps.storage)?;
+    if let Some(previous) = &previous {
+        assert_can_migrate(
+            &previous.contract,
+            &previous.version,
+            CONTRACT_NAME,
+            CONTRACT_VERSION,
+            &[],
+        )?;
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr(
+            "previous_version",
+            previous.map(|previous| previous.version).unwrap_or_default(),
+        ),
+        attr("contract_version", CONTRACT_VERSION),
+    ]))
 }
@@ -3,7 +3,7 @@ pub mod querier;
 pub mod state;
 
 mod global;
-mod swap;
+use basset::swap;
 mod user;
 
 #[cfg(test)]
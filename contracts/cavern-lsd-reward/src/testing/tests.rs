@@ -23,7 +23,9 @@ use cosmwasm_std::{coins, StdError};
 use cosmwasm_std::{from_binary, BankMsg, Coin, CosmosMsg, SubMsg, Uint128};
 
 use crate::contract::{execute, instantiate, migrate, query};
+use cw2::{get_contract_version, set_contract_version};
 
+use crate::state::SWAP_CONFIG;
 use crate::swap::{create_swap_msgs, Asset};
 use crate::testing::mock_querier::{mock_dependencies, MOCK_HUB_CONTRACT_ADDR};
 use basset::reward::{ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
@@ -103,7 +105,7 @@ pub fn swap_to_reward_denom() {
             SubMsg::new(
                 create_swap_msgs(
                     deps.as_ref(),
-                    mock_env(),
+                    &SWAP_CONFIG.load(deps.as_ref().storage).unwrap(),
                     Asset {
                         amount: Uint128::new(1000u128),
                         asset_info: AssetInfo::NativeToken {
@@ -118,7 +120,7 @@ pub fn swap_to_reward_denom() {
             SubMsg::new(
                 create_swap_msgs(
                     deps.as_ref(),
-                    mock_env(),
+                    &SWAP_CONFIG.load(deps.as_ref().storage).unwrap(),
                     Asset {
                         amount: Uint128::new(50u128),
                         asset_info: AssetInfo::NativeToken {
@@ -133,7 +135,7 @@ pub fn swap_to_reward_denom() {
             SubMsg::new(
                 create_swap_msgs(
                     deps.as_ref(),
-                    mock_env(),
+                    &SWAP_CONFIG.load(deps.as_ref().storage).unwrap(),
                     Asset {
                         amount: Uint128::new(50u128),
                         asset_info: AssetInfo::NativeToken {
@@ -267,7 +269,34 @@ fn test_migrate() {
         amount: Uint128::new(100u128),
     }]);
 
+    // Contracts instantiated before they recorded their version can be migrated
     migrate(deps.as_mut(), mock_env(), MigrateMsg {
         max_decompound_ratio: None
     }).unwrap();
+    let version = get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.contract, "crates.io:cavern-lsd-reward");
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    // Migrating to an older version is refused
+    set_contract_version(&mut deps.storage, "crates.io:cavern-lsd-reward", "99.0.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {
+        max_decompound_ratio: None
+    }).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!(
+            "Can't downgrade crates.io:cavern-lsd-reward from 99.0.0 to {}",
+            env!("CARGO_PKG_VERSION")
+        ))
+    );
+
+    // Migrating from another contract is refused
+    set_contract_version(&mut deps.storage, "crates.io:cavern-lsd-hub", "1.0.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {
+        max_decompound_ratio: None
+    }).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Can't migrate from crates.io:cavern-lsd-hub to crates.io:cavern-lsd-reward")
+    );
 }
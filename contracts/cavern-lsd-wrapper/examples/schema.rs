@@ -1,7 +1,7 @@
 use cosmwasm_schema::write_api;
 
 use wrapper_implementations::coin::StrideInitMsg;
-use cw20_base::msg::{ExecuteMsg, QueryMsg};
+use basset::wrapper::{ExecuteMsg, QueryMsg};
 fn main() {
     write_api! {
         instantiate: StrideInitMsg,
@@ -1,17 +1,26 @@
-use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use cw20_base::{msg::QueryMsg, ContractError};
+use cosmwasm_std::{
+    entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+};
+use cw2::set_contract_version;
+use cw20_base::ContractError;
 
-use basset::wrapper::ExecuteMsg;
+use basset::wrapper::{ExecuteMsg, QueryMsg};
 use wrapper_implementations::coin;
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: coin::StrideInitMsg,
 ) -> StdResult<Response> {
-    coin::instantiate(deps, env, info, msg)
+    let res = coin::instantiate(deps.branch(), env, info, msg)?;
+    // cw20-base records its own contract name on instantiation, we replace it with the wrapper's
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -28,3 +37,13 @@ pub fn execute(
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     coin::query(deps, env, msg)
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    coin::reply(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: coin::MigrateMsg) -> Result<Response, ContractError> {
+    coin::migrate(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
+}
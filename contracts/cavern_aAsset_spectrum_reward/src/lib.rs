@@ -12,7 +12,8 @@ use basset::custom_reward::ExecuteWithSwapReply;
 
 use anchor_basset_custom_reward::contract;
 use astroport::pair::Cw20HookMsg;
-use basset::reward::QueryMsg;
+use basset::reward::{MigrateMsg, QueryMsg};
+use cw2::set_contract_version;
 use basset::reward::ExecuteMsg;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::DepsMut;
@@ -100,9 +101,14 @@ impl ExecuteWithSwapReply for SpectrumRetrieve {
     type RetrieveConfig = RetrieveConfig;
 }
 
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg<SpectrumRetrieve>) -> StdResult<Response> {
-    contract::instantiate::<SpectrumRetrieve>(deps, env, info, msg)
+pub fn instantiate(mut deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg<SpectrumRetrieve>) -> StdResult<Response> {
+    let res = contract::instantiate::<SpectrumRetrieve>(deps.branch(), env, info, msg)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -119,3 +125,8 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
    contract::reply(deps, env, msg)
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    contract::migrate(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
+}
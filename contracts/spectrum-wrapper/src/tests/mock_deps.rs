@@ -177,6 +177,15 @@ impl WasmMockQuerier {
                     _ => panic!("Not unimplemented! for tests")
                 } }
 
+                // The spectrum token answers the generator state query
+                if let Ok(SpectrumQueryMsg::State {}) = from_binary(msg) {
+                    return SystemResult::Ok(ContractResult::from(to_binary(
+                        &CTokenStateResponse {
+                            total_bond_share: self.bond_share_querier.share,
+                        },
+                    )));
+                }
+
                 // If we don't recognize it, we simply assume it's a cw20 query
                 match from_binary(msg).unwrap() {
                     Cw20QueryMsg::TokenInfo {} => {
@@ -212,13 +221,13 @@ impl WasmMockQuerier {
                             match self.token_querier.balances.get(contract_addr) {
                                 Some(balances) => balances,
                                 None => {
-                                    return SystemResult::Err(SystemError::InvalidRequest {
-                                        error: format!(
-                                            "No balance info exists for the contract {}",
-                                            contract_addr
-                                        ),
-                                        request: msg.as_slice().into(),
-                                    })
+                                    // No balance registered for this token yet
+                                    return SystemResult::Ok(ContractResult::Ok(
+                                        to_binary(&Cw20BalanceResponse {
+                                            balance: Uint128::zero(),
+                                        })
+                                        .unwrap(),
+                                    ));
                                 }
                             };
 
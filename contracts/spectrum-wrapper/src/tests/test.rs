@@ -1,15 +1,21 @@
 use crate::tests::mock_deps::MOCK_GENERATOR_ADDR;
-use crate::{execute, instantiate, query, ContractsRaw};
-use basset::wrapper::ExecuteMsg;
-use cavern_lsd_wrapper_token_with_limit::state::DECOMPOUND_CONFIG;
+use crate::{execute, instantiate, migrate, query, ContractsRaw};
+use basset::wrapper::{
+    AccruedRewardsResponse, Cw20HookMsg, DecompoundBudgetResponse, DecompoundConfigResponse, DecompoundStateResponse,
+    ExchangeRatesResponse, ExecuteMsg, QueryMsg, SimulationResponse, SlashingLoss, SlashingLossResponse,
+    WrapperStateResponse,
+};
+use cavern_lsd_wrapper_token_with_limit::msg::MigrateMsg;
+use cavern_lsd_wrapper_token_with_limit::state::{DECOMPOUND_CONFIG, DECOMPOUND_STATE, LSD_LEDGER};
+use cw2::{get_contract_version, set_contract_version};
 use cosmwasm_std::testing::{mock_env, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::testing::{mock_info, MockApi};
 use cosmwasm_std::to_binary;
 use cosmwasm_std::MemoryStorage;
 use cosmwasm_std::OwnedDeps;
 use cosmwasm_std::{from_binary, CosmosMsg, SubMsg, WasmMsg};
-use cosmwasm_std::{Decimal, Uint128, StdError};
-use cw20::{BalanceResponse, Cw20ExecuteMsg};
+use cosmwasm_std::{Decimal, Uint128, Uint256, StdError};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg};
 use cw20_base::ContractError;
 use std::str::FromStr;
 
@@ -66,6 +72,10 @@ fn init_env(
 
         hub_contract: "hub".to_string(),
 
+        guardian: None,
+
+        admin: None,
+
         lsd_config: ContractsRaw {
             token: MOCK_SPECTRUM_TOKEN.to_string(),
             generator: MOCK_GENERATOR_ADDR.to_string(),
@@ -111,6 +121,7 @@ fn test_deposit_funds() {
         ExecuteMsg::MintWith {
             recipient: "depositor".to_string(),
             lsd_amount: 1_000_000u128.into(),
+            min_received: None,
         },
     )
     .unwrap();
@@ -130,7 +141,7 @@ fn test_deposit_funds() {
         &query(
             deps.as_ref(),
             mock_env(),
-            cw20_base::msg::QueryMsg::Balance {
+            basset::wrapper::QueryMsg::Balance {
                 address: "depositor".to_string(),
             },
         )
@@ -153,6 +164,7 @@ fn test_deposit_funds_share() {
         ExecuteMsg::MintWith {
             recipient: "depositor".to_string(),
             lsd_amount: 1_000_000u128.into(),
+            min_received: None,
         },
     )
     .unwrap();
@@ -172,7 +184,7 @@ fn test_deposit_funds_share() {
         &query(
             deps.as_ref(),
             mock_env(),
-            cw20_base::msg::QueryMsg::Balance {
+            basset::wrapper::QueryMsg::Balance {
                 address: "depositor".to_string(),
             },
         )
@@ -183,6 +195,128 @@ fn test_deposit_funds_share() {
     assert_eq!(new_balance.balance, Uint128::from(4_000_000u128));
 }
 
+#[test]
+fn test_deposit_funds_through_receive() {
+    let mut deps = init_env(Some("0.1"));
+
+    // The tokens are already transferred when the hook is called
+    deps.querier.with_token_balances(&[(
+        &MOCK_SPECTRUM_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(1_000_000u128),
+        )],
+    )]);
+
+    let receive_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "depositor".to_string(),
+        amount: 1_000_000u128.into(),
+        msg: to_binary(&Cw20HookMsg::Mint {
+            recipient: None,
+            min_received: None,
+        })
+        .unwrap(),
+    });
+
+    // Only the spectrum token can mint through the hook
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("other-token", &[]),
+        receive_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_SPECTRUM_TOKEN, &[]),
+        receive_msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+
+    let new_balance: BalanceResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            basset::wrapper::QueryMsg::Balance {
+                address: "depositor".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(new_balance.balance, Uint128::from(1_000_000u128));
+}
+
+#[test]
+fn test_simulate_mint_and_burn() {
+    let mut deps = init_env(Some("0.1"));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("depositor", &[]),
+        ExecuteMsg::MintWith {
+            recipient: "depositor".to_string(),
+            lsd_amount: 1_000_000u128.into(),
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    // The wrapper now holds twice as many lsd tokens as there are wrapper tokens
+    deps.querier.with_token_balances(&[(
+        &MOCK_SPECTRUM_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(2_000_000u128),
+        )],
+    )]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::AddSurplusToRewards {},
+    )
+    .unwrap();
+
+    let simulate = |msg: QueryMsg| -> SimulationResponse {
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+    };
+
+    assert_eq!(
+        simulate(QueryMsg::SimulateMintWith {
+            lsd_amount: 1_000u128.into()
+        }),
+        SimulationResponse {
+            lsd_amount: 1_000u128.into(),
+            wrapper_amount: 500u128.into(),
+        }
+    );
+    assert_eq!(
+        simulate(QueryMsg::SimulateMint {
+            amount: 500u128.into()
+        }),
+        SimulationResponse {
+            lsd_amount: 1_000u128.into(),
+            wrapper_amount: 500u128.into(),
+        }
+    );
+    assert_eq!(
+        simulate(QueryMsg::SimulateBurn {
+            amount: 500u128.into()
+        }),
+        SimulationResponse {
+            lsd_amount: 1_000u128.into(),
+            wrapper_amount: 500u128.into(),
+        }
+    );
+}
+
 // What happens when we need to decompound (exchange rate changes)
 #[test]
 fn test_decompound_no_limit() {
@@ -195,6 +329,7 @@ fn test_decompound_no_limit() {
         ExecuteMsg::MintWith {
             recipient: "depositor".to_string(),
             lsd_amount: 1_000_000u128.into(),
+            min_received: None,
         },
     )
     .unwrap();
@@ -230,14 +365,14 @@ fn test_decompound_no_limit() {
     // OLD value : 1_000_000 of lsd deposited at 1.vs.1 exchange rate
     // NEW value : Now 1 unit of LSD equals 4 units of underlying asset.
     // SO the tokens are worth 4_000_000, only 250_000 tokens are needed to get a 1_000_000 value
-    // 750_000 tokens need to be decompounded. Because we limit the compute errors, 1 is substracted
+    // 750_000 tokens need to be decompounded.
 
     assert_eq!(
         res.messages,
         vec![get_transfer_msg(
             MOCK_SPECTRUM_TOKEN,
             "hub",
-            750_000u128 - 1u128
+            750_000u128
         )]
     );
 
@@ -270,6 +405,7 @@ fn test_decompound_same_time() {
         ExecuteMsg::MintWith {
             recipient: "depositor".to_string(),
             lsd_amount: 1_000_000u128.into(),
+            min_received: None,
         },
     )
     .unwrap();
@@ -307,6 +443,7 @@ fn test_decompound_with_limit() {
         ExecuteMsg::MintWith {
             recipient: "depositor".to_string(),
             lsd_amount: 1_000_000u128.into(),
+            min_received: None,
         },
     )
     .unwrap();
@@ -335,14 +472,13 @@ fn test_decompound_with_limit() {
     // NEW value : Now 1 unit of LSD equals 4 units of underlying asset.
     // BUT we limit the decompounding to 10%/year
     // So only 27 tokens may be decompounded per day
-    // Always the -1 to account for computation errors
 
     assert_eq!(
         res.messages,
         vec![get_transfer_msg(
             MOCK_SPECTRUM_TOKEN,
             "hub",
-            273u128 - 1u128
+            273u128
         )]
     );
 
@@ -365,7 +501,7 @@ fn test_decompound_with_limit() {
         vec![get_transfer_msg(
             MOCK_SPECTRUM_TOKEN,
             "hub",
-            275u128 - 1u128
+            274u128
         )]
     );
 
@@ -388,7 +524,598 @@ fn test_decompound_with_limit() {
         vec![get_transfer_msg(
             MOCK_SPECTRUM_TOKEN,
             "hub",
-            274u128 - 1u128
+            273u128
         )]
     );
-}
\ No newline at end of file
+}
+
+// Idle periods don't build up a decompound budget larger than one window
+#[test]
+fn test_decompound_rolling_window() {
+    let mut deps = init_env(Some("0.1"));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("depositor", &[]),
+        ExecuteMsg::MintWith {
+            recipient: "depositor".to_string(),
+            lsd_amount: 1_000_000u128.into(),
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &MOCK_SPECTRUM_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(1_000_000u128),
+        )],
+    )]);
+    deps.querier.with_bond_share(1000000, 4000000);
+
+    let budget = |deps: &OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>, env| -> DecompoundBudgetResponse {
+        from_binary(&query(deps.as_ref(), env, QueryMsg::DecompoundBudget {}).unwrap()).unwrap()
+    };
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(3600 * 24);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![get_transfer_msg(MOCK_SPECTRUM_TOKEN, "hub", 273u128)]
+    );
+    deps.querier.handle_execute(&res.messages).unwrap();
+    let day_one = budget(&deps, env);
+    assert_eq!(day_one.window_seconds, 30 * 24 * 3600);
+    assert_eq!(
+        day_one.released_ratio,
+        DECOMPOUND_STATE.load(&deps.storage).unwrap().ratio_sum
+    );
+
+    // 100 days later, only the limit of the last 30 days can be released
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(101 * 3600 * 24);
+    assert_eq!(
+        budget(&deps, env.clone()),
+        DecompoundBudgetResponse {
+            window_seconds: 30 * 24 * 3600,
+            released_ratio: Decimal::zero(),
+            remaining_ratio: Some(Decimal::from_str("0.008219178082191780").unwrap()),
+        }
+    );
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![get_transfer_msg(MOCK_SPECTRUM_TOKEN, "hub", 8216u128)]
+    );
+
+    // The expected exchange rate only accounts for what is left of the window budget
+    let after = budget(&deps, env.clone());
+    let state: WrapperStateResponse =
+        from_binary(&query(deps.as_ref(), env, QueryMsg::WrapperState {}).unwrap()).unwrap();
+    assert_eq!(
+        state.expected_exchange_rate,
+        Some(state.exchange_rate - after.remaining_ratio.unwrap())
+    );
+}
+
+#[test]
+fn test_update_decompound_config() {
+    let mut deps = init_env(Some("0.1"));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("depositor", &[]),
+        ExecuteMsg::MintWith {
+            recipient: "depositor".to_string(),
+            lsd_amount: 1_000_000u128.into(),
+            min_received: None,
+        },
+    )
+    .unwrap();
+    deps.querier.with_token_balances(&[(
+        &MOCK_SPECTRUM_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(1_000_000u128),
+        )],
+    )]);
+    deps.querier.with_bond_share(1000000, 4000000);
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(3600 * 24);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap();
+    let old_state = DECOMPOUND_STATE.load(&deps.storage).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("hub", &[]),
+        ExecuteMsg::UpdateDecompoundConfig {
+            max_decompound_ratio: Some(Decimal::from_str("0.2").unwrap()),
+            reset_state: false,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The decompound history is carried over
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateDecompoundConfig {
+            max_decompound_ratio: Some(Decimal::from_str("0.2").unwrap()),
+            reset_state: false,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1].value, "0.1");
+    assert_eq!(res.attributes[2].value, "0.2");
+    assert_eq!(DECOMPOUND_STATE.load(&deps.storage).unwrap(), old_state);
+    assert_eq!(
+        DECOMPOUND_CONFIG
+            .load(&deps.storage)
+            .unwrap()
+            .max_decompound_ratio,
+        Some(Decimal::from_str("0.2").unwrap())
+    );
+
+    // Resetting the history removes the decompounds of the window from the budget
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(2 * 3600 * 24);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateDecompoundConfig {
+            max_decompound_ratio: None,
+            reset_state: true,
+        },
+    )
+    .unwrap();
+    let decompound_state: DecompoundStateResponse = from_binary(
+        &query(deps.as_ref(), env.clone(), QueryMsg::DecompoundState {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        decompound_state,
+        DecompoundStateResponse {
+            ratio_sum: Decimal::zero(),
+            total_seconds: 0,
+            last_decompound: env.block.time,
+        }
+    );
+    let budget: DecompoundBudgetResponse = from_binary(
+        &query(deps.as_ref(), env, QueryMsg::DecompoundBudget {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(budget.released_ratio, Decimal::zero());
+    assert_eq!(budget.remaining_ratio, None);
+}
+
+#[test]
+fn test_wrapper_state_queries() {
+    let mut deps = init_env(Some("0.1"));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("depositor", &[]),
+        ExecuteMsg::MintWith {
+            recipient: "depositor".to_string(),
+            lsd_amount: 1_000_000u128.into(),
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &MOCK_SPECTRUM_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(1_000_000u128),
+        )],
+    )]);
+    deps.querier.with_bond_share(1000000, 4000000);
+
+    let config: DecompoundConfigResponse = from_binary(
+        &query(deps.as_ref(), mock_env(), QueryMsg::DecompoundConfig {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        config.max_decompound_ratio,
+        Some(Decimal::from_str("0.1").unwrap())
+    );
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(3600 * 24);
+
+    let state: WrapperStateResponse = from_binary(
+        &query(deps.as_ref(), env.clone(), QueryMsg::WrapperState {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        state,
+        WrapperStateResponse {
+            lsd_exchange_rate: Decimal::from_str("4").unwrap(),
+            lsd_balance: Uint128::from(1_000_000u128),
+            backing_luna: Decimal::from_str("4000000").unwrap(),
+            wlsd_supply: Uint128::from(1_000_000u128),
+            exchange_rate: Decimal::from_str("4").unwrap(),
+            // The decompound limit only allows 10%/year to be released
+            expected_exchange_rate: Some(Decimal::from_str("3.999726027397260274").unwrap()),
+        }
+    );
+
+    // The accrued rewards preview matches what the decompound releases
+    let rewards: AccruedRewardsResponse = from_binary(
+        &query(deps.as_ref(), env.clone(), QueryMsg::AccruedRewards {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(rewards.lsd_rewards, Uint128::from(273u128));
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![get_transfer_msg(
+            MOCK_SPECTRUM_TOKEN,
+            "hub",
+            rewards.lsd_rewards
+        )]
+    );
+
+    let decompound_state: DecompoundStateResponse = from_binary(
+        &query(deps.as_ref(), env.clone(), QueryMsg::DecompoundState {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        decompound_state,
+        DecompoundStateResponse {
+            ratio_sum: rewards.rate_decrease.unwrap(),
+            total_seconds: 3600 * 24,
+            last_decompound: env.block.time,
+        }
+    );
+
+    // The mint and the decompound both recorded the exchange rates
+    let history: ExchangeRatesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ExchangeRates {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(history.exchange_rates.len(), 2);
+    assert_eq!(history.exchange_rates[0].time, env.block.time.seconds());
+    assert_eq!(history.exchange_rates[0].exchange_rate, state.exchange_rate);
+    assert_eq!(history.exchange_rates[1].time, mock_env().block.time.seconds());
+}
+
+#[test]
+fn test_slashing_loss() {
+    let mut deps = init_env(Some("0.1"));
+    let mint_msg = ExecuteMsg::MintWith {
+        recipient: "depositor".to_string(),
+        lsd_amount: 1_000_000u128.into(),
+        min_received: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("depositor", &[]), mint_msg).unwrap();
+
+    let slashing_loss = |deps: &OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>| -> SlashingLossResponse {
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SlashingLoss {}).unwrap()).unwrap()
+    };
+    assert_eq!(
+        slashing_loss(&deps),
+        SlashingLossResponse {
+            under_collateralized: false,
+            redemption_rate: Decimal::one(),
+            luna_loss: Uint128::zero(),
+            last_loss: None,
+        }
+    );
+
+    // The lsd lost half of its value, burns redeem the remaining backing pro-rata
+    deps.querier.with_bond_share(1000000, 500000);
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(3600);
+    let mint_msg = ExecuteMsg::MintWith {
+        recipient: "depositor".to_string(),
+        lsd_amount: 1_000u128.into(),
+        min_received: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("depositor", &[]), mint_msg).unwrap();
+
+    // The loss is recorded before the mint
+    let loss = SlashingLoss {
+        time: env.block.time.seconds(),
+        exchange_rate: Decimal::from_str("0.5").unwrap(),
+        luna_loss: Uint128::from(500_000u128),
+    };
+    assert_eq!(
+        slashing_loss(&deps),
+        SlashingLossResponse {
+            under_collateralized: true,
+            redemption_rate: Decimal::from_str("0.5").unwrap(),
+            luna_loss: Uint128::from(500_500u128),
+            last_loss: Some(loss),
+        }
+    );
+}
+
+#[test]
+fn test_migrate_from_plain_wrapper() {
+    let mut deps = init_env(None);
+    assert_eq!(
+        get_contract_version(&deps.storage).unwrap().contract,
+        "crates.io:spectrum-wrapper-token"
+    );
+
+    // Wrappers instantiated with the plain wrapper package have no decompound state and are registered as cw20-base
+    DECOMPOUND_CONFIG.remove(&mut deps.storage);
+    DECOMPOUND_STATE.remove(&mut deps.storage);
+    set_contract_version(&mut deps.storage, "crates.io:cw20-base", "1.1.0").unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(1000);
+    migrate(
+        deps.as_mut(),
+        env.clone(),
+        MigrateMsg {
+            admin: Some("new_admin".to_string()),
+            guardian: None,
+            max_decompound_ratio: Some(Decimal::from_str("0.1").unwrap()),
+        },
+    )
+    .unwrap();
+
+    let config: DecompoundConfigResponse = from_binary(
+        &query(deps.as_ref(), mock_env(), QueryMsg::DecompoundConfig {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        config.max_decompound_ratio,
+        Some(Decimal::from_str("0.1").unwrap())
+    );
+    let state: DecompoundStateResponse = from_binary(
+        &query(deps.as_ref(), mock_env(), QueryMsg::DecompoundState {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        state,
+        DecompoundStateResponse {
+            ratio_sum: Decimal::zero(),
+            total_seconds: 0,
+            last_decompound: env.block.time,
+        }
+    );
+    let version = get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.contract, "crates.io:spectrum-wrapper-token");
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    // The existing decompound state is kept by later migrations
+    let mut later_env = env.clone();
+    later_env.block.time = later_env.block.time.plus_seconds(1000);
+    migrate(
+        deps.as_mut(),
+        later_env,
+        MigrateMsg {
+            admin: None,
+            guardian: None,
+            max_decompound_ratio: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        DECOMPOUND_STATE.load(&deps.storage).unwrap().last_decompound,
+        env.block.time
+    );
+    assert_eq!(
+        DECOMPOUND_CONFIG
+            .load(&deps.storage)
+            .unwrap()
+            .max_decompound_ratio,
+        Some(Decimal::from_str("0.1").unwrap())
+    );
+
+    // Downgrades are refused
+    set_contract_version(&mut deps.storage, "crates.io:spectrum-wrapper-token", "99.0.0").unwrap();
+    let err = migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            admin: None,
+            guardian: None,
+            max_decompound_ratio: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(format!(
+            "Can't downgrade crates.io:spectrum-wrapper-token from 99.0.0 to {}",
+            env!("CARGO_PKG_VERSION")
+        )))
+    );
+}
+
+#[test]
+fn test_circuit_breaker_on_generator_state() {
+    let mut deps = init_env(None);
+    deps.querier.with_bond_share(1000000, 4000000);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateCircuitBreaker {
+            max_change_per_hour: Some(Decimal::percent(5)),
+        },
+    )
+    .unwrap();
+
+    let mint_msg = ExecuteMsg::MintWith {
+        recipient: "depositor".to_string(),
+        lsd_amount: 1_000_000u128.into(),
+        min_received: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("depositor", &[]),
+        mint_msg.clone(),
+    )
+    .unwrap();
+
+    // A manipulated generator state doubles the spectrum exchange rate
+    deps.querier.with_bond_share(1000000, 8000000);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("depositor", &[]),
+        mint_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Lsd exchange rate moved from 4 to 8, more than the allowed 0.05"
+        ))
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("Lsd exchange rate moved"));
+}
+
+#[test]
+fn test_share_price_after_random_operations() {
+    let mut deps = init_env(Some("0.1"));
+    let share = 1_000_000u128;
+    let mut amount = 1_000_000u128;
+    deps.querier.with_bond_share(share, amount);
+
+    // Deterministic pseudo-random sequence, so that failures can be reproduced
+    let mut seed = 0x9e37_79b9_7f4a_7c15u64;
+    let mut next = |max: u128| -> u128 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed as u128 % max
+    };
+    let supply = |deps: &OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>| {
+        cw20_base::contract::query_token_info(deps.as_ref())
+            .unwrap()
+            .total_supply
+    };
+
+    let mut env = mock_env();
+    for _ in 0..500 {
+        let (sender, msg) = match next(4) {
+            0 => (
+                "depositor",
+                ExecuteMsg::Mint {
+                    recipient: "depositor".to_string(),
+                    amount: Uint128::new(next(1_000_000_000_000_000_000_000) + 1),
+                    max_lsd_in: None,
+                },
+            ),
+            1 => (
+                "depositor",
+                ExecuteMsg::MintWith {
+                    recipient: "depositor".to_string(),
+                    lsd_amount: Uint128::new(next(1_000_000) + 1),
+                    min_received: None,
+                },
+            ),
+            2 => {
+                let balance = cw20_base::contract::query_balance(deps.as_ref(), "depositor".to_string())
+                    .unwrap()
+                    .balance;
+                if balance.is_zero() {
+                    continue;
+                }
+                (
+                    "depositor",
+                    ExecuteMsg::Burn {
+                        amount: Uint128::new(next(balance.u128()) + 1),
+                        min_received: None,
+                        recipient: None,
+                    },
+                )
+            }
+            _ => {
+                // The lsd only accrues rewards
+                amount += next(10_000);
+                deps.querier.with_bond_share(share, amount);
+                env.block.time = env.block.time.plus_seconds(3600 * 24);
+                ("hub", ExecuteMsg::Decompound { recipient: None })
+            }
+        };
+        let is_decompound = matches!(msg, ExecuteMsg::Decompound { .. });
+        let supply_before = supply(&deps);
+        let ledger_before = LSD_LEDGER.load(&deps.storage).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg).unwrap();
+        let supply_after = supply(&deps);
+        let ledger_after = LSD_LEDGER.load(&deps.storage).unwrap();
+
+        // Mints and burns never lower the lsd tokens backing each wrapper token
+        if !is_decompound && !supply_before.is_zero() {
+            assert!(
+                Uint256::from(ledger_after) * Uint256::from(supply_before)
+                    >= Uint256::from(ledger_before) * Uint256::from(supply_after),
+                "{}/{} lsd tokens per wrapper token dropped to {}/{}",
+                ledger_before,
+                supply_before,
+                ledger_after,
+                supply_after
+            );
+        }
+        // And the wrapper tokens stay backed by at least one underlying token each
+        assert!(
+            Uint256::from(ledger_after) * Uint256::from(amount)
+                >= Uint256::from(supply_after) * Uint256::from(share),
+            "{} lsd tokens at {}/{} don't back {} wrapper tokens",
+            ledger_after,
+            amount,
+            share,
+            supply_after
+        );
+    }
+}
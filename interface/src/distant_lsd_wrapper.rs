@@ -3,13 +3,13 @@ use cw_orch::{
     prelude::*,
 };
 
-use wrapper_implementations::coin::StrideInitMsg;
+use wrapper_implementations::coin::{MigrateMsg, StrideInitMsg};
 use basset::wrapper::ExecuteMsg;
-use cw20_base::msg::QueryMsg;
+use basset::wrapper::QueryMsg;
 
-use cavern_lsd_wrapper::{instantiate, execute, query};
+use cavern_lsd_wrapper::{instantiate, execute, query, migrate};
 
-#[interface(StrideInitMsg, ExecuteMsg, QueryMsg, Empty)]
+#[interface(StrideInitMsg, ExecuteMsg, QueryMsg, MigrateMsg)]
 pub struct LsdWrapper;
 
 impl<Chain: CwEnv> Uploadable for LsdWrapper<Chain> {
@@ -27,6 +27,7 @@ impl<Chain: CwEnv> Uploadable for LsdWrapper<Chain> {
                 instantiate,
                 query,
             )
+            .with_migrate(migrate)
         )
     }
 }
@@ -1,6 +1,6 @@
 pub mod distant_lsd_wrapper;
 pub use distant_lsd_wrapper::LsdWrapper;
-pub use cw20_base::msg::QueryMsgFns as WrapperQueryMsgFns;
+pub use basset::wrapper::QueryMsgFns as WrapperQueryMsgFns;
 pub use basset::wrapper::ExecuteMsgFns as WrapperExecuteMsgFns;
 
 pub mod lsd_hub;
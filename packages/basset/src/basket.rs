@@ -0,0 +1,239 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Binary;
+use cosmwasm_std::Decimal;
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+use cw20::Expiration;
+use cw20::Logo;
+
+use crate::wrapper::SimulationResponse;
+
+#[cw_serde]
+#[cfg_attr(feature="interface", derive(cw_orch::ExecuteFns))]
+pub enum ExecuteMsg {
+    /// Transfer is a base message to move tokens to another account without triggering actions
+    Transfer {
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Burn is a base message to destroy tokens forever
+    /// The underlying lsd tokens are sent back to the sender, only from `member` if specified,
+    /// or pro-rata from all the basket members otherwise.
+    /// The call fails if the lsd tokens sent back are worth less than `min_received` underlying tokens
+    Burn {
+        amount: Uint128,
+        member: Option<String>,
+        min_received: Option<Uint128>,
+    },
+    /// Send is a base message to transfer tokens to a contract and trigger an action
+    /// on the receiving contract.
+    Send {
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Only with "approval" extension. Allows spender to access an additional amount tokens
+    /// from the owner's (env.sender) account. If expires is Some(), overwrites current allowance
+    /// expiration with this one.
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Only with "approval" extension. Lowers the spender's access of tokens
+    /// from the owner's (env.sender) account by amount. If expires is Some(), overwrites current
+    /// allowance expiration with this one.
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Only with "approval" extension. Transfers amount tokens from owner -> recipient
+    /// if `env.sender` has sufficient pre-approval.
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Only with "approval" extension. Sends amount tokens from owner -> contract
+    /// if `env.sender` has sufficient pre-approval.
+    SendFrom {
+        owner: String,
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Only with "approval" extension. Destroys tokens forever, with the same payout rules as `Burn`
+    BurnFrom {
+        owner: String,
+        amount: Uint128,
+        member: Option<String>,
+        min_received: Option<Uint128>,
+    },
+    /// Mints wrapper tokens in exchange of `lsd_amount` tokens of the basket `member`.
+    /// Native lsd tokens are sent along with this message, cw20 lsd tokens are pulled from the caller's allowance.
+    /// The call fails if less than `min_received` wrapper tokens would be minted
+    MintWith {
+        member: String,
+        recipient: String,
+        lsd_amount: Uint128,
+        min_received: Option<Uint128>,
+    },
+    /// Receives a message of type [`Cw20ReceiveMsg`] from the token of one of the basket members
+    /// This allows minting wrapper tokens with a single cw20 `Send`, without any prior allowance
+    Receive(Cw20ReceiveMsg),
+    /// Only with the "marketing" extension. If authorized, updates marketing metadata.
+    /// Setting None/null for any of these will leave it unchanged.
+    /// Setting Some("") will clear this field on the contract storage
+    UpdateMarketing {
+        /// A URL pointing to the project behind this token.
+        project: Option<String>,
+        /// A longer description of the token and it's utility. Designed for tooltips or such
+        description: Option<String>,
+        /// The address (if any) who can update this data structure
+        marketing: Option<String>,
+    },
+    /// If set as the "marketing" role on the contract, upload a new URL, SVG, or PNG for the token
+    UploadLogo(Logo),
+
+    /// Wrapper specific message
+    /// Sends the rewards accrued by the whole basket, pro-rata from all the members
+    Decompound {
+        recipient: Option<String>,
+    },
+    /// Only the admin can call this. Replaces the weight bands of the basket members.
+    /// All the members need to be specified
+    UpdateWeights {
+        weights: Vec<MemberWeight>,
+    },
+}
+
+#[cw_serde]
+pub struct MemberWeight {
+    pub member: String,
+    /// Share of the basket backing under which the member can't be burned alone anymore
+    pub min_weight: Decimal,
+    /// Share of the basket backing above which the member can't be deposited anymore
+    pub max_weight: Decimal,
+}
+
+/// This structure describes the hooks that can be attached to a cw20 `Send` of a basket member token
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Mints wrapper tokens in exchange of all the lsd tokens sent along with this message
+    /// The minted tokens go to the cw20 sender if no recipient is specified
+    /// The call fails if less than `min_received` wrapper tokens would be minted
+    Mint {
+        recipient: Option<String>,
+        min_received: Option<Uint128>,
+    },
+}
+
+#[cw_serde]
+#[cfg_attr(feature="interface", derive(cw_orch::QueryFns))]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the current balance of the given address, 0 if unset.
+    #[returns(cw20::BalanceResponse)]
+    Balance { address: String },
+    /// Returns metadata on the contract - name, decimals, supply, etc.
+    #[returns(cw20::TokenInfoResponse)]
+    TokenInfo {},
+    /// Returns who can mint and the hard cap on maximum tokens after minting.
+    #[returns(cw20::MinterResponse)]
+    Minter {},
+    /// Returns how much spender can use from owner account, 0 if unset.
+    #[returns(cw20::AllowanceResponse)]
+    Allowance { owner: String, spender: String },
+    /// Returns all allowances this owner has approved. Supports pagination.
+    #[returns(cw20::AllAllowancesResponse)]
+    AllAllowances {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns all allowances this spender has been granted. Supports pagination.
+    #[returns(cw20::AllSpenderAllowancesResponse)]
+    AllSpenderAllowances {
+        spender: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns all accounts that have balances. Supports pagination.
+    #[returns(cw20::AllAccountsResponse)]
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns more metadata on the contract to display in the client:
+    /// - description, logo, project url, etc.
+    #[returns(cw20::MarketingInfoResponse)]
+    MarketingInfo {},
+    /// Downloads the embedded logo data (if stored on chain). Errors if no logo data is stored for this
+    /// contract.
+    #[returns(cw20::DownloadLogoResponse)]
+    DownloadLogo {},
+
+    /// Wrapper specific queries
+    /// Returns the amount of wrapper tokens minted by `ExecuteMsg::MintWith` when depositing `lsd_amount` tokens of `member`
+    #[returns(SimulationResponse)]
+    SimulateMintWith { member: String, lsd_amount: Uint128 },
+    /// Returns the lsd tokens sent back by `ExecuteMsg::Burn` when burning `amount` wrapper tokens
+    #[returns(BasketBurnResponse)]
+    SimulateBurn {
+        amount: Uint128,
+        member: Option<String>,
+    },
+    /// Returns the holdings of each basket member and the current exchange rate of the wrapper
+    #[returns(BasketStateResponse)]
+    BasketState {},
+    /// Returns the admin and the hub contract of the basket
+    #[returns(BasketConfigResponse)]
+    Config {},
+}
+
+#[cw_serde]
+pub struct MemberAmount {
+    pub member: String,
+    pub lsd_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct BasketBurnResponse {
+    /// Amount of lsd tokens sent back for each member
+    pub payouts: Vec<MemberAmount>,
+    /// Amount of underlying tokens the payouts are worth
+    pub luna_value: Uint128,
+}
+
+#[cw_serde]
+pub struct MemberStateResponse {
+    pub member: String,
+    /// Exchange rate between the underlying token and the lsd, as reported by the member adapter
+    pub lsd_exchange_rate: Decimal,
+    /// Amount of lsd tokens held by the basket
+    pub lsd_balance: Uint128,
+    /// Amount of underlying tokens backed by this member
+    pub backing_luna: Decimal,
+    /// Share of the basket backing held in this member
+    pub weight: Decimal,
+    pub min_weight: Decimal,
+    pub max_weight: Decimal,
+}
+
+#[cw_serde]
+pub struct BasketStateResponse {
+    pub members: Vec<MemberStateResponse>,
+    /// Amount of underlying tokens backing the wrapper supply
+    pub backing_luna: Decimal,
+    /// Total supply of the wrapper token
+    pub wlsd_supply: Uint128,
+    /// Exchange rate between the underlying token and the wrapper token
+    pub exchange_rate: Decimal,
+}
+
+#[cw_serde]
+pub struct BasketConfigResponse {
+    pub admin: String,
+    pub hub_contract: String,
+}
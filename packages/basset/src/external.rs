@@ -8,6 +8,12 @@ pub enum LSDQueryMsg {
     State {},
 }
 
+#[cw_serde]
+pub enum LSDExecuteMsg {
+    /// Bonds the native tokens sent along with this message and mints lsd tokens to `receiver`, or to the sender
+    Bond { receiver: Option<String> },
+}
+
 #[cw_serde]
 pub struct LSDStateResponse {
     /// Total supply to the Steak token
@@ -1,11 +1,17 @@
+pub mod basket;
 pub mod contract_error;
 pub mod custom_reward;
 pub mod dex_router;
 pub mod external;
 pub mod hub;
+pub mod math;
+pub mod migrate;
+pub mod native_wrapper;
 pub mod oracle;
+pub mod permit;
 pub mod price_querier;
 pub mod reward;
+pub mod swap;
 pub mod wrapper;
 pub mod eris_lp;
 
@@ -0,0 +1,28 @@
+use cosmwasm_std::{StdResult, Uint128, Uint256};
+use std::convert::TryFrom;
+
+/// Rounding direction of an amount computed by the wrapper.
+/// Amounts paid to the wrapper are rounded up, amounts paid by the wrapper are rounded down
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Computes `amount * numerator / denominator` on 256 bits, so that the intermediate product can't overflow.
+/// Errors if the denominator is zero or if the result doesn't fit in 128 bits
+pub fn multiply_ratio(
+    amount: Uint128,
+    numerator: impl Into<Uint256>,
+    denominator: impl Into<Uint256>,
+    rounding: Rounding,
+) -> StdResult<Uint128> {
+    let denominator = denominator.into();
+    let product = Uint256::from(amount).checked_mul(numerator.into())?;
+    let quotient = product.checked_div(denominator)?;
+    let result = match rounding {
+        Rounding::Up if !product.checked_rem(denominator)?.is_zero() => quotient + Uint256::one(),
+        _ => quotient,
+    };
+    Ok(Uint128::try_from(result)?)
+}
@@ -0,0 +1,39 @@
+use cosmwasm_std::{StdError, StdResult};
+use semver::Version;
+
+/// Contract name recorded by cw20-base when instantiating the wrapper tokens,
+/// before the wrappers recorded their own name and version
+pub const CW20_BASE_CONTRACT_NAME: &str = "crates.io:cw20-base";
+
+/// Makes sure a contract recorded as `previous_contract` (at `previous_version`) can be migrated to `contract` (at `version`)
+/// A contract can only be migrated from itself or from one of the `legacy_contracts`, and can't be downgraded.
+/// The version recorded by a legacy contract is not the version of the migrated contract, so it is not compared
+pub fn assert_can_migrate(
+    previous_contract: &str,
+    previous_version: &str,
+    contract: &str,
+    version: &str,
+    legacy_contracts: &[&str],
+) -> StdResult<()> {
+    if legacy_contracts.contains(&previous_contract) {
+        return Ok(());
+    }
+    if previous_contract != contract {
+        return Err(StdError::generic_err(format!(
+            "Can't migrate from {} to {}",
+            previous_contract, contract
+        )));
+    }
+    if parse_version(previous_version)? > parse_version(version)? {
+        return Err(StdError::generic_err(format!(
+            "Can't downgrade {} from {} to {}",
+            contract, previous_version, version
+        )));
+    }
+    Ok(())
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    Version::parse(version)
+        .map_err(|err| StdError::generic_err(format!("Invalid version {}: {}", version, err)))
+}
@@ -0,0 +1,215 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Binary;
+use cosmwasm_std::Decimal;
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+
+use crate::wrapper::{
+    AccruedRewardsResponse, AdminResponse, CircuitBreakerResponse, DecompoundBudgetResponse,
+    DecompoundConfigResponse, DecompoundStateResponse, ExchangeRatesResponse, LsdLedgerResponse, MintLimitsResponse,
+    PauseStateResponse, RetentionResponse, RewardRecipient, RewardSplitResponse,
+    SimulationResponse, SlashingLossResponse, TotalSupplyResponse, WrapperStateResponse,
+};
+
+/// Messages of the wrappers minting a token-factory denom instead of a cw20 token.
+/// The lsd tokens are deposited with the same messages as for the cw20 wrappers (`Receive` uses [`crate::wrapper::Cw20HookMsg`])
+#[cw_serde]
+#[cfg_attr(feature="interface", derive(cw_orch::ExecuteFns))]
+pub enum ExecuteMsg {
+    /// If the contract can transfer enough lsd funds from the caller, mints `amount` native wrapper tokens to the recipient.
+    /// The call fails if more than `max_lsd_in` lsd tokens would be taken from the caller
+    Mint {
+        recipient: String,
+        amount: Uint128,
+        max_lsd_in: Option<Uint128>,
+    },
+    /// Same as the Mint function but you specify the amount of funds you want to send to the contract instead
+    /// The call fails if less than `min_received` wrapper tokens would be minted
+    MintWith {
+        recipient: String,
+        lsd_amount: Uint128,
+        min_received: Option<Uint128>,
+    },
+    /// Receives a message of type [`Cw20ReceiveMsg`] from the underlying lsd token
+    Receive(Cw20ReceiveMsg),
+    /// Burns the native wrapper tokens sent along with this message.
+    /// The underlying lsd tokens are sent to `recipient`, or back to the sender if no recipient is specified.
+    /// The call fails if less than `min_received` lsd tokens would be sent
+    Burn {
+        recipient: Option<String>,
+        min_received: Option<Uint128>,
+    },
+    /// Converts the cw20 wrapper tokens held by the sender since before the wrapper was migrated to the native denom, 1:1.
+    /// Converts the whole cw20 balance if no amount is specified. The native tokens go to `recipient`, or to the sender
+    ConvertCw20 {
+        amount: Option<Uint128>,
+        recipient: Option<String>,
+    },
+
+    /// Wrapper specific message
+    Decompound {
+        recipient: Option<String>,
+    },
+    /// Only the guardian can call this. Pauses or resumes the mint, burn and decompound operations.
+    /// Unset fields are left unchanged. Conversions are never paused
+    SetPaused {
+        mint: Option<bool>,
+        burn: Option<bool>,
+        decompound: Option<bool>,
+    },
+    /// Only the guardian can call this. Hands over the guardian role, or removes it if no guardian is specified
+    UpdateGuardian {
+        guardian: Option<String>,
+    },
+    /// Only the admin can call this. Limits the total wrapper supply and the amount of wrapper tokens
+    /// that can be minted by a single address during each `mint_window` (in seconds).
+    /// The per-address limit applies to the address paying for the mint, and needs a non-zero window.
+    /// Setting a limit to None removes it
+    UpdateMintLimits {
+        max_supply: Option<Uint128>,
+        max_mint_per_address: Option<Uint128>,
+        mint_window: u64,
+    },
+    /// Only the admin can call this. Sets the maximum decompound ratio per year, no ratio removes the decompound limit.
+    /// The decompound history is kept unless `reset_state` is set, in which case the limit accrues again from now
+    UpdateDecompoundConfig {
+        max_decompound_ratio: Option<Decimal>,
+        reset_state: bool,
+    },
+    /// Only the admin can call this. Proposes a new admin, who has `expires_in` seconds to claim the role
+    ProposeNewAdmin {
+        admin: String,
+        expires_in: u64,
+    },
+    /// Only the admin can call this. Cancels the pending admin proposal
+    DropAdminProposal {},
+    /// Only the proposed admin can call this. Accepts the admin role
+    ClaimAdmin {},
+    /// Only the admin can call this. Changes the contract allowed to call `Decompound`
+    UpdateHub {
+        hub_contract: String,
+    },
+    /// Only the admin can call this. Replaces the lsd config of the wrapper.
+    /// `lsd_config` is the json encoded config, in the same format as in the instantiate message.
+    /// The lsd token of the new config has to be the same as the current one
+    UpdateLsdConfig {
+        lsd_config: Binary,
+    },
+    /// Only the admin can call this. Adds the lsd tokens held by the wrapper on top of its ledger to the ledger,
+    /// so that they are released as rewards by the next `Decompound`
+    AddSurplusToRewards {},
+    /// Only the admin can call this. Sends the lsd tokens held by the wrapper on top of its ledger to `recipient`,
+    /// or to the admin if no recipient is specified
+    SweepSurplus {
+        recipient: Option<String>,
+    },
+    /// Anyone can call this. Trips the circuit breaker if the lsd exchange rate moved more than allowed
+    /// since the reference rate. Mint, burn and decompound are then rejected until the admin updates the circuit breaker
+    CheckLsdExchangeRate {},
+    /// Only the admin can call this. Sets the maximum relative change of the lsd exchange rate per hour,
+    /// resets the circuit breaker and takes the current lsd exchange rate as reference.
+    /// Setting no maximum change disables the circuit breaker
+    UpdateCircuitBreaker {
+        max_change_per_hour: Option<Decimal>,
+    },
+    /// Only the admin can call this. Sets the recipients taking a share of the decompounded rewards, such as a treasury.
+    /// The rest of the rewards goes to the `Decompound` recipient
+    UpdateRewardSplit {
+        recipients: Vec<RewardRecipient>,
+    },
+    /// Only the admin can call this. Sets the share of the rewards kept in the wrapper on each `Decompound`.
+    /// 0 releases all the rewards, 1 makes the wrapper exchange rate accumulate all of them
+    UpdateRetentionRatio {
+        retention_ratio: Decimal,
+    },
+}
+
+#[cw_serde]
+#[cfg_attr(feature="interface", derive(cw_orch::QueryFns))]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the native denom of the wrapper
+    #[returns(DenomResponse)]
+    Denom {},
+    /// Returns the metadata of the wrapper. The total supply includes the cw20 tokens that were not converted yet
+    #[returns(cw20::TokenInfoResponse)]
+    TokenInfo {},
+    /// Returns the cw20 balance of the given address that can still be converted to the native denom, 0 if unset.
+    #[returns(cw20::BalanceResponse)]
+    Cw20Balance { address: String },
+    /// Returns the cw20 balance of the given address at the end of `height`, or the current one if no height is specified.
+    /// Only heights since the wrapper started recording snapshots are available
+    #[returns(cw20::BalanceResponse)]
+    Cw20BalanceAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+    /// Returns the total supply at the end of `height`, or the current total supply if no height is specified.
+    /// The total supply includes the cw20 tokens that were not converted yet.
+    /// Only heights since the wrapper started recording snapshots are available
+    #[returns(TotalSupplyResponse)]
+    TotalSupplyAtHeight { height: Option<u64> },
+
+    /// Wrapper specific queries
+    /// Returns the amount of lsd tokens pulled from the caller by `ExecuteMsg::Mint` when minting `amount` wrapper tokens
+    #[returns(SimulationResponse)]
+    SimulateMint { amount: Uint128 },
+    /// Returns the amount of wrapper tokens minted by `ExecuteMsg::MintWith` when depositing `lsd_amount` lsd tokens
+    #[returns(SimulationResponse)]
+    SimulateMintWith { lsd_amount: Uint128 },
+    /// Returns the amount of lsd tokens sent back by `ExecuteMsg::Burn` when burning `amount` wrapper tokens
+    #[returns(SimulationResponse)]
+    SimulateBurn { amount: Uint128 },
+    /// Returns the internal accounting state of the wrapper and its current exchange rate
+    #[returns(WrapperStateResponse)]
+    WrapperState {},
+    /// Returns the rewards that would be released by `ExecuteMsg::Decompound` at the current block
+    #[returns(AccruedRewardsResponse)]
+    AccruedRewards {},
+    /// Returns the decompound limit of the wrapper
+    #[returns(DecompoundConfigResponse)]
+    DecompoundConfig {},
+    /// Returns the decompound totals since the decompound limit started accruing
+    #[returns(DecompoundStateResponse)]
+    DecompoundState {},
+    /// Returns the share of the wrapper backing the decompound can still release over the trailing window
+    #[returns(DecompoundBudgetResponse)]
+    DecompoundBudget {},
+    /// Returns the exchange rates recorded on mint, burn and decompound, from the most recent to the oldest
+    #[returns(ExchangeRatesResponse)]
+    ExchangeRates {
+        // start after the provided timestamp in s
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the guardian and the operations it has paused
+    #[returns(PauseStateResponse)]
+    PauseState {},
+    /// Returns the admin, the pending admin proposal and the hub contract of the wrapper
+    #[returns(AdminResponse)]
+    Admin {},
+    /// Returns the admin and the mint limits of the wrapper
+    #[returns(MintLimitsResponse)]
+    MintLimits {},
+    /// Returns the lsd tokens accounted for by the wrapper and the lsd tokens it actually holds
+    #[returns(LsdLedgerResponse)]
+    LsdLedger {},
+    /// Returns the circuit breaker on the lsd exchange rate
+    #[returns(CircuitBreakerResponse)]
+    CircuitBreaker {},
+    /// Returns the current shortfall of the wrapper backing and the last slashing loss recorded
+    #[returns(SlashingLossResponse)]
+    SlashingLoss {},
+    /// Returns the split of the decompounded rewards
+    #[returns(RewardSplitResponse)]
+    RewardSplit {},
+    /// Returns the share of the rewards kept in the wrapper and the exchange rate the wrapper is brought back to
+    #[returns(RetentionResponse)]
+    Retention {},
+}
+
+#[cw_serde]
+pub struct DenomResponse {
+    /// Token-factory denom of the wrapper, `factory/{wrapper}/{subdenom}`
+    pub denom: String,
+}
@@ -0,0 +1,57 @@
+use bech32::{ToBase32, Variant};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_vec, Api, Binary, StdError, StdResult, Uint128};
+use cw20::Expiration;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+/// Allowance signed by the owner of the wrapper tokens.
+/// The owner signs the sha256 hash of the json encoded payload
+#[cw_serde]
+pub struct PermitPayload {
+    pub chain_id: String,
+    /// Wrapper contract the allowance is set on
+    pub contract: String,
+    pub owner: String,
+    pub spender: String,
+    pub amount: Uint128,
+    pub expires: Expiration,
+    pub nonce: u64,
+}
+
+/// Cosmos address of a compressed secp256k1 public key, with the bech32 `prefix` of the chain
+pub fn pubkey_to_address(pubkey: &[u8], prefix: &str) -> StdResult<String> {
+    if pubkey.len() != 33 {
+        return Err(StdError::generic_err(
+            "The public key has to be a compressed secp256k1 key",
+        ));
+    }
+    let hash = Ripemd160::digest(Sha256::digest(pubkey));
+    bech32::encode(prefix, hash.to_base32(), Variant::Bech32)
+        .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+/// Checks the permit was signed by the owner, whose address is derived from `pubkey`
+pub fn verify_permit(
+    api: &dyn Api,
+    payload: &PermitPayload,
+    pubkey: &Binary,
+    signature: &Binary,
+) -> StdResult<()> {
+    let (prefix, _, _) = bech32::decode(&payload.owner)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    if pubkey_to_address(pubkey, &prefix)? != payload.owner {
+        return Err(StdError::generic_err(
+            "The public key doesn't match the permit owner",
+        ));
+    }
+
+    let message_hash = Sha256::digest(to_vec(payload)?);
+    let verified = api
+        .secp256k1_verify(&message_hash, signature, pubkey)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    if !verified {
+        return Err(StdError::generic_err("Invalid permit signature"));
+    }
+    Ok(())
+}
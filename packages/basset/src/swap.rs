@@ -1,16 +1,23 @@
-use crate::state::SWAP_CONFIG;
-use basset::dex_router::{
+use crate::dex_router::{
     AssetInfo, ExecuteMsg as AstroportExecuteMsg, QueryMsg as AstroportQueryMsg,
     SimulateSwapOperationsResponse, SwapMessageType, SwapOperation,
 };
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    to_binary, Coin, CosmosMsg, Deps, Env, QueryRequest, StdResult, Uint128, WasmMsg, WasmQuery,
+    to_binary, Addr, Coin, CosmosMsg, Deps, QueryRequest, StdResult, Uint128, WasmMsg, WasmQuery,
 };
 use cw20::Cw20ExecuteMsg;
 use serde::Serialize;
 use strum::IntoEnumIterator;
 
+/// Routers of the dexes the swaps are routed through
+#[cw_serde]
+pub struct SwapConfig {
+    pub astroport_addr: Addr,
+    pub phoenix_addr: Addr,
+    pub terraswap_addr: Addr,
+}
+
 #[cw_serde]
 pub struct Asset {
     pub amount: Uint128,
@@ -31,44 +38,53 @@ pub fn into_cosmos_msg<M: Serialize, T: Into<String>>(
     Ok(execute.into())
 }
 
+/// Swaps `asset_to_swap` to `reward_denom` on the dex giving the best price.
+/// Returns no message if no dex can make the swap
 pub fn create_swap_msgs(
     deps: Deps,
-    env: Env,
+    swap_config: &SwapConfig,
     asset_to_swap: Asset,
     reward_denom: String,
 ) -> StdResult<Vec<CosmosMsg>> {
-    let best_price_result: Option<(usize, Uint128)> = SwapMessageType::iter()
-        .map(|message_type| {
+    let ask_asset_info = AssetInfo::NativeToken {
+        denom: reward_denom,
+    };
+    match find_best_swap(deps, swap_config, &asset_to_swap, &ask_asset_info) {
+        Some((message_type, best_price)) if !best_price.is_zero() => {
+            Ok(vec![create_swap_message_for(
+                swap_config,
+                asset_to_swap,
+                ask_asset_info,
+                message_type,
+                None,
+                None,
+            )?])
+        }
+        _ => Ok(vec![]),
+    }
+}
+
+/// Returns the dex giving the most `ask_asset_info` tokens for `asset_to_swap`, along with the simulated amount.
+/// Dexes failing to simulate the swap are ignored
+pub fn find_best_swap(
+    deps: Deps,
+    swap_config: &SwapConfig,
+    asset_to_swap: &Asset,
+    ask_asset_info: &AssetInfo,
+) -> Option<(SwapMessageType, Uint128)> {
+    SwapMessageType::iter()
+        .filter_map(|message_type| {
             get_swap_result_for(
                 deps,
+                swap_config,
                 asset_to_swap.clone(),
-                reward_denom.clone(),
+                ask_asset_info.clone(),
                 message_type,
             )
+            .ok()
+            .map(|amount| (message_type, amount))
         })
-        .enumerate()
-        .filter(|(_, best_price)| best_price.is_ok())
-        .map(|(best_price_index, best_price)| (best_price_index, best_price.unwrap()))
-        .max_by_key(|&(_, item)| item);
-
-    if let Some((best_price_index, best_price)) = best_price_result {
-        if best_price == Uint128::zero() {
-            return Ok(vec![]);
-        }
-
-        let best_price_marketplace: SwapMessageType =
-            SwapMessageType::iter().nth(best_price_index).unwrap();
-
-        Ok(vec![create_swap_message_for(
-            deps,
-            env,
-            asset_to_swap,
-            reward_denom,
-            best_price_marketplace,
-        )?])
-    } else {
-        Ok(vec![])
-    }
+        .max_by_key(|&(_, amount)| amount)
 }
 
 // Astroport router smart-contract
@@ -86,7 +102,7 @@ pub fn create_swap_msgs(
 // Testnet : terra1xp6xe6uwqrspumrkazdg90876ns4h78yw03vfxghhcy03yexcrcsdaqvc8
 // Mainnet : terra13ehuhysn5mqjeaheeuew2gjs785f6k7jm8vfsqg3jhtpkwppcmzqcu7chk
 
-fn get_astroport_swap_operation(
+pub fn get_astroport_swap_operation(
     offer_asset_info: AssetInfo,
     ask_asset_info: AssetInfo,
     message_type: SwapMessageType,
@@ -107,58 +123,50 @@ fn get_astroport_swap_operation(
     }
 }
 
-fn get_contract_address(deps: Deps, message_type: SwapMessageType) -> StdResult<String> {
-    let swap_config = SWAP_CONFIG.load(deps.storage)?;
-    Ok(match message_type {
-        SwapMessageType::Astroport => swap_config.astroport_addr,
-        SwapMessageType::Phoenix => swap_config.phoenix_addr,
-        SwapMessageType::TerraSwap => swap_config.terraswap_addr,
+pub fn get_contract_address(swap_config: &SwapConfig, message_type: SwapMessageType) -> String {
+    match message_type {
+        SwapMessageType::Astroport => &swap_config.astroport_addr,
+        SwapMessageType::Phoenix => &swap_config.phoenix_addr,
+        SwapMessageType::TerraSwap => &swap_config.terraswap_addr,
     }
-    .to_string())
+    .to_string()
 }
 
+/// Swaps `asset_to_swap` to `ask_asset_info` on the given dex.
+/// The swapped tokens go to `to`, or to the sender if not specified.
+/// The swap fails if less than `minimum_receive` tokens would be received
 pub fn create_swap_message_for(
-    deps: Deps,
-    _env: Env,
+    swap_config: &SwapConfig,
     asset_to_swap: Asset,
-    stable_denom: String,
+    ask_asset_info: AssetInfo,
     message_type: SwapMessageType,
+    minimum_receive: Option<Uint128>,
+    to: Option<String>,
 ) -> StdResult<CosmosMsg> {
-    let swap_contract_address = get_contract_address(deps, message_type)?;
-    let stable_token = AssetInfo::NativeToken {
-        denom: stable_denom,
+    let swap_contract_address = get_contract_address(swap_config, message_type);
+    let swap_msg = AstroportExecuteMsg::ExecuteSwapOperations {
+        operations: vec![get_astroport_swap_operation(
+            asset_to_swap.asset_info.clone(),
+            ask_asset_info,
+            message_type,
+        )],
+        to,
+        max_spread: None,
+        minimum_receive,
     };
 
-    match asset_to_swap.asset_info.clone() {
+    match asset_to_swap.asset_info {
         AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: contract_addr.to_string(),
             funds: vec![],
             msg: to_binary(&Cw20ExecuteMsg::Send {
                 contract: swap_contract_address,
                 amount: asset_to_swap.amount,
-                msg: to_binary(&AstroportExecuteMsg::ExecuteSwapOperations {
-                    operations: vec![get_astroport_swap_operation(
-                        asset_to_swap.asset_info,
-                        stable_token,
-                        message_type,
-                    )],
-                    to: None,
-                    max_spread: None,
-                    minimum_receive: None,
-                })?,
+                msg: to_binary(&swap_msg)?,
             })?,
         })),
         AssetInfo::NativeToken { denom } => into_cosmos_msg(
-            AstroportExecuteMsg::ExecuteSwapOperations {
-                operations: vec![get_astroport_swap_operation(
-                    asset_to_swap.asset_info.clone(),
-                    stable_token,
-                    message_type,
-                )],
-                to: None,
-                max_spread: None,
-                minimum_receive: None,
-            },
+            swap_msg,
             swap_contract_address,
             vec![Coin {
                 amount: asset_to_swap.amount,
@@ -170,11 +178,12 @@ pub fn create_swap_message_for(
 
 pub fn get_swap_result_for(
     deps: Deps,
+    swap_config: &SwapConfig,
     asset_to_swap: Asset,
-    stable_denom: String,
+    ask_asset_info: AssetInfo,
     message_type: SwapMessageType,
 ) -> StdResult<Uint128> {
-    let contract_address = get_contract_address(deps, message_type)?;
+    let contract_address = get_contract_address(swap_config, message_type);
 
     let swap_operation_response: SimulateSwapOperationsResponse =
         deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
@@ -183,9 +192,7 @@ pub fn get_swap_result_for(
                 offer_amount: asset_to_swap.amount,
                 operations: vec![get_astroport_swap_operation(
                     asset_to_swap.asset_info,
-                    AssetInfo::NativeToken {
-                        denom: stable_denom,
-                    },
+                    ask_asset_info,
                     message_type,
                 )],
             })?,
@@ -1,7 +1,12 @@
-use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::Binary;
 use cosmwasm_std::Decimal;
+use cosmwasm_std::Timestamp;
 use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+
+use crate::dex_router::AssetInfo;
+use crate::swap::SwapConfig;
 use cw20::Expiration;
 use cw20::Logo;
 
@@ -14,10 +19,24 @@ pub enum ExecuteMsg {
         amount: Uint128,
     },
     /// Burn is a base message to destroy tokens forever
+    /// The underlying lsd tokens are sent back to the sender.
+    /// The call fails if less than `min_received` lsd tokens would be sent back
+    /// The lsd tokens go to `recipient`, or to the sender if no recipient is specified
     Burn {
         amount: Uint128,
+        min_received: Option<Uint128>,
+        recipient: Option<String>,
     },
     BurnAll {},
+    /// Burns `amount` wrapper tokens and swaps the underlying lsd tokens to `ask_asset_info` on the dex giving the best price.
+    /// The swapped tokens go to `recipient`, or to the sender if no recipient is specified.
+    /// The call fails if less than `minimum_receive` swapped tokens would be received
+    BurnAndSwap {
+        amount: Uint128,
+        ask_asset_info: AssetInfo,
+        minimum_receive: Uint128,
+        recipient: Option<String>,
+    },
     /// Send is a base message to transfer tokens to a contract and trigger an action
     /// on the receiving contract.
     Send {
@@ -41,6 +60,18 @@ pub enum ExecuteMsg {
         amount: Uint128,
         expires: Option<Expiration>,
     },
+    /// Sets the spender's allowance on the owner's account to `amount`, as signed by the owner.
+    /// The owner signs a `basset::permit::PermitPayload` with the chain id, this contract and its next permit nonce,
+    /// `pubkey` is the compressed secp256k1 public key of the owner
+    Permit {
+        owner: String,
+        spender: String,
+        amount: Uint128,
+        expires: Expiration,
+        nonce: u64,
+        pubkey: Binary,
+        signature: Binary,
+    },
     /// Only with "approval" extension. Transfers amount tokens from owner -> recipient
     /// if `env.sender` has sufficient pre-approval.
     TransferFrom {
@@ -57,21 +88,40 @@ pub enum ExecuteMsg {
         msg: Binary,
     },
     /// Only with "approval" extension. Destroys tokens forever
+    /// The call fails if less than `min_received` lsd tokens would be sent back
+    /// The lsd tokens go to `recipient`, or to the sender if no recipient is specified
     BurnFrom {
         owner: String,
         amount: Uint128,
+        min_received: Option<Uint128>,
+        recipient: Option<String>,
     },
     /// Only with the "mintable" extension. If the contract can transfer enough lsd funds from the caller, creates amount new tokens
     /// and adds to the recipient balance.
+    /// The call fails if more than `max_lsd_in` lsd tokens would be taken from the caller
     Mint {
         recipient: String,
         amount: Uint128,
+        max_lsd_in: Option<Uint128>,
     },
     /// Same as the Mint function but you specify the amount of funds you want to send to the contract instead
+    /// The call fails if less than `min_received` wrapper tokens would be minted
     MintWith {
         recipient: String,
         lsd_amount: Uint128,
+        min_received: Option<Uint128>,
+    },
+    /// Mints wrapper tokens from the native tokens sent along with this message, without acquiring the lsd first.
+    /// The native tokens are bonded at the lsd hub, or swapped for the lsd when it can't be bonded from this chain.
+    /// The wrapper tokens are minted for the lsd tokens received by the wrapper.
+    /// The call fails if less than `min_received` wrapper tokens would be minted
+    MintFromNative {
+        recipient: String,
+        min_received: Option<Uint128>,
     },
+    /// Receives a message of type [`Cw20ReceiveMsg`] from the underlying lsd token
+    /// This allows minting wrapper tokens with a single cw20 `Send`, without any prior allowance
+    Receive(Cw20ReceiveMsg),
     /// Only with the "mintable" extension. The current minter may set
     /// a new minter. Setting the minter to None will remove the
     /// token's minter forever.
@@ -96,6 +146,255 @@ pub enum ExecuteMsg {
     Decompound {
         recipient: Option<String>,
     },
+    /// Only the guardian can call this. Pauses or resumes the mint, burn and decompound operations.
+    /// Unset fields are left unchanged. Transfers are never paused
+    SetPaused {
+        mint: Option<bool>,
+        burn: Option<bool>,
+        decompound: Option<bool>,
+    },
+    /// Only the guardian can call this. Hands over the guardian role, or removes it if no guardian is specified
+    UpdateGuardian {
+        guardian: Option<String>,
+    },
+    /// Only the admin can call this. Limits the total wrapper supply and the amount of wrapper tokens
+    /// that can be minted by a single address during each `mint_window` (in seconds).
+    /// The per-address limit applies to the address paying for the mint, and needs a non-zero window.
+    /// Setting a limit to None removes it
+    UpdateMintLimits {
+        max_supply: Option<Uint128>,
+        max_mint_per_address: Option<Uint128>,
+        mint_window: u64,
+    },
+    /// Only the admin can call this. Sets the maximum decompound ratio per year, no ratio removes the decompound limit.
+    /// The decompound history is kept unless `reset_state` is set, in which case the limit accrues again from now.
+    /// Only available on limited wrappers
+    UpdateDecompoundConfig {
+        max_decompound_ratio: Option<Decimal>,
+        reset_state: bool,
+    },
+    /// Only the admin can call this. Proposes a new admin, who has `expires_in` seconds to claim the role
+    ProposeNewAdmin {
+        admin: String,
+        expires_in: u64,
+    },
+    /// Only the admin can call this. Cancels the pending admin proposal
+    DropAdminProposal {},
+    /// Only the proposed admin can call this. Accepts the admin role
+    ClaimAdmin {},
+    /// Only the admin can call this. Changes the contract allowed to call `Decompound`
+    UpdateHub {
+        hub_contract: String,
+    },
+    /// Only the admin can call this. Replaces the lsd config of the wrapper.
+    /// `lsd_config` is the json encoded config, in the same format as in the instantiate message.
+    /// The lsd token of the new config has to be the same as the current one
+    UpdateLsdConfig {
+        lsd_config: Binary,
+    },
+    /// Only the admin can call this. Adds the lsd tokens held by the wrapper on top of its ledger to the ledger,
+    /// so that they are released as rewards by the next `Decompound`
+    AddSurplusToRewards {},
+    /// Only the admin can call this. Sends the lsd tokens held by the wrapper on top of its ledger to `recipient`,
+    /// or to the admin if no recipient is specified
+    SweepSurplus {
+        recipient: Option<String>,
+    },
+    /// Anyone can call this. Trips the circuit breaker if the lsd exchange rate moved more than allowed
+    /// since the reference rate. Mint, burn and decompound are then rejected until the admin updates the circuit breaker
+    CheckLsdExchangeRate {},
+    /// Only the admin can call this. Sets the maximum relative change of the lsd exchange rate per hour,
+    /// resets the circuit breaker and takes the current lsd exchange rate as reference.
+    /// Setting no maximum change disables the circuit breaker
+    UpdateCircuitBreaker {
+        max_change_per_hour: Option<Decimal>,
+    },
+    /// Only the admin can call this. Sets the dex routers `BurnAndSwap` routes the lsd tokens through
+    UpdateSwapConfig {
+        astroport_addr: String,
+        phoenix_addr: String,
+        terraswap_addr: String,
+    },
+    /// Only the admin can call this. Sets the recipients taking a share of the decompounded rewards, such as a treasury.
+    /// The rest of the rewards goes to the `Decompound` recipient
+    UpdateRewardSplit {
+        recipients: Vec<RewardRecipient>,
+    },
+    /// Only the admin can call this. Sets the share of the rewards kept in the wrapper on each `Decompound`.
+    /// 0 releases all the rewards, 1 makes the wrapper exchange rate accumulate all of them
+    UpdateRetentionRatio {
+        retention_ratio: Decimal,
+    },
+    /// Lends `amount` lsd tokens to the sender and calls it back with `msg`, through a cw20 `Send` for cw20 lsds.
+    /// The lsd tokens have to be sent back along with the flash loan fee within the same transaction.
+    /// The fee is rounded up and added to the rewards released by the next `Decompound`.
+    /// Flash loans are paused along with burns
+    FlashLoan {
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Only the wrapper itself can call this. Checks the last flash loan was paid back with its fee
+    FinishFlashLoan {},
+    /// Only the admin can call this. Sets the fee charged on the lent lsd tokens.
+    /// Setting no fee disables flash loans
+    UpdateFlashLoanFee {
+        fee: Option<Decimal>,
+    },
+}
+
+/// This structure describes the hooks that can be attached to a cw20 `Send` of the underlying lsd token,
+/// or to a cw20 `Send` of the wrapper token to the wrapper itself
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Mints wrapper tokens in exchange of all the lsd tokens sent along with this message
+    /// The minted tokens go to the cw20 sender if no recipient is specified
+    /// The call fails if less than `min_received` wrapper tokens would be minted
+    Mint {
+        recipient: Option<String>,
+        min_received: Option<Uint128>,
+    },
+    /// Burns all the wrapper tokens sent along with this message
+    /// The lsd tokens go to the cw20 sender if no recipient is specified.
+    /// If `msg` is specified, the lsd tokens are sent to the recipient contract which executes `msg`, as with a cw20 `Send`
+    /// The call fails if less than `min_received` lsd tokens would be sent
+    Unwrap {
+        recipient: Option<String>,
+        msg: Option<Binary>,
+        min_received: Option<Uint128>,
+    },
+}
+
+#[cw_serde]
+#[cfg_attr(feature="interface", derive(cw_orch::QueryFns))]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the current balance of the given address, 0 if unset.
+    #[returns(cw20::BalanceResponse)]
+    Balance { address: String },
+    /// Returns the balance of the given address at the end of `height`, or the current balance if no height is specified.
+    /// Only heights since the wrapper started recording snapshots are available
+    #[returns(cw20::BalanceResponse)]
+    BalanceAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+    /// Returns the total supply at the end of `height`, or the current total supply if no height is specified.
+    /// Only heights since the wrapper started recording snapshots are available
+    #[returns(TotalSupplyResponse)]
+    TotalSupplyAtHeight { height: Option<u64> },
+    /// Returns metadata on the contract - name, decimals, supply, etc.
+    #[returns(cw20::TokenInfoResponse)]
+    TokenInfo {},
+    /// Returns who can mint and the hard cap on maximum tokens after minting.
+    #[returns(cw20::MinterResponse)]
+    Minter {},
+    /// Returns how much spender can use from owner account, 0 if unset.
+    #[returns(cw20::AllowanceResponse)]
+    Allowance { owner: String, spender: String },
+    /// Returns all allowances this owner has approved. Supports pagination.
+    #[returns(cw20::AllAllowancesResponse)]
+    AllAllowances {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns all allowances this spender has been granted. Supports pagination.
+    #[returns(cw20::AllSpenderAllowancesResponse)]
+    AllSpenderAllowances {
+        spender: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns all accounts that have balances. Supports pagination.
+    #[returns(cw20::AllAccountsResponse)]
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns more metadata on the contract to display in the client:
+    /// - description, logo, project url, etc.
+    #[returns(cw20::MarketingInfoResponse)]
+    MarketingInfo {},
+    /// Downloads the embedded logo data (if stored on chain). Errors if no logo data is stored for this
+    /// contract.
+    #[returns(cw20::DownloadLogoResponse)]
+    DownloadLogo {},
+
+    /// Wrapper specific queries
+    /// Returns the amount of lsd tokens pulled from the caller by `ExecuteMsg::Mint` when minting `amount` wrapper tokens
+    #[returns(SimulationResponse)]
+    SimulateMint { amount: Uint128 },
+    /// Returns the amount of wrapper tokens minted by `ExecuteMsg::MintWith` when depositing `lsd_amount` lsd tokens
+    #[returns(SimulationResponse)]
+    SimulateMintWith { lsd_amount: Uint128 },
+    /// Returns the amount of lsd tokens sent back by `ExecuteMsg::Burn` when burning `amount` wrapper tokens
+    #[returns(SimulationResponse)]
+    SimulateBurn { amount: Uint128 },
+    /// Returns the internal accounting state of the wrapper and its current exchange rate
+    #[returns(WrapperStateResponse)]
+    WrapperState {},
+    /// Returns the rewards that would be released by `ExecuteMsg::Decompound` at the current block
+    #[returns(AccruedRewardsResponse)]
+    AccruedRewards {},
+    /// Returns the decompound limit of the wrapper. Only available on limited wrappers
+    #[returns(DecompoundConfigResponse)]
+    DecompoundConfig {},
+    /// Returns the decompound totals since the decompound limit started accruing. Only available on limited wrappers.
+    /// The limit over the trailing window is returned by `DecompoundBudget`
+    #[returns(DecompoundStateResponse)]
+    DecompoundState {},
+    /// Returns the share of the wrapper backing the decompound can still release over the trailing window.
+    /// Only available on limited wrappers
+    #[returns(DecompoundBudgetResponse)]
+    DecompoundBudget {},
+    /// Returns the exchange rates recorded on mint, burn and decompound, from the most recent to the oldest
+    #[returns(ExchangeRatesResponse)]
+    ExchangeRates {
+        // start after the provided timestamp in s
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the guardian and the operations it has paused
+    #[returns(PauseStateResponse)]
+    PauseState {},
+    /// Returns the admin, the pending admin proposal and the hub contract of the wrapper
+    #[returns(AdminResponse)]
+    Admin {},
+    /// Returns the admin and the mint limits of the wrapper
+    #[returns(MintLimitsResponse)]
+    MintLimits {},
+    /// Returns the lsd tokens accounted for by the wrapper and the lsd tokens it actually holds
+    #[returns(LsdLedgerResponse)]
+    LsdLedger {},
+    /// Returns the circuit breaker on the lsd exchange rate
+    #[returns(CircuitBreakerResponse)]
+    CircuitBreaker {},
+    /// Returns the dex routers used by `ExecuteMsg::BurnAndSwap`, if set
+    #[returns(SwapConfigResponse)]
+    SwapConfig {},
+    /// Returns the current shortfall of the wrapper backing and the last slashing loss recorded
+    #[returns(SlashingLossResponse)]
+    SlashingLoss {},
+    /// Returns the split of the decompounded rewards
+    #[returns(RewardSplitResponse)]
+    RewardSplit {},
+    /// Returns the share of the rewards kept in the wrapper and the exchange rate the wrapper is brought back to
+    #[returns(RetentionResponse)]
+    Retention {},
+    /// Returns the nonce the next permit of `owner` has to be signed with
+    #[returns(PermitNonceResponse)]
+    PermitNonce { owner: String },
+    /// Returns the fee charged on flash loans, flash loans are disabled if unset
+    #[returns(FlashLoanFeeResponse)]
+    FlashLoanFee {},
+}
+
+#[cw_serde]
+pub struct SimulationResponse {
+    /// Amount of lsd tokens pulled from the caller (mint) or sent back to the caller (burn)
+    pub lsd_amount: Uint128,
+    /// Amount of wrapper tokens minted or burned
+    pub wrapper_amount: Uint128,
 }
 
 #[derive(Default)]
@@ -103,6 +402,8 @@ pub enum ExecuteMsg {
 pub struct AccruedRewards {
     pub luna_rewards: Uint128,
     pub lsd_rewards: Uint128,
+    /// Exchange rate of the wrapper once the rewards are decompounded
+    pub target_exchange_rate: Decimal,
 }
 
 #[derive(Default)]
@@ -111,23 +412,192 @@ pub struct AccruedRewardsLimited {
     pub rate_decrease: Decimal,
     pub luna_rewards: Uint128,
     pub lsd_rewards: Uint128,
+    /// Exchange rate the wrapper is brought back to, without the decompound limit
+    pub target_exchange_rate: Decimal,
 }
 
 #[cw_serde]
-pub struct TokenInfoResponse {
-    pub name: String,
-    pub symbol: String,
-    pub decimals: u8,
-    pub total_supply: Uint128,
+pub struct WrapperStateResponse {
+    /// Exchange rate between the underlying token and the lsd, as reported by the lsd hub
+    pub lsd_exchange_rate: Decimal,
+    /// Amount of lsd tokens deposited in the wrapper, as recorded by its ledger
+    pub lsd_balance: Uint128,
+    /// Amount of underlying tokens backing the wrapper supply, rounded down to a whole token as in the mint and burn math
+    pub backing_luna: Decimal,
+    /// Total supply of the wrapper token
+    pub wlsd_supply: Uint128,
+    /// Exchange rate between the underlying token and the wrapper token
     pub exchange_rate: Decimal,
+    /// Exchange rate with the current decompound limit applied. Only set on limited wrappers
+    pub expected_exchange_rate: Option<Decimal>,
 }
 
 #[cw_serde]
-pub struct TokenInfoResponseWithLimit {
-    pub name: String,
-    pub symbol: String,
-    pub decimals: u8,
-    pub total_supply: Uint128,
+pub struct AccruedRewardsResponse {
+    pub luna_rewards: Uint128,
+    pub lsd_rewards: Uint128,
+    /// Decrease of the wrapper exchange rate caused by the decompound. Only set on limited wrappers
+    pub rate_decrease: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct DecompoundConfigResponse {
+    pub max_decompound_ratio: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct DecompoundStateResponse {
+    /// Sum of the exchange rate decreases caused by the decompounds since the limit started accruing
+    pub ratio_sum: Decimal,
+    /// Seconds elapsed between the start of the limit and the last decompound
+    pub total_seconds: u64,
+    pub last_decompound: Timestamp,
+}
+
+#[cw_serde]
+pub struct DecompoundBudgetResponse {
+    /// Length of the trailing window the decompound limit is enforced over, in seconds
+    pub window_seconds: u64,
+    /// Decrease of the wrapper exchange rate caused by the decompounds of the window
+    pub released_ratio: Decimal,
+    /// Decrease of the wrapper exchange rate the decompound can still cause. Unset without a decompound limit
+    pub remaining_ratio: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct ExchangeRateEntry {
+    /// Block time of the snapshot in seconds
+    pub time: u64,
+    /// Exchange rate between the underlying token and the wrapper token
     pub exchange_rate: Decimal,
-    pub expected_exchange_rate: Decimal
+    /// Exchange rate between the underlying token and the lsd
+    pub lsd_exchange_rate: Decimal,
+}
+
+#[cw_serde]
+pub struct ExchangeRatesResponse {
+    pub exchange_rates: Vec<ExchangeRateEntry>,
+    /// Yearly APR of the wrapper token, computed over the returned snapshots
+    pub apr: Option<Decimal>,
+    /// Yearly APR of the lsd, computed over the returned snapshots
+    pub lsd_apr: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct PauseStateResponse {
+    pub guardian: Option<String>,
+    pub mint: bool,
+    pub burn: bool,
+    pub decompound: bool,
+}
+
+#[cw_serde]
+pub struct MintLimitsResponse {
+    pub admin: Option<String>,
+    /// Maximum total supply of the wrapper token
+    pub max_supply: Option<Uint128>,
+    /// Maximum amount of wrapper tokens minted by a single address during each mint window
+    pub max_mint_per_address: Option<Uint128>,
+    /// Length of the mint window in seconds
+    pub mint_window: u64,
+}
+
+#[cw_serde]
+pub struct AdminResponse {
+    pub admin: Option<String>,
+    pub proposed_admin: Option<String>,
+    /// Block time (in seconds) after which the admin proposal can't be claimed anymore
+    pub proposal_expiry: Option<u64>,
+    pub hub_contract: String,
+}
+
+#[cw_serde]
+pub struct LsdLedgerResponse {
+    /// Amount of lsd tokens deposited through mint, net of the burns and decompounds
+    pub ledger: Uint128,
+    /// Amount of lsd tokens actually held by the wrapper
+    pub lsd_balance: Uint128,
+    /// Lsd tokens held on top of the ledger, that can be added to the rewards or swept by the admin
+    pub surplus: Uint128,
+    /// Lsd tokens missing from the balance of the wrapper compared to the ledger
+    pub deficit: Uint128,
+}
+
+#[cw_serde]
+pub struct SwapConfigResponse {
+    pub swap_config: Option<SwapConfig>,
+}
+
+#[cw_serde]
+pub struct RewardRecipient {
+    pub address: String,
+    /// Share of the decompounded rewards sent to `address`
+    pub ratio: Decimal,
+}
+
+#[cw_serde]
+pub struct RewardSplitResponse {
+    pub recipients: Vec<RewardRecipient>,
+    /// Share of the decompounded rewards left to the `Decompound` recipient
+    pub decompound_recipient_ratio: Decimal,
+}
+
+#[cw_serde]
+pub struct RetentionResponse {
+    /// Share of the rewards kept in the wrapper on each decompound
+    pub retention_ratio: Decimal,
+    /// Exchange rate the decompound brings the wrapper back to, grows with the retained rewards
+    pub target_exchange_rate: Decimal,
+}
+
+/// Shortfall of the wrapper backing, recorded on mint, burn and decompound
+#[cw_serde]
+pub struct SlashingLoss {
+    /// Block time (in seconds) at which the loss was recorded
+    pub time: u64,
+    /// Exchange rate between the underlying token and the wrapper token, below the target exchange rate
+    pub exchange_rate: Decimal,
+    /// Amount of underlying tokens missing for the wrapper supply to be fully backed
+    pub luna_loss: Uint128,
+}
+
+#[cw_serde]
+pub struct SlashingLossResponse {
+    /// Whether the wrapper supply is currently backed by less than the target exchange rate
+    pub under_collateralized: bool,
+    /// Amount of underlying tokens each burned wrapper token currently redeems.
+    /// Wrappers minting at a fixed rate redeem at most the target exchange rate, share based wrappers redeem their whole backing
+    pub redemption_rate: Decimal,
+    /// Amount of underlying tokens currently missing for the wrapper supply to be fully backed
+    pub luna_loss: Uint128,
+    /// Last loss recorded while the wrapper was under-collateralized
+    pub last_loss: Option<SlashingLoss>,
+}
+
+#[cw_serde]
+pub struct TotalSupplyResponse {
+    pub total_supply: Uint128,
+}
+
+#[cw_serde]
+pub struct PermitNonceResponse {
+    pub nonce: u64,
+}
+
+#[cw_serde]
+pub struct FlashLoanFeeResponse {
+    /// Fee charged on the lent lsd tokens
+    pub fee: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct CircuitBreakerResponse {
+    /// Maximum relative change of the lsd exchange rate per hour, the circuit breaker is disabled if unset
+    pub max_change_per_hour: Option<Decimal>,
+    /// Lsd exchange rate the readings are compared to
+    pub reference_rate: Option<Decimal>,
+    /// Block time (in seconds) at which the reference rate was accepted
+    pub reference_time: u64,
+    /// Whether mint, burn and decompound are rejected until the admin updates the circuit breaker
+    pub tripped: bool,
 }
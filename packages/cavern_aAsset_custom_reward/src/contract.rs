@@ -2,9 +2,6 @@ use std::collections::HashSet;
 
 use cosmwasm_std::{Addr, StdError};
 
-#[cfg(not(feature = "library"))]
-use cosmwasm_std::entry_point;
-
 use crate::global::{execute_retrieve_normal_tokens, execute_swap};
 use crate::state::{
     read_config, store_config, store_retrieve_config, store_state, Config, State, SwapConfig,
@@ -12,12 +9,14 @@ use crate::state::{
 };
 use crate::user::{execute_claim_rewards, query_accrued_rewards};
 use cosmwasm_std::{
-    to_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+    attr, to_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
     Uint128,
 };
 
 use basset::custom_reward::{ExecuteWithSwapReply, InstantiateMsg};
+use basset::migrate::assert_can_migrate;
 use basset::reward::{ExecuteMsg, MigrateMsg, QueryMsg};
+use cw2::{set_contract_version, CONTRACT};
 
 fn has_unique_elements(list: &[String]) -> bool {
     let mut uniq = HashSet::new();
@@ -190,7 +189,33 @@ fn query_config(deps: Deps) -> StdResult<Config> {
     Ok(config)
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+/// Migrates the reward contract to `contract_name` at `contract_version`
+pub fn migrate(
+    deps: DepsMut,
+    _env: Env,
+    _msg: MigrateMsg,
+    contract_name: &str,
+    contract_version: &str,
+) -> StdResult<Response> {
+    // Contracts instantiated before they recorded their version don't have any
+    let previous = CONTRACT.may_load(deps.storage)?;
+    if let Some(previous) = &previous {
+        assert_can_migrate(
+            &previous.contract,
+            &previous.version,
+            contract_name,
+            contract_version,
+            &[],
+        )?;
+    }
+    set_contract_version(deps.storage, contract_name, contract_version)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr(
+            "previous_version",
+            previous.map(|previous| previous.version).unwrap_or_default(),
+        ),
+        attr("contract_version", contract_version),
+    ]))
 }
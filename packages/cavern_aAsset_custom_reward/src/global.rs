@@ -3,7 +3,7 @@ use crate::querier::query_all_cw20_balances;
 use crate::swap::Asset;
 use cosmwasm_std::{Coin, ReplyOn, SubMsg};
 
-use crate::state::read_config;
+use crate::state::{read_config, SWAP_CONFIG};
 
 use basset::{custom_reward::ExecuteWithSwapReply, dex_router::AssetInfo};
 use cosmwasm_std::{attr, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
@@ -52,6 +52,7 @@ pub fn execute_swap(deps: DepsMut, env: Env) -> StdResult<Response> {
     let balances = deps.querier.query_all_balances(contr_addr)?;
 
     let reward_denom = config.clone().reward_denom;
+    let swap_config = SWAP_CONFIG.load(deps.storage)?;
 
     let native_swap_messages: Vec<CosmosMsg> = balances
         .iter()
@@ -59,7 +60,7 @@ pub fn execute_swap(deps: DepsMut, env: Env) -> StdResult<Response> {
         .map(|coin: &Coin| {
             create_swap_msgs(
                 deps.as_ref(),
-                env.clone(),
+                &swap_config,
                 Asset {
                     asset_info: AssetInfo::NativeToken {
                         denom: coin.denom.clone(),
@@ -87,7 +88,7 @@ pub fn execute_swap(deps: DepsMut, env: Env) -> StdResult<Response> {
         .map(|asset: &Asset| {
             create_swap_msgs(
                 deps.as_ref(),
-                env.clone(),
+                &swap_config,
                 Asset {
                     asset_info: asset.asset_info.clone(),
                     amount: asset.amount,
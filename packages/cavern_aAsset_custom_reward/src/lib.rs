@@ -3,7 +3,7 @@ pub mod querier;
 pub mod state;
 
 pub mod global;
-pub mod swap;
+pub use basset::swap;
 mod user;
 
 #[cfg(test)]
@@ -12,12 +12,7 @@ pub const HOLDERS: Map<&Addr, Holder> = Map::new("holders");
 // New mecanism specific variables
 pub const SWAP_CONFIG: Item<SwapConfig> = Item::new("swap_config");
 
-#[cw_serde]
-pub struct SwapConfig {
-    pub astroport_addr: Addr,
-    pub phoenix_addr: Addr,
-    pub terraswap_addr: Addr,
-}
+pub use basset::swap::SwapConfig;
 // End
 
 #[cw_serde]
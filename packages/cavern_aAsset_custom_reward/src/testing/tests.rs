@@ -23,7 +23,9 @@ use cosmwasm_std::{coins, Empty, StdError};
 use cosmwasm_std::{from_binary, BankMsg, Coin, CosmosMsg, SubMsg, Uint128};
 
 use crate::contract::{execute, instantiate, migrate, query};
+use cw2::get_contract_version;
 
+use crate::state::SWAP_CONFIG;
 use crate::swap::{create_swap_msgs, Asset};
 use crate::testing::mock_querier::{mock_dependencies, MOCK_HUB_CONTRACT_ADDR};
 use basset::custom_reward::InstantiateMsg;
@@ -106,7 +108,7 @@ pub fn swap_to_reward_denom() {
             SubMsg::new(
                 create_swap_msgs(
                     deps.as_ref(),
-                    mock_env(),
+                    &SWAP_CONFIG.load(deps.as_ref().storage).unwrap(),
                     Asset {
                         amount: Uint128::new(1000u128),
                         asset_info: AssetInfo::NativeToken {
@@ -121,7 +123,7 @@ pub fn swap_to_reward_denom() {
             SubMsg::new(
                 create_swap_msgs(
                     deps.as_ref(),
-                    mock_env(),
+                    &SWAP_CONFIG.load(deps.as_ref().storage).unwrap(),
                     Asset {
                         amount: Uint128::new(50u128),
                         asset_info: AssetInfo::NativeToken {
@@ -136,7 +138,7 @@ pub fn swap_to_reward_denom() {
             SubMsg::new(
                 create_swap_msgs(
                     deps.as_ref(),
-                    mock_env(),
+                    &SWAP_CONFIG.load(deps.as_ref().storage).unwrap(),
                     Asset {
                         amount: Uint128::new(50u128),
                         asset_info: AssetInfo::NativeToken {
@@ -270,7 +272,23 @@ fn test_migrate() {
         amount: Uint128::new(100u128),
     }]);
 
+    // Contracts instantiated before they recorded their version can be migrated
     migrate(deps.as_mut(), mock_env(), MigrateMsg {
         max_decompound_ratio: None
-    }).unwrap();
+    }, "crates.io:reward", "1.1.0").unwrap();
+    let version = get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.contract, "crates.io:reward");
+    assert_eq!(version.version, "1.1.0");
+
+    // Upgrades are allowed, downgrades are not
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {
+        max_decompound_ratio: None
+    }, "crates.io:reward", "1.2.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {
+        max_decompound_ratio: None
+    }, "crates.io:reward", "1.1.0").unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Can't downgrade crates.io:reward from 1.2.0 to 1.1.0")
+    );
 }
@@ -0,0 +1,259 @@
+use crate::handler::*;
+use crate::msg::{NativeMigrateMsg, NativeTokenInitMsg};
+use crate::state::DENOM;
+use crate::token_factory::{factory_denom, into_custom_response, TokenFactoryMsg};
+
+use basset::migrate::{assert_can_migrate, CW20_BASE_CONTRACT_NAME};
+use basset::native_wrapper::{DenomResponse, ExecuteMsg, QueryMsg};
+use basset::wrapper::{ExecuteMsg as WrapperExecuteMsg, QueryMsg as WrapperQueryMsg};
+use cavern_lsd_wrapper_token_with_limit::contract::{
+    execute as wrapper_execute, instantiate as wrapper_instantiate,
+    migrate_wrapper_state, query as wrapper_query,
+};
+use cavern_lsd_wrapper_token_with_limit::msg::{MigrateMsg, TokenInitMsg};
+use cavern_lsd_wrapper_token_with_limit::trait_def::LSDHub;
+use cosmwasm_std::{
+    attr, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw20_base::ContractError;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Creates the denom of the wrapper and saves it
+fn create_denom(
+    deps: DepsMut,
+    env: &Env,
+    subdenom: String,
+) -> StdResult<CosmosMsg<TokenFactoryMsg>> {
+    DENOM.save(
+        deps.storage,
+        &factory_denom(env.contract.address.as_str(), &subdenom),
+    )?;
+    Ok(CosmosMsg::Custom(TokenFactoryMsg::CreateDenom { subdenom }))
+}
+
+pub fn instantiate<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: NativeTokenInitMsg<I>,
+) -> StdResult<Response<TokenFactoryMsg>> {
+    // The native wrapper shares the state of the cw20 wrappers, its supply is recorded in the cw20 token info
+    wrapper_instantiate::<I, T>(
+        deps.branch(),
+        env.clone(),
+        info,
+        TokenInitMsg {
+            types: None,
+            name: msg.name,
+            symbol: msg.symbol,
+            decimals: msg.decimals,
+            initial_balances: vec![],
+            max_decompound_ratio: msg.max_decompound_ratio,
+            hub_contract: msg.hub_contract,
+            guardian: msg.guardian,
+            admin: msg.admin,
+            lsd_config: msg.lsd_config,
+        },
+    )?;
+    let create_msg = create_denom(deps.branch(), &env, msg.subdenom)?;
+
+    Ok(Response::new()
+        .add_message(create_msg)
+        .add_attribute("denom", DENOM.load(deps.storage)?))
+}
+
+pub fn execute<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + for<'a> Deserialize<'a> + Serialize,
+>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    // The operations that don't move the native denom are handled as for the cw20 wrappers
+    let wrapper_msg = match msg {
+        ExecuteMsg::Mint {
+            recipient,
+            amount,
+            max_lsd_in,
+        } => return execute_mint::<I, T>(deps, env, info, recipient, amount, max_lsd_in),
+        ExecuteMsg::MintWith {
+            recipient,
+            lsd_amount,
+            min_received,
+        } => {
+            return execute_mint_with::<I, T>(deps, env, info, recipient, lsd_amount, min_received)
+        }
+        ExecuteMsg::Receive(msg) => return execute_receive::<I, T>(deps, env, info, msg),
+        ExecuteMsg::Burn {
+            recipient,
+            min_received,
+        } => return execute_burn::<I, T>(deps, env, info, recipient, min_received),
+        ExecuteMsg::ConvertCw20 { amount, recipient } => {
+            return execute_convert_cw20(deps, env, info, amount, recipient)
+        }
+        ExecuteMsg::Decompound { recipient } => WrapperExecuteMsg::Decompound { recipient },
+        ExecuteMsg::SetPaused {
+            mint,
+            burn,
+            decompound,
+        } => WrapperExecuteMsg::SetPaused {
+            mint,
+            burn,
+            decompound,
+        },
+        ExecuteMsg::UpdateGuardian { guardian } => WrapperExecuteMsg::UpdateGuardian { guardian },
+        ExecuteMsg::UpdateMintLimits {
+            max_supply,
+            max_mint_per_address,
+            mint_window,
+        } => WrapperExecuteMsg::UpdateMintLimits {
+            max_supply,
+            max_mint_per_address,
+            mint_window,
+        },
+        ExecuteMsg::UpdateDecompoundConfig {
+            max_decompound_ratio,
+            reset_state,
+        } => WrapperExecuteMsg::UpdateDecompoundConfig {
+            max_decompound_ratio,
+            reset_state,
+        },
+        ExecuteMsg::ProposeNewAdmin { admin, expires_in } => {
+            WrapperExecuteMsg::ProposeNewAdmin { admin, expires_in }
+        }
+        ExecuteMsg::DropAdminProposal {} => WrapperExecuteMsg::DropAdminProposal {},
+        ExecuteMsg::ClaimAdmin {} => WrapperExecuteMsg::ClaimAdmin {},
+        ExecuteMsg::UpdateHub { hub_contract } => WrapperExecuteMsg::UpdateHub { hub_contract },
+        ExecuteMsg::UpdateLsdConfig { lsd_config } => {
+            WrapperExecuteMsg::UpdateLsdConfig { lsd_config }
+        }
+        ExecuteMsg::AddSurplusToRewards {} => WrapperExecuteMsg::AddSurplusToRewards {},
+        ExecuteMsg::SweepSurplus { recipient } => WrapperExecuteMsg::SweepSurplus { recipient },
+        ExecuteMsg::CheckLsdExchangeRate {} => WrapperExecuteMsg::CheckLsdExchangeRate {},
+        ExecuteMsg::UpdateCircuitBreaker {
+            max_change_per_hour,
+        } => WrapperExecuteMsg::UpdateCircuitBreaker {
+            max_change_per_hour,
+        },
+        ExecuteMsg::UpdateRewardSplit { recipients } => {
+            WrapperExecuteMsg::UpdateRewardSplit { recipients }
+        }
+        ExecuteMsg::UpdateRetentionRatio { retention_ratio } => {
+            WrapperExecuteMsg::UpdateRetentionRatio { retention_ratio }
+        }
+    };
+
+    let res = wrapper_execute::<I, T>(deps, env, info, wrapper_msg)?;
+    Ok(into_custom_response(res)?)
+}
+
+pub fn query<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: Deps,
+    env: Env,
+    msg: QueryMsg,
+) -> StdResult<Binary> {
+    let wrapper_msg = match msg {
+        QueryMsg::Denom {} => {
+            return to_binary(&DenomResponse {
+                denom: DENOM.load(deps.storage)?,
+            })
+        }
+        QueryMsg::TokenInfo {} => WrapperQueryMsg::TokenInfo {},
+        QueryMsg::Cw20Balance { address } => WrapperQueryMsg::Balance { address },
+        QueryMsg::Cw20BalanceAtHeight { address, height } => {
+            WrapperQueryMsg::BalanceAtHeight { address, height }
+        }
+        QueryMsg::TotalSupplyAtHeight { height } => {
+            WrapperQueryMsg::TotalSupplyAtHeight { height }
+        }
+        QueryMsg::SimulateMint { amount } => WrapperQueryMsg::SimulateMint { amount },
+        QueryMsg::SimulateMintWith { lsd_amount } => {
+            WrapperQueryMsg::SimulateMintWith { lsd_amount }
+        }
+        QueryMsg::SimulateBurn { amount } => WrapperQueryMsg::SimulateBurn { amount },
+        QueryMsg::WrapperState {} => WrapperQueryMsg::WrapperState {},
+        QueryMsg::AccruedRewards {} => WrapperQueryMsg::AccruedRewards {},
+        QueryMsg::DecompoundConfig {} => WrapperQueryMsg::DecompoundConfig {},
+        QueryMsg::DecompoundState {} => WrapperQueryMsg::DecompoundState {},
+        QueryMsg::DecompoundBudget {} => WrapperQueryMsg::DecompoundBudget {},
+        QueryMsg::ExchangeRates { start_after, limit } => {
+            WrapperQueryMsg::ExchangeRates { start_after, limit }
+        }
+        QueryMsg::PauseState {} => WrapperQueryMsg::PauseState {},
+        QueryMsg::Admin {} => WrapperQueryMsg::Admin {},
+        QueryMsg::MintLimits {} => WrapperQueryMsg::MintLimits {},
+        QueryMsg::LsdLedger {} => WrapperQueryMsg::LsdLedger {},
+        QueryMsg::CircuitBreaker {} => WrapperQueryMsg::CircuitBreaker {},
+        QueryMsg::RewardSplit {} => WrapperQueryMsg::RewardSplit {},
+        QueryMsg::Retention {} => WrapperQueryMsg::Retention {},
+        QueryMsg::SlashingLoss {} => WrapperQueryMsg::SlashingLoss {},
+    };
+    wrapper_query::<I, T>(deps, env, wrapper_msg)
+}
+
+/// Migrates the wrapper to `contract_name` at `contract_version`.
+/// A cw20 wrapper listed in `cw20_contracts` can be migrated in place to the native wrapper: it keeps its lsd tokens and its supply,
+/// and its holders convert their cw20 tokens 1:1 with `ExecuteMsg::ConvertCw20`
+pub fn migrate<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    msg: NativeMigrateMsg,
+    contract_name: &str,
+    contract_version: &str,
+    cw20_contracts: &[&str],
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let previous = get_contract_version(deps.storage)?;
+    let legacy_contracts: Vec<&str> = cw20_contracts
+        .iter()
+        .copied()
+        .chain([CW20_BASE_CONTRACT_NAME])
+        .collect();
+    assert_can_migrate(
+        &previous.contract,
+        &previous.version,
+        contract_name,
+        contract_version,
+        &legacy_contracts,
+    )?;
+
+    migrate_wrapper_state::<I, T>(
+        deps.branch(),
+        &env,
+        MigrateMsg {
+            admin: msg.admin,
+            guardian: msg.guardian,
+            max_decompound_ratio: msg.max_decompound_ratio,
+        },
+    )?;
+
+    let mut res = Response::new();
+    if DENOM.may_load(deps.storage)?.is_none() {
+        let subdenom = msg.subdenom.ok_or_else(|| {
+            StdError::generic_err("A subdenom is needed to migrate a cw20 wrapper")
+        })?;
+        res = res.add_message(create_denom(deps.branch(), &env, subdenom)?);
+    }
+    set_contract_version(deps.storage, contract_name, contract_version)?;
+
+    Ok(res.add_attributes(vec![
+        attr("action", "migrate"),
+        attr("previous_contract", previous.contract),
+        attr("previous_version", previous.version),
+        attr("contract_version", contract_version),
+        attr("denom", DENOM.load(deps.storage)?),
+    ]))
+}
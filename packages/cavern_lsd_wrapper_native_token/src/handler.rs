@@ -0,0 +1,283 @@
+use crate::state::DENOM;
+use crate::token_factory::{into_custom_msgs, TokenFactoryMsg};
+use basset::wrapper::Cw20HookMsg;
+use cavern_lsd_wrapper_token_with_limit::handler::{
+    _before_mint, assert_lsd_rate_guard, assert_max_lsd_in, assert_min_received,
+    assert_not_paused, compute_burn_lsd_amount, compute_mint_lsd_amount,
+    compute_mint_with_amount, record_exchange_rate,
+};
+use cavern_lsd_wrapper_token_with_limit::state::{
+    decrease_lsd_ledger, increase_lsd_ledger, read_lsd_config, read_pause_state,
+    record_snapshots, start_balance_snapshots, start_supply_snapshots,
+};
+use cavern_lsd_wrapper_token_with_limit::trait_def::LSDHub;
+use cosmwasm_std::{
+    attr, from_binary, Addr, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError, Uint128,
+};
+use cw20::Cw20ReceiveMsg;
+use cw20_base::state::{BALANCES, TOKEN_INFO};
+use cw20_base::ContractError;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Mints `amount` native wrapper tokens to `recipient`, paid for by `payer`.
+/// The native supply is tracked in the cw20 token info, so the supply cap and the exchange rate work as for the cw20 wrappers
+fn mint_tokens(
+    mut deps: DepsMut,
+    env: &Env,
+    payer: &Addr,
+    recipient: &str,
+    amount: Uint128,
+) -> Result<CosmosMsg<TokenFactoryMsg>, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let recipient = deps.api.addr_validate(recipient)?;
+    _before_mint(deps.branch(), env, payer, amount)?;
+
+    // The native balances are held by the bank module, only the supply is snapshotted
+    start_supply_snapshots(deps.storage)?;
+    let mut token_info = TOKEN_INFO.load(deps.storage)?;
+    token_info.total_supply = token_info
+        .total_supply
+        .checked_add(amount)
+        .map_err(StdError::from)?;
+    if let Some(cap) = token_info.get_cap() {
+        if token_info.total_supply > cap {
+            return Err(ContractError::CannotExceedCap {});
+        }
+    }
+    TOKEN_INFO.save(deps.storage, &token_info)?;
+    record_snapshots(deps.storage, env.block.height, &[])?;
+
+    Ok(CosmosMsg::Custom(TokenFactoryMsg::MintTokens {
+        denom: DENOM.load(deps.storage)?,
+        amount,
+        mint_to_address: recipient.to_string(),
+    }))
+}
+
+fn mint_response(
+    messages: Vec<CosmosMsg<TokenFactoryMsg>>,
+    recipient: String,
+    amount: Uint128,
+) -> Response<TokenFactoryMsg> {
+    Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "mint"),
+        attr("to", recipient),
+        attr("amount", amount),
+    ])
+}
+
+pub fn execute_mint<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    max_lsd_in: Option<Uint128>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_not_paused(read_pause_state(deps.storage)?.mint, "Mint")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    record_exchange_rate::<I, T>(deps.branch(), env.clone())?;
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let send_lsd_amount = compute_mint_lsd_amount(deps.as_ref(), amount)?;
+    assert_max_lsd_in(send_lsd_amount, max_lsd_in)?;
+
+    let mut messages = into_custom_msgs(lsd_config.deposit_funds(
+        deps.as_ref(),
+        env.clone(),
+        info.clone(),
+        send_lsd_amount,
+        info.sender.clone(),
+    )?)?;
+    increase_lsd_ledger(deps.storage, send_lsd_amount)?;
+    messages.push(mint_tokens(deps, &env, &info.sender, &recipient, amount)?);
+
+    Ok(mint_response(messages, recipient, amount))
+}
+
+pub fn execute_mint_with<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    lsd_amount: Uint128,
+    min_received: Option<Uint128>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_not_paused(read_pause_state(deps.storage)?.mint, "Mint")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    record_exchange_rate::<I, T>(deps.branch(), env.clone())?;
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let mint_amount = compute_mint_with_amount(deps.as_ref(), lsd_amount)?;
+    assert_min_received(mint_amount, min_received)?;
+
+    let mut messages = into_custom_msgs(lsd_config.deposit_funds(
+        deps.as_ref(),
+        env.clone(),
+        info.clone(),
+        lsd_amount,
+        info.sender.clone(),
+    )?)?;
+    increase_lsd_ledger(deps.storage, lsd_amount)?;
+    messages.push(mint_tokens(deps, &env, &info.sender, &recipient, mint_amount)?);
+
+    Ok(mint_response(messages, recipient, mint_amount))
+}
+
+pub fn execute_receive<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    // Only the underlying lsd token can trigger this hook
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    if lsd_config.cw20_token() != Some(info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Mint {
+            recipient,
+            min_received,
+        } => {
+            // The mint limit applies to the sender of the lsd tokens
+            let payer = deps.api.addr_validate(&cw20_msg.sender)?;
+            let recipient = recipient.unwrap_or(cw20_msg.sender);
+            assert_not_paused(read_pause_state(deps.storage)?.mint, "Mint")?;
+            assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+            // The lsd tokens were already transferred to the contract, we only need to mint the wrapper tokens
+            let mint_amount = compute_mint_with_amount(deps.as_ref(), cw20_msg.amount)?;
+            assert_min_received(mint_amount, min_received)?;
+
+            let mint_msg = mint_tokens(deps.branch(), &env, &payer, &recipient, mint_amount)?;
+            // The received lsd tokens back the minted tokens, so we record the rate after minting
+            increase_lsd_ledger(deps.storage, cw20_msg.amount)?;
+            record_exchange_rate::<I, T>(deps, env)?;
+
+            Ok(mint_response(vec![mint_msg], recipient, mint_amount))
+        }
+        // The native wrapper tokens are burned with `ExecuteMsg::Burn`
+        Cw20HookMsg::Unwrap { .. } => Err(ContractError::Unauthorized {}),
+    }
+}
+
+/// Returns the amount of native wrapper tokens sent along with the message, no other funds are accepted
+fn received_wrapper_tokens(info: &MessageInfo, denom: &str) -> Result<Uint128, ContractError> {
+    match info.funds.as_slice() {
+        [coin] if coin.denom == denom && !coin.amount.is_zero() => Ok(coin.amount),
+        _ => Err(ContractError::Std(StdError::generic_err(format!(
+            "Only {} tokens can be burned",
+            denom
+        )))),
+    }
+}
+
+pub fn execute_burn<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+    min_received: Option<Uint128>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let denom = DENOM.load(deps.storage)?;
+    let amount = received_wrapper_tokens(&info, &denom)?;
+    assert_not_paused(read_pause_state(deps.storage)?.burn, "Burn")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    record_exchange_rate::<I, T>(deps.branch(), env.clone())?;
+
+    let recipient = recipient
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?
+        .unwrap_or_else(|| info.sender.clone());
+
+    // The lsd amount is computed with the supply before the burn, as for the cw20 wrappers
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let lsd_amount = compute_burn_lsd_amount(deps.as_ref(), amount)?;
+    assert_min_received(lsd_amount, min_received)?;
+    decrease_lsd_ledger(deps.storage, lsd_amount)?;
+    start_supply_snapshots(deps.storage)?;
+    TOKEN_INFO.update(deps.storage, |mut token_info| -> Result<_, ContractError> {
+        token_info.total_supply = token_info
+            .total_supply
+            .checked_sub(amount)
+            .map_err(StdError::from)?;
+        Ok(token_info)
+    })?;
+    record_snapshots(deps.storage, env.block.height, &[])?;
+
+    let mut messages = vec![CosmosMsg::Custom(TokenFactoryMsg::BurnTokens {
+        denom,
+        amount,
+        burn_from_address: env.contract.address.to_string(),
+    })];
+    messages.extend(into_custom_msgs(lsd_config.send_funds(
+        deps.as_ref(),
+        env,
+        lsd_amount,
+        recipient,
+    )?)?);
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "burn"),
+        attr("from", info.sender),
+        attr("amount", amount),
+    ]))
+}
+
+/// Converts cw20 wrapper tokens held since before the wrapper was migrated to the native denom.
+/// Both tokens are accounted for in the same supply, so the conversion doesn't change the exchange rate.
+/// The cw20 balance is snapshotted as for cw20 transfers, so that it can still be queried at past heights
+pub fn execute_convert_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint128>,
+    recipient: Option<String>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let balance = BALANCES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let amount = amount.unwrap_or(balance);
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    start_balance_snapshots(deps.storage, &info.sender)?;
+    start_supply_snapshots(deps.storage)?;
+    BALANCES.save(
+        deps.storage,
+        &info.sender,
+        &balance.checked_sub(amount).map_err(StdError::from)?,
+    )?;
+    record_snapshots(deps.storage, env.block.height, &[&info.sender])?;
+
+    let recipient = recipient.unwrap_or_else(|| info.sender.to_string());
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let denom = DENOM.load(deps.storage)?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Custom(TokenFactoryMsg::MintTokens {
+            denom,
+            amount,
+            mint_to_address: recipient.to_string(),
+        }))
+        .add_attributes(vec![
+            attr("action", "convert_cw20"),
+            attr("from", info.sender),
+            attr("to", recipient),
+            attr("amount", amount),
+        ]))
+}
@@ -0,0 +1,9 @@
+pub mod contract;
+pub mod msg;
+pub mod state;
+pub mod token_factory;
+
+mod handler;
+
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,42 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Decimal;
+use std::marker::PhantomData;
+
+#[cw_serde]
+pub struct NativeTokenInitMsg<I> {
+    pub types: Option<PhantomData<I>>,
+    // the wrapper mints the `factory/{wrapper}/{subdenom}` denom
+    pub subdenom: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+
+    // Maximum decompound ratio per year
+    pub max_decompound_ratio: Option<Decimal>,
+
+    // only hub contract can call decompound
+    pub hub_contract: String,
+
+    // can pause mint, burn and decompound operations
+    pub guardian: Option<String>,
+
+    // can update the mint limits, defaults to the instantiator
+    pub admin: Option<String>,
+
+    pub lsd_config: I,
+}
+
+#[cw_serde]
+pub struct NativeMigrateMsg {
+    // creates the `factory/{wrapper}/{subdenom}` denom, required when migrating a cw20 wrapper
+    pub subdenom: Option<String>,
+
+    // sets the admin, wrappers instantiated before the admin role existed don't have one
+    pub admin: Option<String>,
+
+    // sets the guardian
+    pub guardian: Option<String>,
+
+    // sets the maximum decompound ratio per year
+    pub max_decompound_ratio: Option<Decimal>,
+}
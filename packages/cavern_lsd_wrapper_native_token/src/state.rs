@@ -0,0 +1,5 @@
+use cw_storage_plus::Item;
+
+// Token-factory denom minted and burned by the wrapper
+// Its supply is tracked in the cw20 token info, together with the cw20 tokens that were not converted yet
+pub const DENOM: Item<String> = Item::new("native_denom");
@@ -0,0 +1,80 @@
+use basset::dex_router::AssetInfo;
+use cavern_lsd_wrapper_token_with_limit::trait_def::LSDHub;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, Addr, BankMsg, Binary, CosmosMsg, Decimal, Deps, Env, MessageInfo, StdError, StdResult,
+    Uint128, WasmMsg,
+};
+
+/// Native lsd with a fixed exchange rate, updated with `UpdateLsdConfig`
+#[cw_serde]
+pub struct MockHub {
+    pub denom: String,
+    pub exchange_rate: Decimal,
+}
+
+impl LSDHub<MockHub> for MockHub {
+    fn instantiate_config(_deps: Deps, config: MockHub) -> StdResult<Self> {
+        Ok(config)
+    }
+
+    fn query_exchange_rate(&self, _deps: Deps, _env: Env) -> StdResult<Decimal> {
+        Ok(self.exchange_rate)
+    }
+
+    fn get_balance(&self, deps: Deps, _env: Env, address: Addr) -> StdResult<Uint128> {
+        Ok(deps.querier.query_balance(address, &self.denom)?.amount)
+    }
+
+    fn cw20_token(&self) -> Option<Addr> {
+        None
+    }
+
+    fn lsd_asset_info(&self) -> AssetInfo {
+        AssetInfo::NativeToken {
+            denom: self.denom.clone(),
+        }
+    }
+
+    fn deposit_funds(
+        &self,
+        _deps: Deps,
+        _env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+        _from: Addr,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        if info.funds != coins(amount.u128(), &self.denom) {
+            return Err(StdError::generic_err("Wrong lsd funds"));
+        }
+        Ok(vec![])
+    }
+
+    fn send_funds(
+        &self,
+        _deps: Deps,
+        _env: Env,
+        amount: Uint128,
+        to: Addr,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        Ok(vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: coins(amount.u128(), &self.denom),
+        })])
+    }
+
+    fn send_funds_with_msg(
+        &self,
+        _deps: Deps,
+        _env: Env,
+        amount: Uint128,
+        contract: Addr,
+        msg: Binary,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg,
+            funds: coins(amount.u128(), &self.denom),
+        })])
+    }
+}
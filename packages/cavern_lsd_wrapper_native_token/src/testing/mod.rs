@@ -0,0 +1,2 @@
+mod mock_hub;
+mod tests;
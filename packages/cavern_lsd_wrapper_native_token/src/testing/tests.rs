@@ -0,0 +1,1072 @@
+use crate::contract::{execute, instantiate, migrate, query};
+use crate::msg::{NativeMigrateMsg, NativeTokenInitMsg};
+use crate::testing::mock_hub::MockHub;
+use crate::token_factory::{into_custom_response, TokenFactoryMsg};
+use basset::native_wrapper::{DenomResponse, ExecuteMsg, QueryMsg};
+use basset::wrapper::{
+    AccruedRewardsResponse, DecompoundBudgetResponse, DecompoundConfigResponse, RetentionResponse, RewardRecipient, RewardSplitResponse,
+    SlashingLoss, SlashingLossResponse, TotalSupplyResponse, WrapperStateResponse,
+};
+use basset::wrapper::{ExecuteMsg as WrapperExecuteMsg, QueryMsg as WrapperQueryMsg};
+use cavern_lsd_wrapper_token_with_limit::contract::{
+    execute as wrapper_execute, query as wrapper_query, DECOMPOUND_WINDOW,
+};
+use cavern_lsd_wrapper_token_with_limit::msg::TokenInitMsg;
+use cavern_lsd_wrapper_token_with_limit::state::LSD_LEDGER;
+use cosmwasm_std::testing::{
+    mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, BankMsg, CosmosMsg, Decimal, OwnedDeps, StdError, SubMsg,
+    Uint128,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{BalanceResponse, Cw20Coin, TokenInfoResponse};
+use cw20_base::ContractError;
+use std::str::FromStr;
+
+const LSD_DENOM: &str = "ulsd";
+const WRAPPER_DENOM: &str = "factory/cosmos2contract/wlsd";
+
+fn mock_hub(exchange_rate: &str) -> MockHub {
+    MockHub {
+        denom: LSD_DENOM.to_string(),
+        exchange_rate: Decimal::from_str(exchange_rate).unwrap(),
+    }
+}
+
+fn do_init() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies_with_balance(&coins(1000, LSD_DENOM));
+    let res = instantiate::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        NativeTokenInitMsg {
+            types: None,
+            subdenom: "wlsd".to_string(),
+            name: "wrapped lsd".to_string(),
+            symbol: "WLSD".to_string(),
+            decimals: 6,
+            max_decompound_ratio: None,
+            hub_contract: "hub".to_string(),
+            guardian: None,
+            admin: None,
+            lsd_config: mock_hub("1"),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Custom(TokenFactoryMsg::CreateDenom {
+            subdenom: "wlsd".to_string()
+        }))]
+    );
+    deps
+}
+
+fn mint_msg(amount: u128, recipient: &str) -> SubMsg<TokenFactoryMsg> {
+    SubMsg::new(CosmosMsg::Custom(TokenFactoryMsg::MintTokens {
+        denom: WRAPPER_DENOM.to_string(),
+        amount: Uint128::from(amount),
+        mint_to_address: recipient.to_string(),
+    }))
+}
+
+fn total_supply(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Uint128 {
+    let token_info: TokenInfoResponse = from_binary(
+        &query::<MockHub, MockHub>(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap(),
+    )
+    .unwrap();
+    token_info.total_supply
+}
+
+#[test]
+fn mint_and_burn_native_denom() {
+    let mut deps = do_init();
+    let denom: DenomResponse = from_binary(
+        &query::<MockHub, MockHub>(deps.as_ref(), mock_env(), QueryMsg::Denom {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(denom.denom, WRAPPER_DENOM);
+
+    let res = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(1000, LSD_DENOM)),
+        ExecuteMsg::MintWith {
+            recipient: "alice".to_string(),
+            lsd_amount: Uint128::from(1000u128),
+            min_received: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![mint_msg(1000, "alice")]);
+    assert_eq!(total_supply(&deps), Uint128::from(1000u128));
+
+    // Only the wrapper denom can be burned
+    let err = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(400, LSD_DENOM)),
+        ExecuteMsg::Burn {
+            recipient: None,
+            min_received: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(format!(
+            "Only {} tokens can be burned",
+            WRAPPER_DENOM
+        )))
+    );
+
+    let res = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(400, WRAPPER_DENOM)),
+        ExecuteMsg::Burn {
+            recipient: Some("bob".to_string()),
+            min_received: Some(Uint128::from(400u128)),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Custom(TokenFactoryMsg::BurnTokens {
+                denom: WRAPPER_DENOM.to_string(),
+                amount: Uint128::from(400u128),
+                burn_from_address: MOCK_CONTRACT_ADDR.to_string(),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".to_string(),
+                amount: coins(400, LSD_DENOM),
+            })),
+        ]
+    );
+    assert_eq!(total_supply(&deps), Uint128::from(600u128));
+    assert_eq!(
+        LSD_LEDGER.load(&deps.storage).unwrap(),
+        Uint128::from(600u128)
+    );
+
+    // The supply cap is shared with the cw20 wrappers
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateMintLimits {
+            max_supply: Some(Uint128::from(1000u128)),
+            max_mint_per_address: None,
+            mint_window: 0,
+        },
+    )
+    .unwrap();
+    let err = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(500, LSD_DENOM)),
+        ExecuteMsg::MintWith {
+            recipient: "alice".to_string(),
+            lsd_amount: Uint128::from(500u128),
+            min_received: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CannotExceedCap {});
+}
+
+fn total_supply_at_height(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    height: u64,
+) -> Uint128 {
+    let res: TotalSupplyResponse = from_binary(
+        &query::<MockHub, MockHub>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TotalSupplyAtHeight {
+                height: Some(height),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    res.total_supply
+}
+
+#[test]
+fn native_supply_snapshots() {
+    let mut deps = do_init();
+    let start = mock_env().block.height;
+
+    let mut env = mock_env();
+    env.block.height = start + 1;
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &coins(1000, LSD_DENOM)),
+        ExecuteMsg::MintWith {
+            recipient: "alice".to_string(),
+            lsd_amount: Uint128::from(1000u128),
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    env.block.height = start + 3;
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        env,
+        mock_info("alice", &coins(400, WRAPPER_DENOM)),
+        ExecuteMsg::Burn {
+            recipient: None,
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(total_supply_at_height(&deps, start), Uint128::zero());
+    assert_eq!(total_supply_at_height(&deps, start + 1), Uint128::from(1000u128));
+    assert_eq!(total_supply_at_height(&deps, start + 2), Uint128::from(1000u128));
+    assert_eq!(total_supply_at_height(&deps, start + 3), Uint128::from(600u128));
+    let res: TotalSupplyResponse = from_binary(
+        &query::<MockHub, MockHub>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TotalSupplyAtHeight { height: None },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.total_supply, Uint128::from(600u128));
+}
+
+#[test]
+fn decompound_native_wrapper() {
+    let mut deps = do_init();
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(1000, LSD_DENOM)),
+        ExecuteMsg::MintWith {
+            recipient: "alice".to_string(),
+            lsd_amount: Uint128::from(1000u128),
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    // The lsd denom can't be changed, only its exchange rate is updated
+    let err = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateLsdConfig {
+            lsd_config: to_binary(&MockHub {
+                denom: "other_lsd".to_string(),
+                exchange_rate: Decimal::from_str("1.1").unwrap(),
+            })
+            .unwrap(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The lsd token of the wrapper can't be changed"
+        ))
+    );
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateLsdConfig {
+            lsd_config: to_binary(&mock_hub("1.1")).unwrap(),
+        },
+    )
+    .unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(1000);
+    let rewards: AccruedRewardsResponse = from_binary(
+        &query::<MockHub, MockHub>(deps.as_ref(), env.clone(), QueryMsg::AccruedRewards {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(rewards.lsd_rewards, Uint128::from(90u128));
+
+    // Only the hub can decompound
+    let err = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "hub".to_string(),
+            amount: coins(90, LSD_DENOM),
+        }))]
+    );
+
+    // Without a decompound limit, the released rewards are reported with no remaining budget
+    let budget: DecompoundBudgetResponse = from_binary(
+        &query::<MockHub, MockHub>(deps.as_ref(), env.clone(), QueryMsg::DecompoundBudget {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        budget,
+        DecompoundBudgetResponse {
+            window_seconds: DECOMPOUND_WINDOW,
+            released_ratio: rewards.rate_decrease.unwrap(),
+            remaining_ratio: None,
+        }
+    );
+
+    // The admin can set a decompound limit, resetting the history
+    let update_msg = ExecuteMsg::UpdateDecompoundConfig {
+        max_decompound_ratio: Some(Decimal::percent(10)),
+        reset_state: true,
+    };
+    let err = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        update_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute::<MockHub, MockHub>(deps.as_mut(), env.clone(), mock_info("admin", &[]), update_msg)
+        .unwrap();
+
+    let config: DecompoundConfigResponse = from_binary(
+        &query::<MockHub, MockHub>(deps.as_ref(), env.clone(), QueryMsg::DecompoundConfig {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(config.max_decompound_ratio, Some(Decimal::percent(10)));
+    let budget: DecompoundBudgetResponse = from_binary(
+        &query::<MockHub, MockHub>(deps.as_ref(), env, QueryMsg::DecompoundBudget {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(budget.released_ratio, Decimal::zero());
+    assert_eq!(budget.remaining_ratio, Some(Decimal::zero()));
+}
+
+#[test]
+fn split_native_rewards() {
+    let mut deps = do_init();
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(1000, LSD_DENOM)),
+        ExecuteMsg::MintWith {
+            recipient: "alice".to_string(),
+            lsd_amount: Uint128::from(1000u128),
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    let recipients = vec![RewardRecipient {
+        address: "treasury".to_string(),
+        ratio: Decimal::percent(20),
+    }];
+    let err = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::UpdateRewardSplit {
+            recipients: recipients.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateRewardSplit {
+            recipients: recipients.clone(),
+        },
+    )
+    .unwrap();
+
+    let split: RewardSplitResponse = from_binary(
+        &query::<MockHub, MockHub>(deps.as_ref(), mock_env(), QueryMsg::RewardSplit {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        split,
+        RewardSplitResponse {
+            recipients,
+            decompound_recipient_ratio: Decimal::percent(80),
+        }
+    );
+
+    // The treasury takes its share of the rewards, the rest goes to the hub
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateLsdConfig {
+            lsd_config: to_binary(&mock_hub("1.1")).unwrap(),
+        },
+    )
+    .unwrap();
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(1000);
+    let res = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        env,
+        mock_info("hub", &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(18, LSD_DENOM),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "hub".to_string(),
+                amount: coins(72, LSD_DENOM),
+            })),
+        ]
+    );
+}
+
+#[test]
+fn retain_native_rewards() {
+    let mut deps = do_init();
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(1000, LSD_DENOM)),
+        ExecuteMsg::MintWith {
+            recipient: "alice".to_string(),
+            lsd_amount: Uint128::from(1000u128),
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::UpdateRetentionRatio {
+            retention_ratio: Decimal::percent(50),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateRetentionRatio {
+            retention_ratio: Decimal::percent(50),
+        },
+    )
+    .unwrap();
+
+    // Half of the rewards stay in the wrapper, its exchange rate grows by half of the lsd rewards
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateLsdConfig {
+            lsd_config: to_binary(&mock_hub("1.1")).unwrap(),
+        },
+    )
+    .unwrap();
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(1000);
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("hub", &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap();
+
+    let retention: RetentionResponse = from_binary(
+        &query::<MockHub, MockHub>(deps.as_ref(), env, QueryMsg::Retention {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        retention,
+        RetentionResponse {
+            retention_ratio: Decimal::percent(50),
+            target_exchange_rate: Decimal::percent(105),
+        }
+    );
+}
+
+#[test]
+fn native_slashing_loss() {
+    let mut deps = do_init();
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(1000, LSD_DENOM)),
+        ExecuteMsg::MintWith {
+            recipient: "alice".to_string(),
+            lsd_amount: Uint128::from(1000u128),
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    // The lsd is slashed, the loss is recorded on the next burn, before the burned tokens leave the wrapper
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateLsdConfig {
+            lsd_config: to_binary(&mock_hub("0.8")).unwrap(),
+        },
+    )
+    .unwrap();
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(1000);
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &coins(400, WRAPPER_DENOM)),
+        ExecuteMsg::Burn {
+            recipient: None,
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    let slashing: SlashingLossResponse = from_binary(
+        &query::<MockHub, MockHub>(deps.as_ref(), env.clone(), QueryMsg::SlashingLoss {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        slashing,
+        SlashingLossResponse {
+            under_collateralized: true,
+            redemption_rate: Decimal::percent(80),
+            luna_loss: Uint128::from(120u128),
+            last_loss: Some(SlashingLoss {
+                time: env.block.time.seconds(),
+                exchange_rate: Decimal::percent(80),
+                luna_loss: Uint128::from(200u128),
+            }),
+        }
+    );
+}
+
+/// Native query answering `msg` as the share based wrappers do, None for the features the native wrapper doesn't have.
+/// The match is exhaustive, so that new wrapper queries have to be forwarded or left out explicitly
+fn native_query(msg: WrapperQueryMsg) -> Option<QueryMsg> {
+    Some(match msg {
+        WrapperQueryMsg::Balance { address } => QueryMsg::Cw20Balance { address },
+        WrapperQueryMsg::BalanceAtHeight { address, height } => {
+            QueryMsg::Cw20BalanceAtHeight { address, height }
+        }
+        WrapperQueryMsg::TotalSupplyAtHeight { height } => QueryMsg::TotalSupplyAtHeight { height },
+        WrapperQueryMsg::TokenInfo {} => QueryMsg::TokenInfo {},
+        WrapperQueryMsg::SimulateMint { amount } => QueryMsg::SimulateMint { amount },
+        WrapperQueryMsg::SimulateMintWith { lsd_amount } => {
+            QueryMsg::SimulateMintWith { lsd_amount }
+        }
+        WrapperQueryMsg::SimulateBurn { amount } => QueryMsg::SimulateBurn { amount },
+        WrapperQueryMsg::WrapperState {} => QueryMsg::WrapperState {},
+        WrapperQueryMsg::AccruedRewards {} => QueryMsg::AccruedRewards {},
+        WrapperQueryMsg::DecompoundConfig {} => QueryMsg::DecompoundConfig {},
+        WrapperQueryMsg::DecompoundState {} => QueryMsg::DecompoundState {},
+        WrapperQueryMsg::DecompoundBudget {} => QueryMsg::DecompoundBudget {},
+        WrapperQueryMsg::ExchangeRates { start_after, limit } => {
+            QueryMsg::ExchangeRates { start_after, limit }
+        }
+        WrapperQueryMsg::PauseState {} => QueryMsg::PauseState {},
+        WrapperQueryMsg::Admin {} => QueryMsg::Admin {},
+        WrapperQueryMsg::MintLimits {} => QueryMsg::MintLimits {},
+        WrapperQueryMsg::LsdLedger {} => QueryMsg::LsdLedger {},
+        WrapperQueryMsg::CircuitBreaker {} => QueryMsg::CircuitBreaker {},
+        WrapperQueryMsg::SlashingLoss {} => QueryMsg::SlashingLoss {},
+        WrapperQueryMsg::RewardSplit {} => QueryMsg::RewardSplit {},
+        WrapperQueryMsg::Retention {} => QueryMsg::Retention {},
+        // The native denom has no cw20 minter, allowances, marketing info or permits
+        WrapperQueryMsg::Minter {}
+        | WrapperQueryMsg::Allowance { .. }
+        | WrapperQueryMsg::AllAllowances { .. }
+        | WrapperQueryMsg::AllSpenderAllowances { .. }
+        | WrapperQueryMsg::AllAccounts { .. }
+        | WrapperQueryMsg::MarketingInfo {}
+        | WrapperQueryMsg::DownloadLogo {}
+        | WrapperQueryMsg::PermitNonce { .. } => return None,
+        // Swaps and flash loans are not supported on the share based wrappers
+        WrapperQueryMsg::SwapConfig {} | WrapperQueryMsg::FlashLoanFee {} => return None,
+    })
+}
+
+#[test]
+fn forward_wrapper_queries() {
+    let mut deps = do_init();
+    execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(1000, LSD_DENOM)),
+        ExecuteMsg::MintWith {
+            recipient: "alice".to_string(),
+            lsd_amount: Uint128::from(1000u128),
+            min_received: None,
+        },
+    )
+    .unwrap();
+
+    let wrapper_queries = vec![
+        WrapperQueryMsg::Balance {
+            address: "alice".to_string(),
+        },
+        WrapperQueryMsg::BalanceAtHeight {
+            address: "alice".to_string(),
+            height: Some(mock_env().block.height),
+        },
+        WrapperQueryMsg::TotalSupplyAtHeight {
+            height: Some(mock_env().block.height),
+        },
+        WrapperQueryMsg::TokenInfo {},
+        WrapperQueryMsg::SimulateMint {
+            amount: Uint128::from(100u128),
+        },
+        WrapperQueryMsg::SimulateMintWith {
+            lsd_amount: Uint128::from(100u128),
+        },
+        WrapperQueryMsg::SimulateBurn {
+            amount: Uint128::from(100u128),
+        },
+        WrapperQueryMsg::WrapperState {},
+        WrapperQueryMsg::AccruedRewards {},
+        WrapperQueryMsg::DecompoundConfig {},
+        WrapperQueryMsg::DecompoundState {},
+        WrapperQueryMsg::DecompoundBudget {},
+        WrapperQueryMsg::ExchangeRates {
+            start_after: None,
+            limit: None,
+        },
+        WrapperQueryMsg::PauseState {},
+        WrapperQueryMsg::Admin {},
+        WrapperQueryMsg::MintLimits {},
+        WrapperQueryMsg::LsdLedger {},
+        WrapperQueryMsg::CircuitBreaker {},
+        WrapperQueryMsg::SlashingLoss {},
+        WrapperQueryMsg::RewardSplit {},
+        WrapperQueryMsg::Retention {},
+    ];
+    for wrapper_msg in wrapper_queries {
+        let native_msg = native_query(wrapper_msg.clone())
+            .unwrap_or_else(|| panic!("{:?} is not forwarded", wrapper_msg));
+        assert_eq!(
+            query::<MockHub, MockHub>(deps.as_ref(), mock_env(), native_msg),
+            wrapper_query::<MockHub, MockHub>(deps.as_ref(), mock_env(), wrapper_msg.clone()),
+            "{:?}",
+            wrapper_msg
+        );
+    }
+}
+
+/// Native message forwarded as `msg` to the share based wrappers, None for the messages the native wrapper handles itself
+/// or doesn't have. The match is exhaustive, so that new wrapper messages have to be forwarded or left out explicitly
+fn native_execute(msg: WrapperExecuteMsg) -> Option<ExecuteMsg> {
+    Some(match msg {
+        WrapperExecuteMsg::Decompound { recipient } => ExecuteMsg::Decompound { recipient },
+        WrapperExecuteMsg::SetPaused {
+            mint,
+            burn,
+            decompound,
+        } => ExecuteMsg::SetPaused {
+            mint,
+            burn,
+            decompound,
+        },
+        WrapperExecuteMsg::UpdateGuardian { guardian } => ExecuteMsg::UpdateGuardian { guardian },
+        WrapperExecuteMsg::UpdateMintLimits {
+            max_supply,
+            max_mint_per_address,
+            mint_window,
+        } => ExecuteMsg::UpdateMintLimits {
+            max_supply,
+            max_mint_per_address,
+            mint_window,
+        },
+        WrapperExecuteMsg::UpdateDecompoundConfig {
+            max_decompound_ratio,
+            reset_state,
+        } => ExecuteMsg::UpdateDecompoundConfig {
+            max_decompound_ratio,
+            reset_state,
+        },
+        WrapperExecuteMsg::ProposeNewAdmin { admin, expires_in } => {
+            ExecuteMsg::ProposeNewAdmin { admin, expires_in }
+        }
+        WrapperExecuteMsg::DropAdminProposal {} => ExecuteMsg::DropAdminProposal {},
+        WrapperExecuteMsg::ClaimAdmin {} => ExecuteMsg::ClaimAdmin {},
+        WrapperExecuteMsg::UpdateHub { hub_contract } => ExecuteMsg::UpdateHub { hub_contract },
+        WrapperExecuteMsg::UpdateLsdConfig { lsd_config } => {
+            ExecuteMsg::UpdateLsdConfig { lsd_config }
+        }
+        WrapperExecuteMsg::AddSurplusToRewards {} => ExecuteMsg::AddSurplusToRewards {},
+        WrapperExecuteMsg::SweepSurplus { recipient } => ExecuteMsg::SweepSurplus { recipient },
+        WrapperExecuteMsg::CheckLsdExchangeRate {} => ExecuteMsg::CheckLsdExchangeRate {},
+        WrapperExecuteMsg::UpdateCircuitBreaker {
+            max_change_per_hour,
+        } => ExecuteMsg::UpdateCircuitBreaker {
+            max_change_per_hour,
+        },
+        WrapperExecuteMsg::UpdateRewardSplit { recipients } => {
+            ExecuteMsg::UpdateRewardSplit { recipients }
+        }
+        WrapperExecuteMsg::UpdateRetentionRatio { retention_ratio } => {
+            ExecuteMsg::UpdateRetentionRatio { retention_ratio }
+        }
+        // Mints and burns move the native denom, they are handled by the native wrapper itself
+        WrapperExecuteMsg::Mint { .. }
+        | WrapperExecuteMsg::MintWith { .. }
+        | WrapperExecuteMsg::MintFromNative { .. }
+        | WrapperExecuteMsg::Receive(_)
+        | WrapperExecuteMsg::Burn { .. }
+        | WrapperExecuteMsg::BurnAll {} => return None,
+        // The native denom has no cw20 transfers, allowances, minter, marketing info or permits
+        WrapperExecuteMsg::Transfer { .. }
+        | WrapperExecuteMsg::Send { .. }
+        | WrapperExecuteMsg::IncreaseAllowance { .. }
+        | WrapperExecuteMsg::DecreaseAllowance { .. }
+        | WrapperExecuteMsg::Permit { .. }
+        | WrapperExecuteMsg::TransferFrom { .. }
+        | WrapperExecuteMsg::SendFrom { .. }
+        | WrapperExecuteMsg::BurnFrom { .. }
+        | WrapperExecuteMsg::UpdateMinter { .. }
+        | WrapperExecuteMsg::UpdateMarketing { .. }
+        | WrapperExecuteMsg::UploadLogo(_) => return None,
+        // Swaps and flash loans are not supported on the share based wrappers
+        WrapperExecuteMsg::BurnAndSwap { .. }
+        | WrapperExecuteMsg::UpdateSwapConfig { .. }
+        | WrapperExecuteMsg::FlashLoan { .. }
+        | WrapperExecuteMsg::FinishFlashLoan {}
+        | WrapperExecuteMsg::UpdateFlashLoanFee { .. } => return None,
+    })
+}
+
+#[test]
+fn forward_wrapper_messages() {
+    let wrapper_messages = vec![
+        ("hub", WrapperExecuteMsg::Decompound { recipient: None }),
+        (
+            "admin",
+            WrapperExecuteMsg::SetPaused {
+                mint: Some(true),
+                burn: None,
+                decompound: None,
+            },
+        ),
+        (
+            "admin",
+            WrapperExecuteMsg::UpdateGuardian {
+                guardian: Some("guardian".to_string()),
+            },
+        ),
+        (
+            "admin",
+            WrapperExecuteMsg::UpdateMintLimits {
+                max_supply: Some(Uint128::from(2000u128)),
+                max_mint_per_address: None,
+                mint_window: 0,
+            },
+        ),
+        (
+            "admin",
+            WrapperExecuteMsg::UpdateDecompoundConfig {
+                max_decompound_ratio: Some(Decimal::percent(10)),
+                reset_state: true,
+            },
+        ),
+        (
+            "admin",
+            WrapperExecuteMsg::ProposeNewAdmin {
+                admin: "new_admin".to_string(),
+                expires_in: 100,
+            },
+        ),
+        ("admin", WrapperExecuteMsg::DropAdminProposal {}),
+        ("new_admin", WrapperExecuteMsg::ClaimAdmin {}),
+        (
+            "admin",
+            WrapperExecuteMsg::UpdateHub {
+                hub_contract: "new_hub".to_string(),
+            },
+        ),
+        (
+            "admin",
+            WrapperExecuteMsg::UpdateLsdConfig {
+                lsd_config: to_binary(&mock_hub("1.1")).unwrap(),
+            },
+        ),
+        ("admin", WrapperExecuteMsg::AddSurplusToRewards {}),
+        ("admin", WrapperExecuteMsg::SweepSurplus { recipient: None }),
+        ("alice", WrapperExecuteMsg::CheckLsdExchangeRate {}),
+        (
+            "admin",
+            WrapperExecuteMsg::UpdateCircuitBreaker {
+                max_change_per_hour: Some(Decimal::percent(1)),
+            },
+        ),
+        (
+            "admin",
+            WrapperExecuteMsg::UpdateRewardSplit {
+                recipients: vec![RewardRecipient {
+                    address: "treasury".to_string(),
+                    ratio: Decimal::percent(20),
+                }],
+            },
+        ),
+        (
+            "admin",
+            WrapperExecuteMsg::UpdateRetentionRatio {
+                retention_ratio: Decimal::percent(50),
+            },
+        ),
+    ];
+    for (sender, wrapper_msg) in wrapper_messages {
+        let native_msg = native_execute(wrapper_msg.clone())
+            .unwrap_or_else(|| panic!("{:?} is not forwarded", wrapper_msg));
+        // Both messages run on the same state, they have to leave the same response and the same wrapper state
+        let mut native_deps = do_init();
+        let mut wrapper_deps = do_init();
+        let native_res =
+            execute::<MockHub, MockHub>(native_deps.as_mut(), mock_env(), mock_info(sender, &[]), native_msg);
+        let wrapper_res = wrapper_execute::<MockHub, MockHub>(
+            wrapper_deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            wrapper_msg.clone(),
+        )
+        .map(|res| into_custom_response(res).unwrap());
+        assert_eq!(native_res, wrapper_res, "{:?}", wrapper_msg);
+        for state_query in [
+            QueryMsg::Admin {},
+            QueryMsg::PauseState {},
+            QueryMsg::MintLimits {},
+            QueryMsg::DecompoundConfig {},
+            QueryMsg::CircuitBreaker {},
+            QueryMsg::RewardSplit {},
+            QueryMsg::Retention {},
+            QueryMsg::WrapperState {},
+        ] {
+            assert_eq!(
+                query::<MockHub, MockHub>(native_deps.as_ref(), mock_env(), state_query.clone()),
+                query::<MockHub, MockHub>(wrapper_deps.as_ref(), mock_env(), state_query),
+                "{:?}",
+                wrapper_msg
+            );
+        }
+    }
+}
+
+#[test]
+fn migrate_and_convert_cw20_wrapper() {
+    let mut deps = mock_dependencies_with_balance(&coins(1000, LSD_DENOM));
+    cavern_lsd_wrapper_token_with_limit::contract::instantiate::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        TokenInitMsg {
+            types: None,
+            name: "wrapped lsd".to_string(),
+            symbol: "WLSD".to_string(),
+            decimals: 6,
+            initial_balances: vec![
+                Cw20Coin {
+                    address: "alice".to_string(),
+                    amount: Uint128::from(700u128),
+                },
+                Cw20Coin {
+                    address: "bob".to_string(),
+                    amount: Uint128::from(300u128),
+                },
+            ],
+            max_decompound_ratio: None,
+            hub_contract: "hub".to_string(),
+            guardian: None,
+            admin: None,
+            lsd_config: mock_hub("1.5"),
+        },
+    )
+    .unwrap();
+    LSD_LEDGER
+        .save(&mut deps.storage, &Uint128::from(1000u128))
+        .unwrap();
+    set_contract_version(&mut deps.storage, "crates.io:cw20-wrapper", "1.0.0").unwrap();
+
+    let wrapper_state = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| -> WrapperStateResponse {
+        from_binary(
+            &query::<MockHub, MockHub>(deps.as_ref(), mock_env(), QueryMsg::WrapperState {})
+                .unwrap(),
+        )
+        .unwrap()
+    };
+    let state_before = wrapper_state(&deps);
+
+    // The cw20 wrapper has no denom yet
+    let mut msg = NativeMigrateMsg {
+        subdenom: None,
+        admin: None,
+        guardian: None,
+        max_decompound_ratio: None,
+    };
+    let err = migrate::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        msg.clone(),
+        "crates.io:native-wrapper",
+        "1.0.0",
+        &["crates.io:cw20-wrapper"],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "A subdenom is needed to migrate a cw20 wrapper"
+        ))
+    );
+
+    msg.subdenom = Some("wlsd".to_string());
+    let res = migrate::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        msg,
+        "crates.io:native-wrapper",
+        "1.0.0",
+        &["crates.io:cw20-wrapper"],
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Custom(TokenFactoryMsg::CreateDenom {
+            subdenom: "wlsd".to_string()
+        }))]
+    );
+    assert_eq!(
+        get_contract_version(&deps.storage).unwrap().contract,
+        "crates.io:native-wrapper"
+    );
+
+    // The holders convert their cw20 tokens 1:1, the exchange rate doesn't change
+    let mut env = mock_env();
+    env.block.height += 1;
+    let res = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        ExecuteMsg::ConvertCw20 {
+            amount: None,
+            recipient: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![mint_msg(700, "alice")]);
+    let res = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        ExecuteMsg::ConvertCw20 {
+            amount: Some(Uint128::from(100u128)),
+            recipient: Some("carol".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![mint_msg(100, "carol")]);
+
+    let balance: BalanceResponse = from_binary(
+        &query::<MockHub, MockHub>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Cw20Balance {
+                address: "bob".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(balance.balance, Uint128::from(200u128));
+
+    // The converted balances are still known before the conversion
+    let balance_at = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, height: u64| -> Uint128 {
+        let balance: BalanceResponse = from_binary(
+            &query::<MockHub, MockHub>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Cw20BalanceAtHeight {
+                    address: "alice".to_string(),
+                    height: Some(height),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        balance.balance
+    };
+    assert_eq!(balance_at(&deps, env.block.height - 1), Uint128::from(700u128));
+    assert_eq!(balance_at(&deps, env.block.height), Uint128::zero());
+    assert_eq!(total_supply(&deps), Uint128::from(1000u128));
+    assert_eq!(wrapper_state(&deps), state_before);
+
+    // Nothing left to convert
+    let err = execute::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::ConvertCw20 {
+            amount: None,
+            recipient: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidZeroAmount {});
+
+    // Other contracts can't be migrated to the native wrapper
+    set_contract_version(&mut deps.storage, "crates.io:other-wrapper", "1.0.0").unwrap();
+    let err = migrate::<MockHub, MockHub>(
+        deps.as_mut(),
+        mock_env(),
+        NativeMigrateMsg {
+            subdenom: None,
+            admin: None,
+            guardian: None,
+            max_decompound_ratio: None,
+        },
+        "crates.io:native-wrapper",
+        "1.0.0",
+        &["crates.io:cw20-wrapper"],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Can't migrate from crates.io:other-wrapper to crates.io:native-wrapper"
+        ))
+    );
+}
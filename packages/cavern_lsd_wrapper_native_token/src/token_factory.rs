@@ -0,0 +1,63 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CosmosMsg, CustomMsg, Response, StdError, StdResult, SubMsg, Uint128};
+
+/// Token-factory messages handled by the chain bindings
+#[cw_serde]
+pub enum TokenFactoryMsg {
+    /// Creates the `factory/{sender}/{subdenom}` denom, administrated by the sender
+    CreateDenom { subdenom: String },
+    MintTokens {
+        denom: String,
+        amount: Uint128,
+        mint_to_address: String,
+    },
+    BurnTokens {
+        denom: String,
+        amount: Uint128,
+        burn_from_address: String,
+    },
+}
+
+impl CustomMsg for TokenFactoryMsg {}
+
+/// Returns the denom created by `contract` for `subdenom`
+pub fn factory_denom(contract: &str, subdenom: &str) -> String {
+    format!("factory/{}/{}", contract, subdenom)
+}
+
+/// Converts a message of the lsd adapters or of the cw20 wrapper handlers, which never emit custom messages
+pub fn into_custom_msg(msg: CosmosMsg) -> StdResult<CosmosMsg<TokenFactoryMsg>> {
+    match msg {
+        CosmosMsg::Bank(msg) => Ok(CosmosMsg::Bank(msg)),
+        CosmosMsg::Wasm(msg) => Ok(CosmosMsg::Wasm(msg)),
+        CosmosMsg::Staking(msg) => Ok(CosmosMsg::Staking(msg)),
+        CosmosMsg::Distribution(msg) => Ok(CosmosMsg::Distribution(msg)),
+        msg => Err(StdError::generic_err(format!(
+            "Unsupported message for the native wrapper: {:?}",
+            msg
+        ))),
+    }
+}
+
+pub fn into_custom_msgs(msgs: Vec<CosmosMsg>) -> StdResult<Vec<CosmosMsg<TokenFactoryMsg>>> {
+    msgs.into_iter().map(into_custom_msg).collect()
+}
+
+/// Converts a response of the cw20 wrapper handlers reused by the native wrapper
+pub fn into_custom_response(res: Response) -> StdResult<Response<TokenFactoryMsg>> {
+    let mut custom_res = Response::new()
+        .add_attributes(res.attributes)
+        .add_events(res.events);
+    for sub_msg in res.messages {
+        custom_res = custom_res.add_submessage(SubMsg {
+            id: sub_msg.id,
+            msg: into_custom_msg(sub_msg.msg)?,
+            gas_limit: sub_msg.gas_limit,
+            reply_on: sub_msg.reply_on,
+        });
+    }
+    if let Some(data) = res.data {
+        custom_res = custom_res.set_data(data);
+    }
+    Ok(custom_res)
+}
@@ -1,12 +1,20 @@
-use crate::querier::get_current_exchange_rate;
+use crate::querier::{compute_luna_loss, get_current_exchange_rate, query_exchange_rates, query_lsd_ledger};
+use crate::querier::{query_balance_at_height, query_total_supply_at_height};
 use crate::state::read_lsd_config;
 use crate::state::HUB_CONTRACT_KEY;
+use crate::state::PERMIT_NONCES;
+use crate::state::SNAPSHOT_START;
+use crate::state::read_reward_split;
+use crate::state::{read_retention_ratio, read_target_exchange_rate, TARGET_EXCHANGE_RATE};
+use crate::state::SLASHING_LOSS;
+use crate::state::{decrease_lsd_ledger, LSD_LEDGER};
+use crate::state::{read_mint_limits, read_pause_state, read_rate_guard, ADMIN, ADMIN_PROPOSAL, GUARDIAN};
+use crate::state::{FLASH_LOAN_FEE, SWAP_CONFIG};
+use cw20_base::state::TOKEN_INFO;
 use serde::Serialize;
 
 use crate::trait_def::LSDHub;
-use basset::reward::MigrateMsg;
-use basset::wrapper::TokenInfoResponse;
-use cw20_base::contract::query_token_info;
+use basset::migrate::{assert_can_migrate, CW20_BASE_CONTRACT_NAME};
 use serde::Deserialize;
 
 use crate::state::WrapperState;
@@ -14,29 +22,38 @@ use crate::state::WrapperState;
 use crate::state::store_hub_contract;
 use crate::state::store_lsd_config;
 
+use basset::math::{multiply_ratio, Rounding};
 use basset::wrapper::AccruedRewards;
 use basset::wrapper::ExecuteMsg;
+use basset::wrapper::{
+    AccruedRewardsResponse, AdminResponse, CircuitBreakerResponse, MintLimitsResponse, PauseStateResponse, QueryMsg, SimulationResponse,
+    SwapConfigResponse, WrapperStateResponse, FlashLoanFeeResponse, PermitNonceResponse, RetentionResponse, RewardRecipient, RewardSplitResponse, SlashingLossResponse,
+};
 use cosmwasm_std::attr;
-use cosmwasm_std::entry_point;
 use cosmwasm_std::to_binary;
 
 use cosmwasm_std::Decimal;
 use cosmwasm_std::Uint128;
 
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult};
 
 use cw20_base::allowances::{execute_decrease_allowance, execute_increase_allowance};
 use cw20_base::contract::query as cw20_query;
 use cw20_base::contract::{
     execute_update_marketing, execute_update_minter, execute_upload_logo, instantiate as cw20_init,
 };
-use cw20_base::msg::{InstantiateMsg, QueryMsg};
+use cw20_base::msg::{InstantiateMsg, QueryMsg as Cw20QueryMsg};
 
 use crate::handler::*;
-use crate::msg::TokenInitMsg;
+use crate::msg::{MigrateMsg, TokenInitMsg};
+use cw2::{get_contract_version, set_contract_version};
 use cw20::MinterResponse;
 use cw20_base::ContractError;
 
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+// Reply to the last message bonding the native tokens of `ExecuteMsg::MintFromNative`
+pub const NATIVE_MINT_REPLY_ID: u64 = 1u64;
+
 pub fn instantiate<
     I: Serialize + for<'b> Deserialize<'b>,
     T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
@@ -48,8 +65,18 @@ pub fn instantiate<
 ) -> StdResult<Response> {
     let lsd_config = T::instantiate_config(deps.as_ref(), msg.lsd_config)?;
     store_lsd_config(deps.storage, &lsd_config)?;
+    LSD_LEDGER.save(deps.storage, &Uint128::zero())?;
+    SNAPSHOT_START.save(deps.storage, &env.block.height)?;
 
     store_hub_contract(deps.storage, &deps.api.addr_validate(&msg.hub_contract)?)?;
+    if let Some(guardian) = msg.guardian {
+        GUARDIAN.save(deps.storage, &deps.api.addr_validate(&guardian)?)?;
+    }
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender.clone(),
+    };
+    ADMIN.save(deps.storage, &admin)?;
 
     cw20_init(
         deps,
@@ -86,20 +113,46 @@ pub fn execute<
         ExecuteMsg::Transfer { recipient, amount } => {
             execute_transfer(deps, env, info, recipient, amount)
         }
-        ExecuteMsg::Burn { amount } => execute_burn::<I, T>(deps, env, info, amount),
+        ExecuteMsg::Burn {
+            amount,
+            min_received,
+            recipient,
+        } => execute_burn::<I, T>(deps, env, info, amount, min_received, recipient),
         ExecuteMsg::BurnAll {} => execute_burn_all::<I, T>(deps, env, info),
+        ExecuteMsg::BurnAndSwap {
+            amount,
+            ask_asset_info,
+            minimum_receive,
+            recipient,
+        } => execute_burn_and_swap::<I, T>(
+            deps,
+            env,
+            info,
+            amount,
+            ask_asset_info,
+            minimum_receive,
+            recipient,
+        ),
         ExecuteMsg::Send {
             contract,
             amount,
             msg,
         } => execute_send(deps, env, info, contract, amount, msg),
-        ExecuteMsg::Mint { recipient, amount } => {
-            execute_mint::<I, T>(deps, env, info, recipient, amount)
-        }
+        ExecuteMsg::Mint {
+            recipient,
+            amount,
+            max_lsd_in,
+        } => execute_mint::<I, T>(deps, env, info, recipient, amount, max_lsd_in),
         ExecuteMsg::MintWith {
             recipient,
             lsd_amount,
-        } => execute_mint_with::<I, T>(deps, env, info, recipient, lsd_amount),
+            min_received,
+        } => execute_mint_with::<I, T>(deps, env, info, recipient, lsd_amount, min_received),
+        ExecuteMsg::MintFromNative {
+            recipient,
+            min_received,
+        } => execute_mint_from_native::<I, T>(deps, env, info, recipient, min_received),
+        ExecuteMsg::Receive(msg) => execute_receive::<I, T>(deps, env, info, msg),
         ExecuteMsg::IncreaseAllowance {
             spender,
             amount,
@@ -110,14 +163,26 @@ pub fn execute<
             amount,
             expires,
         } => execute_decrease_allowance(deps, env, info, spender, amount, expires),
+        ExecuteMsg::Permit {
+            owner,
+            spender,
+            amount,
+            expires,
+            nonce,
+            pubkey,
+            signature,
+        } => execute_permit(deps, env, owner, spender, amount, expires, nonce, pubkey, signature),
         ExecuteMsg::TransferFrom {
             owner,
             recipient,
             amount,
         } => execute_transfer_from(deps, env, info, owner, recipient, amount),
-        ExecuteMsg::BurnFrom { owner, amount } => {
-            execute_burn_from::<I, T>(deps, env, info, owner, amount)
-        }
+        ExecuteMsg::BurnFrom {
+            owner,
+            amount,
+            min_received,
+            recipient,
+        } => execute_burn_from::<I, T>(deps, env, info, owner, amount, min_received, recipient),
         ExecuteMsg::SendFrom {
             owner,
             contract,
@@ -136,6 +201,66 @@ pub fn execute<
         ExecuteMsg::Decompound { recipient } => {
             execute_decompound::<I, T>(deps, env, info, recipient)
         }
+        ExecuteMsg::SetPaused {
+            mint,
+            burn,
+            decompound,
+        } => execute_set_paused(deps, info, mint, burn, decompound),
+        ExecuteMsg::UpdateGuardian { guardian } => execute_update_guardian(deps, info, guardian),
+        ExecuteMsg::UpdateMintLimits {
+            max_supply,
+            max_mint_per_address,
+            mint_window,
+        } => execute_update_mint_limits(deps, info, max_supply, max_mint_per_address, mint_window),
+        ExecuteMsg::ProposeNewAdmin { admin, expires_in } => {
+            execute_propose_new_admin(deps, env, info, admin, expires_in)
+        }
+        ExecuteMsg::DropAdminProposal {} => execute_drop_admin_proposal(deps, info),
+        ExecuteMsg::ClaimAdmin {} => execute_claim_admin(deps, env, info),
+        ExecuteMsg::UpdateHub { hub_contract } => execute_update_hub(deps, info, hub_contract),
+        ExecuteMsg::UpdateLsdConfig { lsd_config } => {
+            execute_update_lsd_config::<I, T>(deps, info, lsd_config)
+        }
+        ExecuteMsg::AddSurplusToRewards {} => execute_add_surplus_to_rewards::<I, T>(deps, env, info),
+        ExecuteMsg::SweepSurplus { recipient } => {
+            execute_sweep_surplus::<I, T>(deps, env, info, recipient)
+        }
+        ExecuteMsg::CheckLsdExchangeRate {} => execute_check_lsd_exchange_rate::<I, T>(deps, env),
+        ExecuteMsg::UpdateCircuitBreaker {
+            max_change_per_hour,
+        } => execute_update_circuit_breaker::<I, T>(deps, env, info, max_change_per_hour),
+        ExecuteMsg::UpdateSwapConfig {
+            astroport_addr,
+            phoenix_addr,
+            terraswap_addr,
+        } => execute_update_swap_config(deps, info, astroport_addr, phoenix_addr, terraswap_addr),
+        // This wrapper doesn't limit the decompounded rewards
+        ExecuteMsg::UpdateDecompoundConfig { .. } => Err(ContractError::Std(StdError::generic_err(
+            "This wrapper has no decompound limit",
+        ))),
+        ExecuteMsg::UpdateRewardSplit { recipients } => {
+            execute_update_reward_split(deps, info, recipients)
+        }
+        ExecuteMsg::UpdateRetentionRatio { retention_ratio } => {
+            execute_update_retention_ratio(deps, info, retention_ratio)
+        }
+        ExecuteMsg::FlashLoan { amount, msg } => execute_flash_loan::<I, T>(deps, env, info, amount, msg),
+        ExecuteMsg::FinishFlashLoan {} => execute_finish_flash_loan::<I, T>(deps, env, info),
+        ExecuteMsg::UpdateFlashLoanFee { fee } => execute_update_flash_loan_fee(deps, info, fee),
+    }
+}
+
+pub fn reply<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    match msg.id {
+        NATIVE_MINT_REPLY_ID => execute_native_mint_reply::<I, T>(deps, env),
+        _ => Err(StdError::generic_err("Invalid Reply Id").into()),
     }
 }
 
@@ -147,21 +272,173 @@ pub fn query<
     env: Env,
     msg: QueryMsg,
 ) -> StdResult<Binary> {
-    // If the token info are queried, we also add the current wLSD exchange rate to it
     match msg {
-        QueryMsg::TokenInfo {} => {
-            let token_info = query_token_info(deps)?;
+        QueryMsg::WrapperState {} => {
             let mut state = WrapperState::default();
-            to_binary(&TokenInfoResponse {
-                name: token_info.name,
-                symbol: token_info.symbol,
-                decimals: token_info.decimals,
-                total_supply: token_info.total_supply,
-                exchange_rate: get_current_exchange_rate::<I, T>(deps, env, &mut state)
-                    .map_err(|err| StdError::generic_err(err.to_string()))?,
+            let exchange_rate = get_current_exchange_rate::<I, T>(deps, env, &mut state)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            to_binary(&WrapperStateResponse {
+                lsd_exchange_rate: state.lsd_exchange_rate,
+                lsd_balance: state.lsd_balance,
+                backing_luna: Decimal::from_ratio(state.backing_luna, 1u128),
+                wlsd_supply: state.wlsd_supply,
+                exchange_rate,
+                expected_exchange_rate: None,
             })
         }
-        _ => cw20_query(deps, env, msg),
+        QueryMsg::AccruedRewards {} => {
+            let rewards = compute_accrued_rewards::<I, T>(deps, env)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            to_binary(&AccruedRewardsResponse {
+                luna_rewards: rewards.luna_rewards,
+                lsd_rewards: rewards.lsd_rewards,
+                rate_decrease: None,
+            })
+        }
+        // This wrapper doesn't limit the decompounded rewards
+        QueryMsg::DecompoundConfig {} | QueryMsg::DecompoundState {} | QueryMsg::DecompoundBudget {} => {
+            Err(StdError::generic_err("This wrapper has no decompound limit"))
+        }
+        QueryMsg::SimulateMint { amount } => to_binary(&SimulationResponse {
+            lsd_amount: compute_mint_lsd_amount::<I, T>(deps, env, amount)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+            wrapper_amount: amount,
+        }),
+        QueryMsg::SimulateMintWith { lsd_amount } => to_binary(&SimulationResponse {
+            lsd_amount,
+            wrapper_amount: compute_mint_with_amount::<I, T>(deps, env, lsd_amount)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+        }),
+        QueryMsg::SimulateBurn { amount } => to_binary(&SimulationResponse {
+            lsd_amount: compute_burn_lsd_amount::<I, T>(deps, env, amount)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+            wrapper_amount: amount,
+        }),
+        QueryMsg::ExchangeRates { start_after, limit } => {
+            to_binary(&query_exchange_rates(deps, start_after, limit)?)
+        }
+        QueryMsg::PauseState {} => {
+            let pause_state = read_pause_state(deps.storage)?;
+            to_binary(&PauseStateResponse {
+                guardian: GUARDIAN.may_load(deps.storage)?.map(|guardian| guardian.to_string()),
+                mint: pause_state.mint,
+                burn: pause_state.burn,
+                decompound: pause_state.decompound,
+            })
+        }
+        QueryMsg::Admin {} => {
+            let proposal = ADMIN_PROPOSAL.may_load(deps.storage)?;
+            to_binary(&AdminResponse {
+                admin: ADMIN.may_load(deps.storage)?.map(|admin| admin.to_string()),
+                proposed_admin: proposal.as_ref().map(|proposal| proposal.admin.to_string()),
+                proposal_expiry: proposal.map(|proposal| proposal.expiry),
+                hub_contract: HUB_CONTRACT_KEY.load(deps.storage)?.to_string(),
+            })
+        }
+        QueryMsg::MintLimits {} => {
+            let mint_limits = read_mint_limits(deps.storage)?;
+            to_binary(&MintLimitsResponse {
+                admin: ADMIN.may_load(deps.storage)?.map(|admin| admin.to_string()),
+                max_supply: TOKEN_INFO.load(deps.storage)?.get_cap(),
+                max_mint_per_address: mint_limits.max_mint_per_address,
+                mint_window: mint_limits.mint_window,
+            })
+        }
+        QueryMsg::LsdLedger {} => to_binary(&query_lsd_ledger::<I, T>(deps, env)?),
+        QueryMsg::CircuitBreaker {} => {
+            let guard = read_rate_guard(deps.storage)?;
+            to_binary(&CircuitBreakerResponse {
+                max_change_per_hour: guard.max_change_per_hour,
+                reference_rate: guard.last_rate,
+                reference_time: guard.last_update,
+                tripped: guard.tripped,
+            })
+        }
+        QueryMsg::SwapConfig {} => to_binary(&SwapConfigResponse {
+            swap_config: SWAP_CONFIG.may_load(deps.storage)?,
+        }),
+        QueryMsg::FlashLoanFee {} => to_binary(&FlashLoanFeeResponse {
+            fee: FLASH_LOAN_FEE.may_load(deps.storage)?,
+        }),
+        QueryMsg::SlashingLoss {} => {
+            let mut state = WrapperState::default();
+            let exchange_rate = get_current_exchange_rate::<I, T>(deps, env, &mut state)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            to_binary(&SlashingLossResponse {
+                under_collateralized: exchange_rate < state.target_exchange_rate,
+                redemption_rate: exchange_rate.min(state.target_exchange_rate),
+                luna_loss: compute_luna_loss(&state)?,
+                last_loss: SLASHING_LOSS.may_load(deps.storage)?,
+            })
+        }
+        QueryMsg::RewardSplit {} => {
+            let split = read_reward_split(deps.storage)?;
+            let split_ratio = split.iter().fold(Decimal::zero(), |total, share| total + share.ratio);
+            to_binary(&RewardSplitResponse {
+                recipients: split
+                    .into_iter()
+                    .map(|share| RewardRecipient {
+                        address: share.address.to_string(),
+                        ratio: share.ratio,
+                    })
+                    .collect(),
+                decompound_recipient_ratio: Decimal::one() - split_ratio,
+            })
+        }
+        QueryMsg::Retention {} => to_binary(&RetentionResponse {
+            retention_ratio: read_retention_ratio(deps.storage)?,
+            target_exchange_rate: read_target_exchange_rate(deps.storage)?,
+        }),
+        QueryMsg::PermitNonce { owner } => to_binary(&PermitNonceResponse {
+            nonce: PERMIT_NONCES
+                .may_load(deps.storage, &deps.api.addr_validate(&owner)?)?
+                .unwrap_or_default(),
+        }),
+        QueryMsg::Balance { address } => cw20_query(deps, env, Cw20QueryMsg::Balance { address }),
+        QueryMsg::BalanceAtHeight { address, height } => {
+            to_binary(&query_balance_at_height(deps, address, height)?)
+        }
+        QueryMsg::TotalSupplyAtHeight { height } => {
+            to_binary(&query_total_supply_at_height(deps, height)?)
+        }
+        QueryMsg::TokenInfo {} => cw20_query(deps, env, Cw20QueryMsg::TokenInfo {}),
+        QueryMsg::Minter {} => cw20_query(deps, env, Cw20QueryMsg::Minter {}),
+        QueryMsg::Allowance { owner, spender } => {
+            cw20_query(deps, env, Cw20QueryMsg::Allowance { owner, spender })
+        }
+        QueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => cw20_query(
+            deps,
+            env,
+            Cw20QueryMsg::AllAllowances {
+                owner,
+                start_after,
+                limit,
+            },
+        ),
+        QueryMsg::AllSpenderAllowances {
+            spender,
+            start_after,
+            limit,
+        } => cw20_query(
+            deps,
+            env,
+            Cw20QueryMsg::AllSpenderAllowances {
+                spender,
+                start_after,
+                limit,
+            },
+        ),
+        QueryMsg::AllAccounts { start_after, limit } => cw20_query(
+            deps,
+            env,
+            Cw20QueryMsg::AllAccounts { start_after, limit },
+        ),
+        QueryMsg::MarketingInfo {} => cw20_query(deps, env, Cw20QueryMsg::MarketingInfo {}),
+        QueryMsg::DownloadLogo {} => cw20_query(deps, env, Cw20QueryMsg::DownloadLogo {}),
     }
 }
 
@@ -178,29 +455,38 @@ fn compute_accrued_rewards<
 
     // If the current exchange rate is lower than the previous one, we have just had a slashing event or something else
     // We can't decompound and we can't recompound
-    if current_exchange_rate < Decimal::one() {
+    if current_exchange_rate < state.target_exchange_rate {
         // There is no accrued rewards to decompound.
         return Err(ContractError::Std(StdError::generic_err(
             "No rewards to decompound",
         )));
     }
+    // The retained share of the rewards stays in the wrapper and raises the exchange rate it is brought back to
+    let target_exchange_rate = state.target_exchange_rate
+        + read_retention_ratio(deps.storage)? * (current_exchange_rate - state.target_exchange_rate);
+    // The luna and lsd amounts backing the supply at the target exchange rate are rounded up, in favor of the wrapper
+    let target_backing_luna = multiply_ratio(
+        state.wlsd_supply,
+        target_exchange_rate.atomics(),
+        Decimal::one().atomics(),
+        Rounding::Up,
+    )?;
+    let target_lsd_balance = multiply_ratio(
+        state.wlsd_supply,
+        target_exchange_rate.atomics(),
+        state.lsd_exchange_rate.atomics(),
+        Rounding::Up,
+    )?;
+
     // Else , we have some available rewards to decompound
-    let mut luna_rewards = state.backing_luna * Uint128::one() - state.wlsd_supply;
+    let luna_rewards = state.backing_luna.saturating_sub(target_backing_luna);
 
-    let mut rewards_to_decompound = (Decimal::from_ratio(state.lsd_balance, 1u128)
-        - (Decimal::from_ratio(state.wlsd_supply, 1u128) / state.lsd_exchange_rate))
-        * Uint128::one();
-
-    // We substract 1 to the rewards to decompound so that we don't screw up the underlying value of the wrapper token
-    // The underlying value should be if possible always above 1 luna per wrapper token (slashing events should happen as little often as possible)
-    if rewards_to_decompound > Uint128::zero() {
-        rewards_to_decompound -= Uint128::one();
-        luna_rewards -= state.lsd_exchange_rate * Uint128::one();
-    }
+    let rewards_to_decompound = state.lsd_balance.saturating_sub(target_lsd_balance);
 
     Ok(AccruedRewards {
-        luna_rewards: luna_rewards * Uint128::one(),
-        lsd_rewards: rewards_to_decompound * Uint128::one(),
+        luna_rewards,
+        lsd_rewards: rewards_to_decompound,
+        target_exchange_rate,
     })
 }
 
@@ -223,7 +509,7 @@ pub fn execute_decompound<
     I: Serialize + for<'b> Deserialize<'b>,
     T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
 >(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     recipient: Option<String>,
@@ -232,6 +518,9 @@ pub fn execute_decompound<
     if info.sender != hub_contract {
         return Err(ContractError::Unauthorized {});
     }
+    assert_not_paused(read_pause_state(deps.storage)?.decompound, "Decompound")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    record_exchange_rate::<I, T>(deps.branch(), env.clone())?;
 
     let recipient = recipient
         .map(|x| deps.api.addr_validate(&x))
@@ -250,11 +539,18 @@ pub fn execute_decompound<
                 }
             }
             Ok(rewards) => {
-                let decompound_messages = if !rewards.lsd_rewards.is_zero() {
-                    lsd_config.send_funds(deps.as_ref(), env, rewards.lsd_rewards, recipient)?
-                } else {
-                    vec![]
-                };
+                TARGET_EXCHANGE_RATE.save(deps.storage, &rewards.target_exchange_rate)?;
+                decrease_lsd_ledger(deps.storage, rewards.lsd_rewards)?;
+                // One message per recipient of the reward split
+                let mut decompound_messages = vec![];
+                for (leg_recipient, amount) in split_rewards(deps.storage, rewards.lsd_rewards, recipient)? {
+                    decompound_messages.extend(lsd_config.send_funds(
+                        deps.as_ref(),
+                        env.clone(),
+                        amount,
+                        leg_recipient,
+                    )?);
+                }
                 Ok((decompound_messages, rewards))
             }
         }?;
@@ -272,8 +568,51 @@ pub fn execute_decompound<
     Ok(res)
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    //Err(StdError::GenericErr { msg: "No Migrate Implemented".to_string() })
-    Ok(Response::default())
+/// Migrates the wrapper to `contract_name` at `contract_version`.
+/// Wrappers instantiated before they recorded their own version are registered as cw20-base contracts
+pub fn migrate<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+    contract_name: &str,
+    contract_version: &str,
+) -> Result<Response, ContractError> {
+    let previous = get_contract_version(deps.storage)?;
+    assert_can_migrate(
+        &previous.contract,
+        &previous.version,
+        contract_name,
+        contract_version,
+        &[CW20_BASE_CONTRACT_NAME],
+    )?;
+
+    if let Some(admin) = msg.admin {
+        ADMIN.save(deps.storage, &deps.api.addr_validate(&admin)?)?;
+    }
+    if let Some(guardian) = msg.guardian {
+        GUARDIAN.save(deps.storage, &deps.api.addr_validate(&guardian)?)?;
+    }
+
+    // Wrappers migrated from a version without ledger account for their whole lsd balance
+    if LSD_LEDGER.may_load(deps.storage)?.is_none() {
+        let lsd_config: T = read_lsd_config(deps.storage)?;
+        let lsd_balance = lsd_config.get_balance(deps.as_ref(), env.clone(), env.contract.address)?;
+        LSD_LEDGER.save(deps.storage, &lsd_balance)?;
+    }
+    // Balances are snapshotted from the first migration to a version with snapshots on
+    if SNAPSHOT_START.may_load(deps.storage)?.is_none() {
+        SNAPSHOT_START.save(deps.storage, &env.block.height)?;
+    }
+
+    set_contract_version(deps.storage, contract_name, contract_version)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr("previous_contract", previous.contract),
+        attr("previous_version", previous.version),
+        attr("contract_version", contract_version),
+    ]))
 }
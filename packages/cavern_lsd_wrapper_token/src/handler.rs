@@ -1,14 +1,42 @@
-use crate::state::read_lsd_config;
+use crate::contract::NATIVE_MINT_REPLY_ID;
+use crate::querier::{compute_luna_loss, get_current_exchange_rate};
+use crate::state::{decrease_lsd_ledger, increase_lsd_ledger, LSD_LEDGER};
+use crate::state::{read_lsd_config, store_hub_contract, store_lsd_config};
+use crate::state::{read_mint_limits, AddressMints, MintLimits, ADDRESS_MINTS, MINT_LIMITS};
+use crate::state::{AdminProposal, ADMIN, ADMIN_PROPOSAL};
+use crate::state::{read_pause_state, GUARDIAN, PAUSE_STATE};
+use crate::state::{read_rate_guard, RateGuard, RATE_GUARD};
+use crate::state::{ExchangeRateSnapshot, WrapperState, EXCHANGE_RATE_HISTORY};
+use crate::state::{PendingNativeMint, PENDING_NATIVE_MINT, SWAP_CONFIG};
+use crate::state::{FlashLoan, FLASH_LOAN, FLASH_LOAN_FEE};
+use crate::state::SLASHING_LOSS;
+use crate::state::PERMIT_NONCES;
+use crate::state::{read_reward_split, RewardShare, RETENTION_RATIO, REWARD_SPLIT};
+use crate::state::read_target_exchange_rate;
+use crate::state::{record_snapshots, start_balance_snapshots, start_supply_snapshots};
 use crate::trait_def::LSDHub;
+use basset::math::{multiply_ratio, Rounding};
+use basset::permit::{verify_permit, PermitPayload};
+use basset::dex_router::AssetInfo;
+use basset::swap::{create_swap_message_for, find_best_swap, Asset, SwapConfig};
+use basset::wrapper::{Cw20HookMsg, ExecuteMsg, RewardRecipient, SlashingLoss};
 use cosmwasm_std::Decimal;
 use cosmwasm_std::Deps;
+use cosmwasm_std::StdError;
 use cosmwasm_std::StdResult;
-use cosmwasm_std::{Binary, CosmosMsg, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, Binary, CosmosMsg, DepsMut, Env, Event, MessageInfo, Response, Storage,
+    SubMsg, Uint128, Uint256, WasmMsg,
+};
+use cw20::{Cw20ReceiveMsg, Expiration};
 use cw20_base::contract::query_balance;
+use cw20_base::state::TOKEN_INFO;
 use serde::Deserialize;
 use serde::Serialize;
 
 use cw20_base::allowances::{
+    execute_decrease_allowance as cw20_decrease_allowance,
+    execute_increase_allowance as cw20_increase_allowance, query_allowance,
     execute_burn_from as cw20_burn_from, execute_send_from as cw20_send_from,
     execute_transfer_from as cw20_transfer_from,
 };
@@ -25,38 +53,250 @@ pub fn execute_transfer(
     recipient: String,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
-    cw20_transfer(deps, env, info, recipient, amount)
+    let sender = info.sender.clone();
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    with_snapshots(deps, env.block.height, &[&sender, &recipient_addr], |deps| {
+        cw20_transfer(deps, env, info, recipient, amount)
+    })
 }
 
-fn _before_burn<
+/// Runs the cw20 operation `op` and snapshots the balances of `accounts` and the total supply it leaves at `height`
+fn with_snapshots<F>(mut deps: DepsMut, height: u64, accounts: &[&Addr], op: F) -> Result<Response, ContractError>
+where
+    F: FnOnce(DepsMut) -> Result<Response, ContractError>,
+{
+    for account in accounts {
+        start_balance_snapshots(deps.storage, account)?;
+    }
+    start_supply_snapshots(deps.storage)?;
+    let res = op(deps.branch())?;
+    record_snapshots(deps.storage, height, accounts)?;
+    Ok(res)
+}
+
+/// Makes sure the caller receives at least `min_received` tokens out of the operation
+fn assert_min_received(received: Uint128, min_received: Option<Uint128>) -> Result<(), ContractError> {
+    if let Some(min_received) = min_received {
+        if received < min_received {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Slippage exceeded: received {}, expected at least {}",
+                received, min_received
+            ))));
+        }
+    }
+    Ok(())
+}
+
+/// Makes sure the caller doesn't deposit more than `max_lsd_in` lsd tokens in the operation
+fn assert_max_lsd_in(lsd_in: Uint128, max_lsd_in: Option<Uint128>) -> Result<(), ContractError> {
+    if let Some(max_lsd_in) = max_lsd_in {
+        if lsd_in > max_lsd_in {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "Slippage exceeded: {} lsd tokens needed, expected at most {}",
+                lsd_in, max_lsd_in
+            ))));
+        }
+    }
+    Ok(())
+}
+
+/// Computes the amount of lsd tokens sent back to the burner when burning `amount` wrapper tokens
+pub fn compute_burn_lsd_amount<
     I: Serialize + for<'b> Deserialize<'b>,
     T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
 >(
     deps: Deps,
     env: Env,
-    info: MessageInfo,
     amount: Uint128,
-) -> StdResult<Vec<CosmosMsg>> {
+) -> Result<Uint128, ContractError> {
+    let mut state = WrapperState::default();
+    get_current_exchange_rate::<I, T>(deps, env, &mut state)?;
+    // When burning some tokens from here, we transfer the target exchange rate in Luna per each burned token to the burner
+    // While the wrapper is under-collateralized, each burned token only redeems its share of the backing
+    let backing = Uint256::from(state.lsd_balance) * Uint256::from(state.lsd_exchange_rate.atomics());
+    let target_backing = Uint256::from(state.wlsd_supply) * Uint256::from(state.target_exchange_rate.atomics());
+    // The burner gets the lsd amount rounded down
+    if backing < target_backing {
+        Ok(multiply_ratio(amount, state.lsd_balance, state.wlsd_supply, Rounding::Down)?)
+    } else {
+        Ok(multiply_ratio(
+            amount,
+            state.target_exchange_rate.atomics(),
+            state.lsd_exchange_rate.atomics(),
+            Rounding::Down,
+        )?)
+    }
+}
+
+/// Computes the amount of lsd tokens pulled from the minter when minting `amount` wrapper tokens
+pub fn compute_mint_lsd_amount<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: Deps,
+    env: Env,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
     let lsd_config: T = read_lsd_config(deps.storage)?;
-    // When burning some tokens from here, we transfer an equivalent amount of 1 Luna per each burned token to the burner
-    let lsd_exchange_rate = lsd_config.query_exchange_rate(deps, env.clone())?;
-    let lsd_amount = Decimal::from_ratio(amount, 1u128) / lsd_exchange_rate;
+    // We query the exchange rate with respect to the LSD at which we can mint some new wrapper token
+    let lsd_exchange_rate = lsd_config.query_exchange_rate(deps, env)?;
+    // Each minted token is worth the target exchange rate in Luna
+    let target_exchange_rate = read_target_exchange_rate(deps.storage)?;
+    // We round the send_lsd_amount up here to make sure we are not undercollateralizing our token
+    Ok(multiply_ratio(
+        amount,
+        target_exchange_rate.atomics(),
+        lsd_exchange_rate.atomics(),
+        Rounding::Up,
+    )?)
+}
 
-    lsd_config.send_funds(deps, env, lsd_amount * Uint128::one(), info.sender)
+/// Computes the amount of wrapper tokens minted in exchange of `lsd_amount` lsd tokens
+pub fn compute_mint_with_amount<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: Deps,
+    env: Env,
+    lsd_amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    // We query the exchange rate with respect to the LSD at which we can mint some new wrapper token
+    let lsd_exchange_rate = lsd_config.query_exchange_rate(deps, env)?;
+    let target_exchange_rate = read_target_exchange_rate(deps.storage)?;
+    // The minted amount is rounded down
+    Ok(multiply_ratio(
+        lsd_amount,
+        lsd_exchange_rate.atomics(),
+        target_exchange_rate.atomics(),
+        Rounding::Down,
+    )?)
 }
 
-pub fn execute_burn<
+/// Records the current exchange rates of the wrapper and of the lsd, so that their history can be queried
+pub fn record_exchange_rate<
     I: Serialize + for<'b> Deserialize<'b>,
     T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
 >(
     deps: DepsMut,
     env: Env,
+) -> Result<(), ContractError> {
+    let mut state = WrapperState::default();
+    let exchange_rate = get_current_exchange_rate::<I, T>(deps.as_ref(), env.clone(), &mut state)?;
+    EXCHANGE_RATE_HISTORY.save(
+        deps.storage,
+        env.block.time.seconds(),
+        &ExchangeRateSnapshot {
+            exchange_rate,
+            lsd_exchange_rate: state.lsd_exchange_rate,
+        },
+    )?;
+    if exchange_rate < state.target_exchange_rate {
+        SLASHING_LOSS.save(
+            deps.storage,
+            &SlashingLoss {
+                time: env.block.time.seconds(),
+                exchange_rate,
+                luna_loss: compute_luna_loss(&state)?,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Makes sure the mint doesn't exceed the per-address mint limit of the wrapper.
+/// The limit is counted on the address paying for the mint, not on the recipient it chooses.
+/// The total supply limit is enforced by the cw20 minter cap
+fn _before_mint(
+    deps: DepsMut,
+    env: &Env,
+    payer: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let mint_limits = read_mint_limits(deps.storage)?;
+    let max_mint_per_address = match mint_limits.max_mint_per_address {
+        Some(max_mint_per_address) => max_mint_per_address,
+        None => return Ok(()),
+    };
+
+    let now = env.block.time.seconds();
+    // A new window starts with the first mint following the end of the previous one
+    let mut address_mints = ADDRESS_MINTS
+        .may_load(deps.storage, payer)?
+        .filter(|mints| now < mints.window_start + mint_limits.mint_window)
+        .unwrap_or(AddressMints {
+            window_start: now,
+            minted: Uint128::zero(),
+        });
+
+    let remaining = max_mint_per_address.saturating_sub(address_mints.minted);
+    if amount > remaining {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Mint limit per address exceeded: {} can mint at most {} wrapper tokens every {} seconds, {} left in the current window",
+            payer, max_mint_per_address, mint_limits.mint_window, remaining
+        ))));
+    }
+    address_mints.minted += amount;
+    ADDRESS_MINTS.save(deps.storage, payer, &address_mints)?;
+
+    Ok(())
+}
+
+fn _before_burn<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: Env,
+    recipient: Addr,
+    msg: Option<Binary>,
+    amount: Uint128,
+    min_received: Option<Uint128>,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let lsd_amount = compute_burn_lsd_amount::<I, T>(deps.as_ref(), env.clone(), amount)?;
+    assert_min_received(lsd_amount, min_received)?;
+    decrease_lsd_ledger(deps.storage, lsd_amount)?;
+
+    // The lsd tokens are either sent to the recipient or forwarded to it along with a message
+    let msgs = match msg {
+        Some(msg) => lsd_config.send_funds_with_msg(deps.as_ref(), env, lsd_amount, recipient, msg)?,
+        None => lsd_config.send_funds(deps.as_ref(), env, lsd_amount, recipient)?,
+    };
+    Ok(msgs)
+}
+
+pub fn execute_burn<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     amount: Uint128,
+    min_received: Option<Uint128>,
+    recipient: Option<String>,
 ) -> Result<Response, ContractError> {
-    let transfer_messages = _before_burn::<I, T>(deps.as_ref(), env.clone(), info.clone(), amount)?;
+    assert_not_paused(read_pause_state(deps.storage)?.burn, "Burn")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    record_exchange_rate::<I, T>(deps.branch(), env.clone())?;
+    let recipient = recipient
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?
+        .unwrap_or_else(|| info.sender.clone());
+    let transfer_messages = _before_burn::<I, T>(
+        deps.branch(),
+        env.clone(),
+        recipient,
+        None,
+        amount,
+        min_received,
+    )?;
 
-    let res = cw20_burn(deps, env, info, amount)?;
+    let sender = info.sender.clone();
+    let res = with_snapshots(deps, env.block.height, &[&sender], |deps| {
+        cw20_burn(deps, env, info, amount)
+    })?;
 
     Ok(res.add_messages(transfer_messages))
 }
@@ -74,37 +314,106 @@ pub fn execute_burn_all<
     if amount.balance.is_zero() {
         return Ok(Response::new());
     }
-    execute_burn::<I, T>(deps, env, info, amount.balance)
+    execute_burn::<I, T>(deps, env, info, amount.balance, None, None)
+}
+
+/// Burns `amount` wrapper tokens and swaps the released lsd tokens to `ask_asset_info` through the dex router giving the best price
+#[allow(clippy::too_many_arguments)]
+pub fn execute_burn_and_swap<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    ask_asset_info: AssetInfo,
+    minimum_receive: Uint128,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(read_pause_state(deps.storage)?.burn, "Burn")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    record_exchange_rate::<I, T>(deps.branch(), env.clone())?;
+    let recipient = recipient
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?
+        .unwrap_or_else(|| info.sender.clone());
+    let swap_config = SWAP_CONFIG
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("No dex routers are set to swap the lsd tokens"))?;
+
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let lsd_amount = compute_burn_lsd_amount::<I, T>(deps.as_ref(), env.clone(), amount)?;
+    decrease_lsd_ledger(deps.storage, lsd_amount)?;
+    let lsd_asset = Asset {
+        amount: lsd_amount,
+        asset_info: lsd_config.lsd_asset_info(),
+    };
+    let (message_type, simulated_amount) =
+        find_best_swap(deps.as_ref(), &swap_config, &lsd_asset, &ask_asset_info).ok_or_else(
+            || StdError::generic_err("No dex can swap the lsd tokens to the requested asset"),
+        )?;
+    if simulated_amount < minimum_receive {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "The swap would return {}, less than the minimum of {}",
+            simulated_amount, minimum_receive
+        ))));
+    }
+    // The router checks the minimum amount again when the swap is executed
+    let swap_message = create_swap_message_for(
+        &swap_config,
+        lsd_asset,
+        ask_asset_info,
+        message_type,
+        Some(minimum_receive),
+        Some(recipient.to_string()),
+    )?;
+
+    let sender = info.sender.clone();
+    let res = with_snapshots(deps, env.block.height, &[&sender], |deps| {
+        cw20_burn(deps, env, info, amount)
+    })?;
+
+    Ok(res
+        .add_attribute("swap_recipient", recipient)
+        .add_message(swap_message))
 }
 
 pub fn execute_mint<
     I: Serialize + for<'b> Deserialize<'b>,
     T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
 >(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     mut info: MessageInfo,
     recipient: String,
     amount: Uint128,
+    max_lsd_in: Option<Uint128>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(read_pause_state(deps.storage)?.mint, "Mint")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    record_exchange_rate::<I, T>(deps.branch(), env.clone())?;
     // In order to mint, we need to transfer the underlying lsd asset to the contract
     // Any sender can call this function as long as they have the sufficient lsd balance
     let lsd_config: T = read_lsd_config(deps.storage)?;
-    // We query the exchange rate with respect to the LSD at which we can mint some new wrapper token
-    let lsd_exchange_rate = lsd_config.query_exchange_rate(deps.as_ref(), env.clone())?;
-    // We add 1 to the send_lsd_amount here to make sure we are not undercollateralizing our token at the start
-    let send_lsd_amount = Decimal::from_ratio(amount, 1u128) / lsd_exchange_rate + Decimal::one();
+    let send_lsd_amount = compute_mint_lsd_amount::<I, T>(deps.as_ref(), env.clone(), amount)?;
+    assert_max_lsd_in(send_lsd_amount, max_lsd_in)?;
+    _before_mint(deps.branch(), &env, &info.sender, amount)?;
 
     let messages = lsd_config.deposit_funds(
         deps.as_ref(),
         env.clone(),
         info.clone(),
-        send_lsd_amount * Uint128::one(),
+        send_lsd_amount,
         info.sender,
     )?;
+    increase_lsd_ledger(deps.storage, send_lsd_amount)?;
     info.sender = env.contract.address.clone();
 
-    let res = cw20_mint(deps, env, info, recipient, amount)?;
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    let res = with_snapshots(deps, env.block.height, &[&recipient_addr], |deps| {
+        cw20_mint(deps, env, info, recipient, amount)
+    })?;
 
     Ok(res.add_messages(messages))
 }
@@ -113,18 +422,22 @@ pub fn execute_mint_with<
     I: Serialize + for<'b> Deserialize<'b>,
     T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
 >(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     mut info: MessageInfo,
     recipient: String,
     lsd_amount: Uint128,
+    min_received: Option<Uint128>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(read_pause_state(deps.storage)?.mint, "Mint")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    record_exchange_rate::<I, T>(deps.branch(), env.clone())?;
     // In order to mint, we need to transfer the underlying lsd asset to the contract
     // Any sender can call this function as long as they have the sufficient lsd balance
     let lsd_config: T = read_lsd_config(deps.storage)?;
-    // We query the exchange rate with respect to the LSD at which we can mint some new wrapper token
-    let lsd_exchange_rate = lsd_config.query_exchange_rate(deps.as_ref(), env.clone())?;
-    let mint_amount = Decimal::from_ratio(lsd_amount, 1u128) * lsd_exchange_rate;
+    let mint_amount = compute_mint_with_amount::<I, T>(deps.as_ref(), env.clone(), lsd_amount)?;
+    assert_min_received(mint_amount, min_received)?;
+    _before_mint(deps.branch(), &env, &info.sender, mint_amount)?;
 
     let messages = lsd_config.deposit_funds(
         deps.as_ref(),
@@ -133,14 +446,222 @@ pub fn execute_mint_with<
         lsd_amount,
         info.sender,
     )?;
-
+    increase_lsd_ledger(deps.storage, lsd_amount)?;
     info.sender = env.contract.address.clone();
 
-    let res = cw20_mint(deps, env, info, recipient, mint_amount * Uint128::one())?;
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    let res = with_snapshots(deps, env.block.height, &[&recipient_addr], |deps| {
+        cw20_mint(deps, env, info, recipient, mint_amount)
+    })?;
 
     Ok(res.add_messages(messages))
 }
 
+pub fn execute_mint_from_native<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    min_received: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(read_pause_state(deps.storage)?.mint, "Mint")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    if info.funds.iter().all(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    // The lsd tokens received are measured from the balance of the wrapper, before and after bonding
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let lsd_balance =
+        lsd_config.get_balance(deps.as_ref(), env.clone(), env.contract.address.clone())?;
+    let mut messages = lsd_config.bond_funds(deps.as_ref(), env, info.funds)?;
+    let last_message = messages
+        .pop()
+        .ok_or_else(|| StdError::generic_err("The native tokens can't be bonded"))?;
+    PENDING_NATIVE_MINT.save(
+        deps.storage,
+        &PendingNativeMint {
+            sender: info.sender.clone(),
+            recipient,
+            min_received,
+            lsd_balance,
+        },
+    )?;
+
+    // The wrapper tokens are minted in the reply to the last message, once all the lsd tokens were received
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessage(SubMsg::reply_on_success(last_message, NATIVE_MINT_REPLY_ID))
+        .add_attributes(vec![
+            attr("action", "mint_from_native"),
+            attr("from", info.sender),
+        ]))
+}
+
+/// Mints the wrapper tokens for the lsd tokens received since `ExecuteMsg::MintFromNative`
+pub fn execute_native_mint_reply<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: Env,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_NATIVE_MINT.load(deps.storage)?;
+    PENDING_NATIVE_MINT.remove(deps.storage);
+
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let lsd_amount = lsd_config
+        .get_balance(deps.as_ref(), env.clone(), env.contract.address.clone())?
+        .checked_sub(pending.lsd_balance)
+        .map_err(StdError::from)?;
+    if lsd_amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let info = MessageInfo {
+        sender: env.contract.address.clone(),
+        funds: vec![],
+    };
+    execute_mint_received::<I, T>(
+        deps,
+        env,
+        info,
+        pending.sender,
+        pending.recipient.to_string(),
+        lsd_amount,
+        pending.min_received,
+    )
+}
+
+pub fn execute_receive<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Mint {
+            recipient,
+            min_received,
+        } => {
+            // Only the underlying lsd token can trigger this hook
+            let lsd_config: T = read_lsd_config(deps.storage)?;
+            if lsd_config.cw20_token() != Some(info.sender.clone()) {
+                return Err(ContractError::Unauthorized {});
+            }
+            // The mint limit applies to the sender of the lsd tokens
+            let payer = deps.api.addr_validate(&cw20_msg.sender)?;
+            let recipient = recipient.unwrap_or(cw20_msg.sender);
+            execute_mint_received::<I, T>(
+                deps,
+                env,
+                info,
+                payer,
+                recipient,
+                cw20_msg.amount,
+                min_received,
+            )
+        }
+        Cw20HookMsg::Unwrap {
+            recipient,
+            msg,
+            min_received,
+        } => {
+            // Only the wrapper token itself can trigger this hook
+            if info.sender != env.contract.address {
+                return Err(ContractError::Unauthorized {});
+            }
+            let recipient = recipient.unwrap_or(cw20_msg.sender);
+            execute_unwrap_received::<I, T>(
+                deps,
+                env,
+                info,
+                recipient,
+                msg,
+                cw20_msg.amount,
+                min_received,
+            )
+        }
+    }
+}
+
+/// Burns the wrapper tokens sent to the contract and sends the underlying lsd tokens to `recipient`,
+/// along with `msg` if specified
+fn execute_unwrap_received<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    msg: Option<Binary>,
+    amount: Uint128,
+    min_received: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(read_pause_state(deps.storage)?.burn, "Burn")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    record_exchange_rate::<I, T>(deps.branch(), env.clone())?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let transfer_messages = _before_burn::<I, T>(
+        deps.branch(),
+        env.clone(),
+        recipient.clone(),
+        msg,
+        amount,
+        min_received,
+    )?;
+
+    // The wrapper tokens were sent to the contract, so the contract burns them from its own balance
+    let sender = info.sender.clone();
+    let res = with_snapshots(deps, env.block.height, &[&sender], |deps| {
+        cw20_burn(deps, env, info, amount)
+    })?;
+
+    Ok(res
+        .add_attribute("unwrap_recipient", recipient)
+        .add_messages(transfer_messages))
+}
+
+fn execute_mint_received<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    mut info: MessageInfo,
+    payer: Addr,
+    recipient: String,
+    lsd_amount: Uint128,
+    min_received: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(read_pause_state(deps.storage)?.mint, "Mint")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    // The lsd tokens were already transferred to the contract, we only need to mint the wrapper tokens
+    let mint_amount = compute_mint_with_amount::<I, T>(deps.as_ref(), env.clone(), lsd_amount)?;
+    assert_min_received(mint_amount, min_received)?;
+
+    _before_mint(deps.branch(), &env, &payer, mint_amount)?;
+    info.sender = env.contract.address.clone();
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    let res = with_snapshots(deps.branch(), env.block.height, &[&recipient_addr], |deps| {
+        cw20_mint(deps, env.clone(), info, recipient, mint_amount)
+    })?;
+    // The received lsd tokens back the minted tokens, so we record the rate after minting
+    increase_lsd_ledger(deps.storage, lsd_amount)?;
+    record_exchange_rate::<I, T>(deps, env)?;
+
+    Ok(res)
+}
+
 pub fn execute_send(
     deps: DepsMut,
     env: Env,
@@ -149,7 +670,11 @@ pub fn execute_send(
     amount: Uint128,
     msg: Binary,
 ) -> Result<Response, ContractError> {
-    cw20_send(deps, env, info, contract, amount, msg)
+    let sender = info.sender.clone();
+    let contract_addr = deps.api.addr_validate(&contract)?;
+    with_snapshots(deps, env.block.height, &[&sender, &contract_addr], |deps| {
+        cw20_send(deps, env, info, contract, amount, msg)
+    })
 }
 
 pub fn execute_transfer_from(
@@ -160,22 +685,45 @@ pub fn execute_transfer_from(
     recipient: String,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
-    cw20_transfer_from(deps, env, info, owner, recipient, amount)
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    with_snapshots(deps, env.block.height, &[&owner_addr, &recipient_addr], |deps| {
+        cw20_transfer_from(deps, env, info, owner, recipient, amount)
+    })
 }
 
 pub fn execute_burn_from<
     I: Serialize + for<'b> Deserialize<'b>,
     T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
 >(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     owner: String,
     amount: Uint128,
+    min_received: Option<Uint128>,
+    recipient: Option<String>,
 ) -> Result<Response, ContractError> {
-    let transfer_messages = _before_burn::<I, T>(deps.as_ref(), env.clone(), info.clone(), amount)?;
+    assert_not_paused(read_pause_state(deps.storage)?.burn, "Burn")?;
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+    record_exchange_rate::<I, T>(deps.branch(), env.clone())?;
+    let recipient = recipient
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?
+        .unwrap_or_else(|| info.sender.clone());
+    let transfer_messages = _before_burn::<I, T>(
+        deps.branch(),
+        env.clone(),
+        recipient,
+        None,
+        amount,
+        min_received,
+    )?;
 
-    let res = cw20_burn_from(deps, env, info, owner, amount)?;
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let res = with_snapshots(deps, env.block.height, &[&owner_addr], |deps| {
+        cw20_burn_from(deps, env, info, owner, amount)
+    })?;
 
     Ok(res.add_messages(transfer_messages))
 }
@@ -189,5 +737,727 @@ pub fn execute_send_from(
     amount: Uint128,
     msg: Binary,
 ) -> Result<Response, ContractError> {
-    cw20_send_from(deps, env, info, owner, contract, amount, msg)
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let contract_addr = deps.api.addr_validate(&contract)?;
+    with_snapshots(deps, env.block.height, &[&owner_addr, &contract_addr], |deps| {
+        cw20_send_from(deps, env, info, owner, contract, amount, msg)
+    })
+}
+
+/// Sets the allowance signed by the owner. Each permit of an owner has to use its next nonce, so that it can't be replayed
+#[allow(clippy::too_many_arguments)]
+pub fn execute_permit(
+    deps: DepsMut,
+    env: Env,
+    owner: String,
+    spender: String,
+    amount: Uint128,
+    expires: Expiration,
+    nonce: u64,
+    pubkey: Binary,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    let expected_nonce = PERMIT_NONCES
+        .may_load(deps.storage, &owner_addr)?
+        .unwrap_or_default();
+    if nonce != expected_nonce {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Invalid permit nonce, expected {}",
+            expected_nonce
+        ))));
+    }
+
+    let payload = PermitPayload {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        owner,
+        spender: spender.clone(),
+        amount,
+        expires,
+        nonce,
+    };
+    verify_permit(deps.api, &payload, &pubkey, &signature)?;
+    PERMIT_NONCES.save(deps.storage, &owner_addr, &(nonce + 1))?;
+
+    // The allowance is set through the cw20 allowance handlers, as if the owner had sent the message
+    let current = query_allowance(deps.as_ref(), payload.owner, spender.clone())?.allowance;
+    let owner_info = MessageInfo {
+        sender: owner_addr,
+        funds: vec![],
+    };
+    let res = if amount >= current {
+        cw20_increase_allowance(deps, env, owner_info, spender, amount - current, Some(expires))?
+    } else {
+        cw20_decrease_allowance(deps, env, owner_info, spender, current - amount, Some(expires))?
+    };
+
+    Ok(res.add_attributes(vec![attr("permit_nonce", nonce.to_string())]))
+}
+
+/// Makes sure the operation was not paused by the guardian
+pub(crate) fn assert_not_paused(paused: bool, operation: &str) -> Result<(), ContractError> {
+    if paused {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "{} is paused",
+            operation
+        ))));
+    }
+    Ok(())
+}
+
+fn assert_guardian(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    if GUARDIAN.may_load(deps.storage)?.as_ref() != Some(sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn execute_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    mint: Option<bool>,
+    burn: Option<bool>,
+    decompound: Option<bool>,
+) -> Result<Response, ContractError> {
+    assert_guardian(deps.as_ref(), &info.sender)?;
+
+    let mut pause_state = read_pause_state(deps.storage)?;
+    if let Some(mint) = mint {
+        pause_state.mint = mint;
+    }
+    if let Some(burn) = burn {
+        pause_state.burn = burn;
+    }
+    if let Some(decompound) = decompound {
+        pause_state.decompound = decompound;
+    }
+    PAUSE_STATE.save(deps.storage, &pause_state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_paused"),
+        attr("mint", pause_state.mint.to_string()),
+        attr("burn", pause_state.burn.to_string()),
+        attr("decompound", pause_state.decompound.to_string()),
+    ]))
+}
+
+pub fn execute_update_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_guardian(deps.as_ref(), &info.sender)?;
+
+    match &guardian {
+        Some(guardian) => GUARDIAN.save(deps.storage, &deps.api.addr_validate(guardian)?)?,
+        None => GUARDIAN.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_guardian"),
+        attr("guardian", guardian.unwrap_or_default()),
+    ]))
+}
+
+fn assert_admin(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    if ADMIN.may_load(deps.storage)?.as_ref() != Some(sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn execute_update_mint_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_supply: Option<Uint128>,
+    max_mint_per_address: Option<Uint128>,
+    mint_window: u64,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+    // An empty window would silently disable the per-address limit
+    if max_mint_per_address.is_some() && mint_window == 0 {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The mint window can't be 0 with a per-address mint limit",
+        )));
+    }
+
+    // The supply limit is the cap of the cw20 minter, so that it is also reported by the `Minter` query
+    TOKEN_INFO.update(deps.storage, |mut token_info| -> StdResult<_> {
+        if let Some(minter) = token_info.mint.as_mut() {
+            minter.cap = max_supply;
+        }
+        Ok(token_info)
+    })?;
+    MINT_LIMITS.save(
+        deps.storage,
+        &MintLimits {
+            max_mint_per_address,
+            mint_window,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_mint_limits"),
+        attr("max_supply", max_supply.map(|cap| cap.to_string()).unwrap_or_default()),
+        attr(
+            "max_mint_per_address",
+            max_mint_per_address.map(|max| max.to_string()).unwrap_or_default(),
+        ),
+        attr("mint_window", mint_window.to_string()),
+    ]))
+}
+
+/// Maximum lifetime of an admin proposal, 2 weeks
+const MAX_ADMIN_PROPOSAL_TTL: u64 = 14 * 24 * 60 * 60;
+
+pub fn execute_propose_new_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    admin: String,
+    expires_in: u64,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    let admin = deps.api.addr_validate(&admin)?;
+    if admin == info.sender {
+        return Err(ContractError::Std(StdError::generic_err(
+            "New admin cannot be the current admin",
+        )));
+    }
+    if expires_in > MAX_ADMIN_PROPOSAL_TTL {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Admin proposal can't expire in more than {} seconds",
+            MAX_ADMIN_PROPOSAL_TTL
+        ))));
+    }
+
+    ADMIN_PROPOSAL.save(
+        deps.storage,
+        &AdminProposal {
+            admin: admin.clone(),
+            expiry: env.block.time.seconds() + expires_in,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_new_admin"),
+        attr("proposed_admin", admin),
+    ]))
+}
+
+pub fn execute_drop_admin_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    ADMIN_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "drop_admin_proposal"))
+}
+
+pub fn execute_claim_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let proposal = ADMIN_PROPOSAL
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("Admin proposal not found"))?;
+
+    if proposal.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time.seconds() > proposal.expiry {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Admin proposal expired",
+        )));
+    }
+
+    ADMIN.save(deps.storage, &proposal.admin)?;
+    ADMIN_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "claim_admin"),
+        attr("admin", proposal.admin),
+    ]))
+}
+
+pub fn execute_update_hub(
+    deps: DepsMut,
+    info: MessageInfo,
+    hub_contract: String,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    let hub_contract = deps.api.addr_validate(&hub_contract)?;
+    store_hub_contract(deps.storage, &hub_contract)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_hub"),
+        attr("hub_contract", hub_contract),
+    ]))
+}
+
+pub fn execute_update_swap_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    astroport_addr: String,
+    phoenix_addr: String,
+    terraswap_addr: String,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    SWAP_CONFIG.save(
+        deps.storage,
+        &SwapConfig {
+            astroport_addr: deps.api.addr_validate(&astroport_addr)?,
+            phoenix_addr: deps.api.addr_validate(&phoenix_addr)?,
+            terraswap_addr: deps.api.addr_validate(&terraswap_addr)?,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_swap_config"),
+        attr("astroport_addr", astroport_addr),
+        attr("phoenix_addr", phoenix_addr),
+        attr("terraswap_addr", terraswap_addr),
+    ]))
+}
+
+/// Lends `amount` lsd tokens to the sender and calls it back with `msg`.
+/// `ExecuteMsg::FinishFlashLoan` is executed right after the callback to check the loan was paid back.
+/// Flash loans take lsd tokens out of the wrapper, so they are paused along with burns and stopped by the circuit breaker
+pub fn execute_flash_loan<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    assert_not_paused(read_pause_state(deps.storage)?.burn, "Flash loan")?;
+    let fee_rate = FLASH_LOAN_FEE
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("Flash loans are disabled"))?;
+    if FLASH_LOAN.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "A flash loan is already in progress",
+        )));
+    }
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    assert_lsd_rate_guard::<I, T>(deps.branch(), &env)?;
+
+    // The fee is rounded up, in favor of the wrapper
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let loan = FlashLoan {
+        lsd_balance: lsd_config.get_balance(deps.as_ref(), env.clone(), env.contract.address.clone())?,
+        ledger: LSD_LEDGER.load(deps.storage)?,
+        fee: multiply_ratio(amount, fee_rate.atomics(), Decimal::one().atomics(), Rounding::Up)?,
+    };
+    FLASH_LOAN.save(deps.storage, &loan)?;
+
+    let mut messages =
+        lsd_config.send_funds_with_msg(deps.as_ref(), env.clone(), amount, info.sender.clone(), msg)?;
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_binary(&ExecuteMsg::FinishFlashLoan {})?,
+        funds: vec![],
+    }));
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "flash_loan"),
+            attr("borrower", info.sender),
+            attr("amount", amount),
+            attr("fee", loan.fee),
+        ])
+        .add_messages(messages))
+}
+
+/// Checks the lsd tokens lent by the last flash loan were sent back along with the fee.
+/// The fee is added to the ledger so that it is released by the next `Decompound`
+pub fn execute_finish_flash_loan<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    let loan = FLASH_LOAN.load(deps.storage)?;
+
+    // Lsd tokens deposited or withdrawn through the wrapper during the loan move the ledger as much as the balance
+    // Only the lsd tokens on top of the ledger can pay the loan back
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let lsd_balance = lsd_config.get_balance(deps.as_ref(), env.clone(), env.contract.address)?;
+    let ledger = LSD_LEDGER.load(deps.storage)?;
+    let expected_balance = loan.lsd_balance + loan.fee + ledger;
+    if lsd_balance + loan.ledger < expected_balance {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "The flash loan was not paid back, expected {} more lsd tokens",
+            expected_balance - lsd_balance - loan.ledger
+        ))));
+    }
+    FLASH_LOAN.remove(deps.storage);
+    increase_lsd_ledger(deps.storage, loan.fee)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "finish_flash_loan"),
+        attr("fee", loan.fee),
+    ]))
+}
+
+pub fn execute_update_flash_loan_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    match fee {
+        Some(fee) => {
+            if fee > Decimal::one() {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "The flash loan fee can't be more than 100%",
+                )));
+            }
+            FLASH_LOAN_FEE.save(deps.storage, &fee)?
+        }
+        None => FLASH_LOAN_FEE.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_flash_loan_fee"),
+        attr("fee", fee.map(|fee| fee.to_string()).unwrap_or_default()),
+    ]))
+}
+
+const MAX_REWARD_RECIPIENTS: usize = 10;
+
+/// Sets the recipients taking a share of the decompounded rewards. Their shares can't exceed all the rewards
+pub fn execute_update_reward_split(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipients: Vec<RewardRecipient>,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    if recipients.len() > MAX_REWARD_RECIPIENTS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "The rewards can't be split between more than {} recipients",
+            MAX_REWARD_RECIPIENTS
+        ))));
+    }
+    let split = recipients
+        .iter()
+        .map(|recipient| {
+            if recipient.ratio.is_zero() {
+                return Err(StdError::generic_err("Reward recipients need a non-zero share"));
+            }
+            Ok(RewardShare {
+                address: deps.api.addr_validate(&recipient.address)?,
+                ratio: recipient.ratio,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let total_ratio = split
+        .iter()
+        .try_fold(Decimal::zero(), |total, share| total.checked_add(share.ratio))
+        .map_err(StdError::from)?;
+    if total_ratio > Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "The reward shares add up to {}, more than all the rewards",
+            total_ratio
+        ))));
+    }
+    REWARD_SPLIT.save(deps.storage, &split)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_reward_split"),
+        attr("split_ratio", total_ratio.to_string()),
+    ]))
+}
+
+/// Sets the share of the rewards kept in the wrapper on each decompound, at most all the rewards
+pub fn execute_update_retention_ratio(
+    deps: DepsMut,
+    info: MessageInfo,
+    retention_ratio: Decimal,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    if retention_ratio > Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The retention ratio can't be more than 100%",
+        )));
+    }
+    RETENTION_RATIO.save(deps.storage, &retention_ratio)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_retention_ratio"),
+        attr("retention_ratio", retention_ratio.to_string()),
+    ]))
+}
+
+/// Splits `lsd_rewards` between the reward recipients, the rest goes to `recipient`. Empty shares are skipped
+pub fn split_rewards(
+    storage: &dyn Storage,
+    lsd_rewards: Uint128,
+    recipient: Addr,
+) -> StdResult<Vec<(Addr, Uint128)>> {
+    let mut remaining = lsd_rewards;
+    let mut legs = vec![];
+    for share in read_reward_split(storage)? {
+        let amount = lsd_rewards * share.ratio;
+        if !amount.is_zero() {
+            remaining = remaining.checked_sub(amount)?;
+            legs.push((share.address, amount));
+        }
+    }
+    if !remaining.is_zero() {
+        legs.push((recipient, remaining));
+    }
+    Ok(legs)
+}
+
+/// Replaces the lsd config of the wrapper.
+/// The new config goes through the same validation as the one provided at instantiation
+pub fn execute_update_lsd_config<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    info: MessageInfo,
+    lsd_config: Binary,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    let old_config: T = read_lsd_config(deps.storage)?;
+    let lsd_config = T::instantiate_config(deps.as_ref(), from_binary(&lsd_config)?)?;
+    // The ledger and the surplus count the lsd held by the wrapper, so the lsd token itself can't change
+    if lsd_config.cw20_token() != old_config.cw20_token()
+        || lsd_config.lsd_asset_info() != old_config.lsd_asset_info()
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "The lsd token of the wrapper can't be changed",
+        )));
+    }
+    store_lsd_config(deps.storage, &lsd_config)?;
+
+    Ok(Response::new().add_attribute("action", "update_lsd_config"))
+}
+
+/// Amount of lsd tokens held by the wrapper on top of its ledger
+fn query_lsd_surplus<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: Deps,
+    env: Env,
+    lsd_config: &T,
+) -> Result<Uint128, ContractError> {
+    let ledger = LSD_LEDGER.load(deps.storage)?;
+    let lsd_balance = lsd_config.get_balance(deps, env.clone(), env.contract.address)?;
+    Ok(lsd_balance.saturating_sub(ledger))
+}
+
+/// Adds the lsd surplus to the ledger. The surplus then backs the wrapper token and is released by the next `Decompound`
+pub fn execute_add_surplus_to_rewards<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let surplus = query_lsd_surplus::<I, T>(deps.as_ref(), env, &lsd_config)?;
+    increase_lsd_ledger(deps.storage, surplus)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_surplus_to_rewards"),
+        attr("surplus", surplus),
+    ]))
+}
+
+/// Sends the lsd surplus out of the wrapper, to the admin if no recipient is specified
+pub fn execute_sweep_surplus<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    let recipient = recipient
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?
+        .unwrap_or(info.sender);
+
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let surplus = query_lsd_surplus::<I, T>(deps.as_ref(), env.clone(), &lsd_config)?;
+    let messages = if !surplus.is_zero() {
+        lsd_config.send_funds(deps.as_ref(), env, surplus, recipient.clone())?
+    } else {
+        vec![]
+    };
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "sweep_surplus"),
+            attr("recipient", recipient),
+            attr("surplus", surplus),
+        ])
+        .add_messages(messages))
+}
+
+const SECONDS_PER_HOUR: u64 = 60 * 60;
+
+/// Compares the lsd exchange rate with the reference rate of the circuit breaker
+/// Fails if it moved more than the maximum change allowed since the reference rate was accepted
+fn check_lsd_rate_move(guard: &RateGuard, env: &Env, lsd_exchange_rate: Decimal) -> Result<(), ContractError> {
+    let (max_change_per_hour, last_rate) = match (guard.max_change_per_hour, guard.last_rate) {
+        (Some(max_change_per_hour), Some(last_rate)) if !last_rate.is_zero() => (max_change_per_hour, last_rate),
+        _ => return Ok(()),
+    };
+    // The rate is always allowed to move by one hour worth of change, hubs update it in discrete steps
+    let elapsed = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(guard.last_update)
+        .max(SECONDS_PER_HOUR);
+    let max_change = max_change_per_hour * Decimal::from_ratio(elapsed, SECONDS_PER_HOUR);
+    let change = if lsd_exchange_rate > last_rate {
+        lsd_exchange_rate - last_rate
+    } else {
+        last_rate - lsd_exchange_rate
+    } / last_rate;
+    if change > max_change {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Lsd exchange rate moved from {} to {}, more than the allowed {}",
+            last_rate, lsd_exchange_rate, max_change
+        ))));
+    }
+    Ok(())
+}
+
+/// Makes sure the lsd exchange rate didn't move abruptly before minting, burning or decompounding
+/// The reference rate of the circuit breaker is renewed once it is more than an hour old
+pub(crate) fn assert_lsd_rate_guard<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: &Env,
+) -> Result<(), ContractError> {
+    let mut guard = read_rate_guard(deps.storage)?;
+    if guard.max_change_per_hour.is_none() {
+        return Ok(());
+    }
+    if guard.tripped {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Circuit breaker tripped, the lsd exchange rate has to be reviewed by the admin",
+        )));
+    }
+
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let lsd_exchange_rate = lsd_config.query_exchange_rate(deps.as_ref(), env.clone())?;
+    check_lsd_rate_move(&guard, env, lsd_exchange_rate)?;
+
+    let now = env.block.time.seconds();
+    if guard.last_rate.is_none() || now >= guard.last_update + SECONDS_PER_HOUR {
+        guard.last_rate = Some(lsd_exchange_rate);
+        guard.last_update = now;
+        RATE_GUARD.save(deps.storage, &guard)?;
+    }
+    Ok(())
+}
+
+/// Trips the circuit breaker if the lsd exchange rate moved abruptly.
+/// Mint, burn and decompound are then rejected until the admin updates the circuit breaker
+pub fn execute_check_lsd_exchange_rate<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: Env,
+) -> Result<Response, ContractError> {
+    let mut guard = read_rate_guard(deps.storage)?;
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let lsd_exchange_rate = lsd_config.query_exchange_rate(deps.as_ref(), env.clone())?;
+
+    let mut res = Response::new().add_attributes(vec![
+        attr("action", "check_lsd_exchange_rate"),
+        attr("lsd_exchange_rate", lsd_exchange_rate.to_string()),
+    ]);
+    if !guard.tripped {
+        if let Err(err) = check_lsd_rate_move(&guard, &env, lsd_exchange_rate) {
+            guard.tripped = true;
+            RATE_GUARD.save(deps.storage, &guard)?;
+            res = res.add_event(
+                Event::new("circuit_breaker_tripped")
+                    .add_attribute(
+                        "reference_rate",
+                        guard.last_rate.unwrap_or_default().to_string(),
+                    )
+                    .add_attribute("lsd_exchange_rate", lsd_exchange_rate.to_string())
+                    .add_attribute("reason", err.to_string()),
+            );
+        }
+    }
+    Ok(res.add_attribute("tripped", guard.tripped.to_string()))
+}
+
+/// Sets the maximum lsd exchange rate change per hour and resets the circuit breaker.
+/// The current lsd exchange rate becomes the reference rate. Setting no maximum change disables the circuit breaker
+pub fn execute_update_circuit_breaker<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    max_change_per_hour: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &info.sender)?;
+
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let lsd_exchange_rate = lsd_config.query_exchange_rate(deps.as_ref(), env.clone())?;
+    RATE_GUARD.save(
+        deps.storage,
+        &RateGuard {
+            max_change_per_hour,
+            last_rate: Some(lsd_exchange_rate),
+            last_update: env.block.time.seconds(),
+            tripped: false,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_circuit_breaker"),
+        attr(
+            "max_change_per_hour",
+            max_change_per_hour.map(|max| max.to_string()).unwrap_or_default(),
+        ),
+        attr("lsd_exchange_rate", lsd_exchange_rate.to_string()),
+    ]))
 }
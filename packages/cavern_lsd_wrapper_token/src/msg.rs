@@ -13,5 +13,20 @@ pub struct TokenInitMsg<I> {
     // only hub contract can call decompound
     pub hub_contract: String,
 
+    // can pause mint, burn and decompound operations
+    pub guardian: Option<String>,
+
+    // can update the mint limits, defaults to the instantiator
+    pub admin: Option<String>,
+
     pub lsd_config: I,
 }
+
+#[cw_serde]
+pub struct MigrateMsg {
+    // sets the admin, wrappers instantiated before the admin role existed don't have one
+    pub admin: Option<String>,
+
+    // sets the guardian
+    pub guardian: Option<String>,
+}
@@ -1,15 +1,66 @@
 use crate::trait_def::LSDHub;
+use basset::swap::SwapConfig;
+use basset::wrapper::SlashingLoss;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Decimal;
 use cosmwasm_std::Uint128;
-use cosmwasm_std::{Addr, StdResult, Storage};
+use cosmwasm_std::{Addr, Order, StdResult, Storage};
 use serde::Deserialize;
 use serde::Serialize;
 //use cosmwasm_storage::{singleton, singleton_read};
-use cw_storage_plus::Item;
+use cw20_base::state::{BALANCES, TOKEN_INFO};
+use cw_storage_plus::{Item, Map};
 
 pub const LSD_CONFIG_KEY: &str = "lcd_config";
 pub const HUB_CONTRACT_KEY: Item<Addr> = Item::new("\u{0}\u{c}hub_contract");
+// The guardian can pause mint, burn and decompound operations in case of emergency
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+pub const PAUSE_STATE: Item<PauseState> = Item::new("pause_state");
+// The admin can update the mint limits of the wrapper
+pub const ADMIN: Item<Addr> = Item::new("admin");
+// Admin transfers are two-step: the proposed admin has to claim the role before the proposal expires
+pub const ADMIN_PROPOSAL: Item<AdminProposal> = Item::new("admin_proposal");
+pub const MINT_LIMITS: Item<MintLimits> = Item::new("mint_limits");
+// Wrapper tokens minted to each address during its current mint window
+pub const ADDRESS_MINTS: Map<&Addr, AddressMints> = Map::new("address_mints");
+// Recipients taking a share of the decompounded rewards, the rest goes to the decompound recipient
+pub const REWARD_SPLIT: Item<Vec<RewardShare>> = Item::new("reward_split");
+// Share of the rewards kept in the wrapper on each decompound, no rewards are kept until the admin sets it
+pub const RETENTION_RATIO: Item<Decimal> = Item::new("retention_ratio");
+// Exchange rate the decompound brings the wrapper back to, starts at 1 and grows by the retained rewards
+pub const TARGET_EXCHANGE_RATE: Item<Decimal> = Item::new("target_exchange_rate");
+
+// Balances and total supply of the wrapper token at the end of each height they changed, from `SNAPSHOT_START` on
+// Balances that didn't change since the snapshots started have no snapshot
+pub const SNAPSHOT_START: Item<u64> = Item::new("snapshot_start");
+pub const BALANCE_SNAPSHOTS: Map<(&Addr, u64), Uint128> = Map::new("balance_snapshots");
+pub const SUPPLY_SNAPSHOTS: Map<u64, Uint128> = Map::new("supply_snapshots");
+// Nonce the next permit of each owner has to be signed with
+pub const PERMIT_NONCES: Map<&Addr, u64> = Map::new("permit_nonces");
+
+// Amount of lsd tokens deposited and withdrawn through mint, burn and decompound
+// Lsd tokens sent to the wrapper by any other mean are a surplus, they don't back the wrapper token until the admin allocates them
+pub const LSD_LEDGER: Item<Uint128> = Item::new("lsd_ledger");
+
+// Circuit breaker on the lsd exchange rate, disabled until the admin sets a maximum rate change
+pub const RATE_GUARD: Item<RateGuard> = Item::new("rate_guard");
+
+// Mint from native tokens waiting for the lsd tokens to be received, only set during the transaction of the mint
+pub const PENDING_NATIVE_MINT: Item<PendingNativeMint> = Item::new("pending_native_mint");
+
+// Dex routers used to swap the lsd tokens released by `BurnAndSwap`, unset until the admin sets them
+pub const SWAP_CONFIG: Item<SwapConfig> = Item::new("swap_config");
+
+// Fee charged on flash loans, flash loans are disabled until the admin sets it
+pub const FLASH_LOAN_FEE: Item<Decimal> = Item::new("flash_loan_fee");
+// Flash loan waiting to be paid back, only set during the transaction of the loan
+pub const FLASH_LOAN: Item<FlashLoan> = Item::new("flash_loan");
+
+// Last loss recorded while the wrapper was under-collateralized
+pub const SLASHING_LOSS: Item<SlashingLoss> = Item::new("slashing_loss");
+
+// Exchange rates recorded on mint, burn and decompound, indexed by block time (in seconds)
+pub const EXCHANGE_RATE_HISTORY: Map<u64, ExchangeRateSnapshot> = Map::new("exchange_rate_history");
 
 #[cw_serde]
 pub struct LsdContracts {
@@ -22,6 +73,83 @@ pub fn read_hub_contract(storage: &dyn Storage) -> StdResult<Addr> {
     HUB_CONTRACT_KEY.load(storage)
 }
 
+// Wrappers instantiated before the pause switch existed are not paused
+pub fn read_pause_state(storage: &dyn Storage) -> StdResult<PauseState> {
+    Ok(PAUSE_STATE.may_load(storage)?.unwrap_or_default())
+}
+
+// Wrappers instantiated before the mint limits existed are not limited
+pub fn read_mint_limits(storage: &dyn Storage) -> StdResult<MintLimits> {
+    Ok(MINT_LIMITS.may_load(storage)?.unwrap_or_default())
+}
+
+// Wrappers instantiated before the circuit breaker existed have no rate guard
+pub fn read_rate_guard(storage: &dyn Storage) -> StdResult<RateGuard> {
+    Ok(RATE_GUARD.may_load(storage)?.unwrap_or_default())
+}
+
+// Wrappers instantiated before the retention ratio existed keep no rewards
+pub fn read_retention_ratio(storage: &dyn Storage) -> StdResult<Decimal> {
+    Ok(RETENTION_RATIO.may_load(storage)?.unwrap_or_default())
+}
+
+// Wrappers that never retained any rewards are brought back to 1 underlying token per wrapper token
+pub fn read_target_exchange_rate(storage: &dyn Storage) -> StdResult<Decimal> {
+    Ok(TARGET_EXCHANGE_RATE.may_load(storage)?.unwrap_or(Decimal::one()))
+}
+
+pub fn increase_lsd_ledger(storage: &mut dyn Storage, amount: Uint128) -> StdResult<Uint128> {
+    LSD_LEDGER.update(storage, |ledger| -> StdResult<_> { Ok(ledger.checked_add(amount)?) })
+}
+
+pub fn decrease_lsd_ledger(storage: &mut dyn Storage, amount: Uint128) -> StdResult<Uint128> {
+    LSD_LEDGER.update(storage, |ledger| -> StdResult<_> { Ok(ledger.checked_sub(amount)?) })
+}
+
+/// Records the balance `account` had when the snapshots started, before it changes for the first time
+pub fn start_balance_snapshots(storage: &mut dyn Storage, account: &Addr) -> StdResult<()> {
+    let tracked = BALANCE_SNAPSHOTS
+        .prefix(account)
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if !tracked {
+        let balance = BALANCES.may_load(storage, account)?.unwrap_or_default();
+        let start = SNAPSHOT_START.load(storage)?;
+        BALANCE_SNAPSHOTS.save(storage, (account, start), &balance)?;
+    }
+    Ok(())
+}
+
+/// Records the total supply when the snapshots started, before it changes for the first time
+pub fn start_supply_snapshots(storage: &mut dyn Storage) -> StdResult<()> {
+    let tracked = SUPPLY_SNAPSHOTS
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if !tracked {
+        let total_supply = TOKEN_INFO.load(storage)?.total_supply;
+        let start = SNAPSHOT_START.load(storage)?;
+        SUPPLY_SNAPSHOTS.save(storage, start, &total_supply)?;
+    }
+    Ok(())
+}
+
+/// Records the current balances of `accounts` and the current total supply at `height`
+pub fn record_snapshots(storage: &mut dyn Storage, height: u64, accounts: &[&Addr]) -> StdResult<()> {
+    for account in accounts {
+        let balance = BALANCES.may_load(storage, account)?.unwrap_or_default();
+        BALANCE_SNAPSHOTS.save(storage, (account, height), &balance)?;
+    }
+    let total_supply = TOKEN_INFO.load(storage)?.total_supply;
+    SUPPLY_SNAPSHOTS.save(storage, height, &total_supply)
+}
+
+// Wrappers instantiated before the reward split existed send all the rewards to the decompound recipient
+pub fn read_reward_split(storage: &dyn Storage) -> StdResult<Vec<RewardShare>> {
+    Ok(REWARD_SPLIT.may_load(storage)?.unwrap_or_default())
+}
+
 pub fn store_hub_contract(storage: &mut dyn Storage, hub_contract: &Addr) -> StdResult<()> {
     HUB_CONTRACT_KEY.save(storage, hub_contract)
 }
@@ -43,13 +171,87 @@ pub fn store_lsd_config<
     Item::new(LSD_CONFIG_KEY).save(storage, lsd_config)
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseState {
+    pub mint: bool,
+    pub burn: bool,
+    pub decompound: bool,
+}
+
+#[cw_serde]
+pub struct AdminProposal {
+    pub admin: Addr,
+    // Block time (in seconds) after which the proposal can't be claimed anymore
+    pub expiry: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct MintLimits {
+    pub max_mint_per_address: Option<Uint128>,
+    // Length of the per-address mint window in seconds
+    pub mint_window: u64,
+}
+
+#[cw_serde]
+pub struct RewardShare {
+    pub address: Addr,
+    pub ratio: Decimal,
+}
+
+#[cw_serde]
+pub struct AddressMints {
+    pub window_start: u64,
+    pub minted: Uint128,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct RateGuard {
+    // Maximum relative change of the lsd exchange rate per hour
+    pub max_change_per_hour: Option<Decimal>,
+    // Reference lsd exchange rate, renewed at most once per hour
+    pub last_rate: Option<Decimal>,
+    // Block time (in seconds) at which the reference rate was accepted
+    pub last_update: u64,
+    // Set by `CheckLsdExchangeRate` when the limit is exceeded, until the admin updates the circuit breaker
+    pub tripped: bool,
+}
+
+#[cw_serde]
+pub struct PendingNativeMint {
+    // Sender of the native tokens, the mint limit applies to them
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub min_received: Option<Uint128>,
+    // Lsd balance of the wrapper before the native tokens were bonded
+    pub lsd_balance: Uint128,
+}
+
+#[cw_serde]
+pub struct FlashLoan {
+    // Lsd balance and ledger of the wrapper before the tokens were lent
+    pub lsd_balance: Uint128,
+    pub ledger: Uint128,
+    pub fee: Uint128,
+}
+
+#[cw_serde]
+pub struct ExchangeRateSnapshot {
+    pub exchange_rate: Decimal,
+    pub lsd_exchange_rate: Decimal,
+}
+
 #[cw_serde]
 #[derive(Default)]
 pub struct WrapperState {
     pub lsd_exchange_rate: Decimal,
     pub wlsd_supply: Uint128,
-    pub backing_luna: Decimal,
+    // Rounded down to the unit
+    pub backing_luna: Uint128,
     pub lsd_balance: Uint128,
+    pub target_exchange_rate: Decimal,
 }
 
 #[cfg(test)]
@@ -1,7 +1,11 @@
 use cosmwasm_std::MessageInfo;
 use serde::{Deserialize, Serialize};
 
+use basset::dex_router::AssetInfo;
+
 use cosmwasm_std::Addr;
+use cosmwasm_std::Binary;
+use cosmwasm_std::Coin;
 use cosmwasm_std::CosmosMsg;
 use cosmwasm_std::Decimal;
 use cosmwasm_std::Deps;
@@ -15,6 +19,11 @@ pub trait LSDHub<I: for<'a> Deserialize<'a> + Serialize> {
         Self: std::marker::Sized;
     fn query_exchange_rate(&self, deps: Deps, env: Env) -> StdResult<Decimal>;
     fn get_balance(&self, deps: Deps, env: Env, address: Addr) -> StdResult<Uint128>;
+    /// Address of the lsd token contract, if the lsd is a cw20 token.
+    /// Only this contract is allowed to mint wrapper tokens through the cw20 `Receive` hook
+    fn cw20_token(&self) -> Option<Addr>;
+    /// Lsd token, as swapped on the dexes by `BurnAndSwap`
+    fn lsd_asset_info(&self) -> AssetInfo;
     fn deposit_funds(
         &self,
         deps: Deps,
@@ -30,4 +39,17 @@ pub trait LSDHub<I: for<'a> Deserialize<'a> + Serialize> {
         amount: Uint128,
         to: Addr,
     ) -> StdResult<Vec<CosmosMsg>>;
+    /// Sends lsd tokens to the `contract` and executes `msg` on it.
+    /// cw20 lsds use a cw20 `Send`, native lsds attach the tokens to the contract call
+    fn send_funds_with_msg(
+        &self,
+        deps: Deps,
+        env: Env,
+        amount: Uint128,
+        contract: Addr,
+        msg: Binary,
+    ) -> StdResult<Vec<CosmosMsg>>;
+    /// Turns the native `funds` into lsd tokens held by the wrapper, for `MintFromNative`.
+    /// The wrapper mints for the increase of its lsd balance once all these messages are executed
+    fn bond_funds(&self, deps: Deps, env: Env, funds: Vec<Coin>) -> StdResult<Vec<CosmosMsg>>;
 }
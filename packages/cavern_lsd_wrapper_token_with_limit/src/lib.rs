@@ -2,6 +2,6 @@ pub mod contract;
 pub mod msg;
 pub mod state;
 
-mod handler;
-mod querier;
+pub mod handler;
+pub mod querier;
 pub mod trait_def;
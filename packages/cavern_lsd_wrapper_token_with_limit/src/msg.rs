@@ -17,5 +17,24 @@ pub struct TokenInitMsg<I> {
     // only hub contract can call decompound
     pub hub_contract: String,
 
+    // can pause mint, burn and decompound operations
+    pub guardian: Option<String>,
+
+    // can update the mint limits, defaults to the instantiator
+    pub admin: Option<String>,
+
     pub lsd_config: I,
 }
+
+#[cw_serde]
+pub struct MigrateMsg {
+    // sets the admin, wrappers instantiated before the admin role existed don't have one
+    pub admin: Option<String>,
+
+    // sets the guardian
+    pub guardian: Option<String>,
+
+    // sets the maximum decompound ratio per year
+    // wrappers migrated from the plain wrapper package use it to initialize their decompound limit
+    pub max_decompound_ratio: Option<Decimal>,
+}
@@ -4,14 +4,27 @@ use serde::Serialize;
 use cosmwasm_std::Decimal;
 use cosmwasm_std::Deps;
 use cosmwasm_std::Env;
+use cosmwasm_std::Order;
+use cosmwasm_std::StdError;
+use cosmwasm_std::StdResult;
 use cosmwasm_std::Uint128;
 
 use cw20_base::contract::query_token_info;
 use cw20_base::ContractError;
+use cw_storage_plus::Bound;
 
-use crate::contract::SECONDS_PER_YEAR;
-use crate::state::{DECOMPOUND_CONFIG, DECOMPOUND_STATE};
+use basset::math::{multiply_ratio, Rounding};
+use basset::wrapper::{ExchangeRateEntry, ExchangeRatesResponse, LsdLedgerResponse, TotalSupplyResponse};
+use cw20::BalanceResponse;
+use cw20_base::state::BALANCES;
+
+use crate::contract::{DECOMPOUND_EPOCH, DECOMPOUND_WINDOW, SECONDS_PER_YEAR};
+use crate::state::{DECOMPOUND_CONFIG, DECOMPOUND_EPOCHS, DECOMPOUND_STATE};
 use crate::state::read_lsd_config;
+use crate::state::{read_retention_ratio, read_target_exchange_rate};
+use crate::state::EXCHANGE_RATE_HISTORY;
+use crate::state::LSD_LEDGER;
+use crate::state::{BALANCE_SNAPSHOTS, SNAPSHOT_START, SUPPLY_SNAPSHOTS};
 use crate::state::WrapperState;
 use crate::trait_def::LSDHub;
 
@@ -27,11 +40,11 @@ pub fn get_current_exchange_rate<
     let lsd_config: T = read_lsd_config(deps.storage)?;
     let lsd_exchange_rate = lsd_config.query_exchange_rate(deps, env.clone())?; // This is the exchange rate underlyingToken/LSD
 
-    // We query how much lsd tokens the contract holds
-    let balance: Uint128 = lsd_config.get_balance(deps, env.clone(), env.contract.address)?;
+    // We only account for the lsd tokens deposited through the wrapper, donations can't move the exchange rate
+    let balance: Uint128 = LSD_LEDGER.load(deps.storage)?;
 
-    // We now have the number of underlying lunas backing the token
-    let luna_backing_token: Decimal = Decimal::from_ratio(balance, 1u128) * lsd_exchange_rate;
+    // We now have the number of underlying lunas backing the token, rounded down
+    let luna_backing_token = multiply_ratio(balance, lsd_exchange_rate.atomics(), Decimal::one().atomics(), Rounding::Down)?;
 
     // We can divide that by the number of issued tokens to get the exchange rate
     let total_wlsd_supply = query_token_info(deps)?.total_supply;
@@ -40,12 +53,16 @@ pub fn get_current_exchange_rate<
     state.wlsd_supply = total_wlsd_supply;
     state.backing_luna = luna_backing_token;
     state.lsd_balance = balance;
+    state.target_exchange_rate = read_target_exchange_rate(deps.storage)?;
 
     // Luna / WLSD
+    // Without any supply, the wrapper token is worth what the decompound would bring it back to
     if total_wlsd_supply.is_zero() {
-        Ok(Decimal::one())
+        Ok(state.target_exchange_rate)
     } else {
-        Ok(luna_backing_token / total_wlsd_supply)
+        // The exchange rate is computed from the lsd balance directly, it is rounded down to its last decimal
+        let exchange_rate = multiply_ratio(balance, lsd_exchange_rate.atomics(), total_wlsd_supply, Rounding::Down)?;
+        Ok(Decimal::raw(exchange_rate.u128()))
     }
 }
 
@@ -62,20 +79,22 @@ pub fn get_expected_exchange_rate<
 
     let exchange_rate = get_current_exchange_rate::<I,T>(deps, env.clone(), state)?;
 
-    // If the exchange rate is lower than 1, we return it,
+    // If the exchange rate is lower than the target exchange rate, we return it,
     // The token has had a slashing event 
-    if exchange_rate < Decimal::one(){
+    if exchange_rate < state.target_exchange_rate{
         return Ok(exchange_rate);
     }
 
+    // The decompound can't go below the target exchange rate increased by the retained share of the rewards
+    let retained_exchange_rate = state.target_exchange_rate
+        + read_retention_ratio(deps.storage)? * (exchange_rate - state.target_exchange_rate);
+
     // Then if there is a maximum_decompound ratio, we try to get the expected exchange rate
     if let Some(max_decompound_ratio) = DECOMPOUND_CONFIG.load(deps.storage)?.max_decompound_ratio {
-        let state = DECOMPOUND_STATE.load(deps.storage)?;
-
-        let time_since_start = state.total_seconds + (env.block.time.seconds() - state.last_decompound.seconds());
+        let (_, remaining_ratio) = get_decompound_budget(deps, &env, max_decompound_ratio)?;
 
-        let mut expected_exchange_rate = (exchange_rate + state.ratio_sum).checked_sub(max_decompound_ratio * Decimal::from_ratio(time_since_start, SECONDS_PER_YEAR)).unwrap_or(Decimal::one());
-        expected_exchange_rate = expected_exchange_rate.max(Decimal::one());
+        let mut expected_exchange_rate = exchange_rate.checked_sub(remaining_ratio).unwrap_or(retained_exchange_rate);
+        expected_exchange_rate = expected_exchange_rate.max(retained_exchange_rate);
 
         return Ok(expected_exchange_rate)
     }
@@ -84,18 +103,190 @@ pub fn get_expected_exchange_rate<
 }
 
 
-/// Queries the exchange rate lsd <-> Wrapper token (how much wrapper token for 1 LSD amount)
-/// This only requires querying the amount of LSD tokens locked in the contract
-pub fn get_lsd_wrapper_exchange_rate<
-    I: Serialize + for<'b> Deserialize<'b>,
-    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
->(deps: Deps, env: Env) -> Result<Decimal, ContractError>{
+/// Returns the decrease of the exchange rate caused by the decompounds of the trailing window, along with the decrease the decompound can still cause.
+/// The limit accrues at `max_decompound_ratio` per year from the start of the limiter, over one window at most,
+/// so that idle periods can't build up a budget released all at once
+pub fn get_decompound_budget(
+    deps: Deps,
+    env: &Env,
+    max_decompound_ratio: Decimal,
+) -> StdResult<(Decimal, Decimal)> {
+    let state = DECOMPOUND_STATE.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    let window_start = now.saturating_sub(DECOMPOUND_WINDOW).max(state.limiter_start());
 
-    let lsd_config: T = read_lsd_config(deps.storage)?;
-    let total_lsd_balance = lsd_config.get_balance(deps, env.clone(), env.contract.address)?;
+    // Epochs starting before the window are counted whole
+    let released_ratio = DECOMPOUND_EPOCHS
+        .range(
+            deps.storage,
+            Some(Bound::inclusive(window_start / DECOMPOUND_EPOCH)),
+            None,
+            Order::Ascending,
+        )
+        .try_fold(Decimal::zero(), |total, item| -> StdResult<_> {
+            Ok(total.checked_add(item?.1)?)
+        })?;
+    let allowed_ratio = max_decompound_ratio
+        * Decimal::from_ratio(now.saturating_sub(window_start), SECONDS_PER_YEAR);
+
+    Ok((
+        released_ratio,
+        allowed_ratio.checked_sub(released_ratio).unwrap_or_default(),
+    ))
+}
+
+/// Queries the exchange rate lsd <-> Wrapper token, as the wrapper supply and the lsd balance backing it
+/// (how much wrapper token for 1 LSD amount is `supply / lsd_balance`). The exchange rate is 1 while the wrapper is empty
+/// This only requires reading the amount of LSD tokens recorded in the wrapper ledger
+/// Deposits of the current message are recorded after the exchange rate is computed, so they are never accounted for
+pub fn get_lsd_wrapper_exchange_rate(deps: Deps) -> Result<(Uint128, Uint128), ContractError> {
+    let total_lsd_balance = LSD_LEDGER.load(deps.storage)?;
     let total_supply = query_token_info(deps)?.total_supply;
     if total_lsd_balance.is_zero() || total_supply.is_zero(){
-        return Ok(Decimal::one())
+        return Ok((Uint128::one(), Uint128::one()))
+    }
+    Ok((total_supply, total_lsd_balance))
+}
+
+/// Luna missing for the backing of the supply to reach the target exchange rate, once the state is filled
+pub fn compute_luna_loss(state: &WrapperState) -> StdResult<Uint128> {
+    let target_backing_luna = multiply_ratio(
+        state.wlsd_supply,
+        state.target_exchange_rate.atomics(),
+        Decimal::one().atomics(),
+        Rounding::Up,
+    )?;
+    Ok(target_backing_luna.saturating_sub(state.backing_luna))
+}
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Returns the recorded exchange rates, from the most recent to the oldest
+/// The APRs are computed between the oldest and the most recent returned snapshots
+pub fn query_exchange_rates(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ExchangeRatesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_after.map(Bound::exclusive);
+
+    let exchange_rates = EXCHANGE_RATE_HISTORY
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| {
+            let (time, snapshot) = item?;
+            Ok(ExchangeRateEntry {
+                time,
+                exchange_rate: snapshot.exchange_rate,
+                lsd_exchange_rate: snapshot.lsd_exchange_rate,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let (apr, lsd_apr) = match (exchange_rates.first(), exchange_rates.last()) {
+        (Some(newest), Some(oldest)) if newest.time > oldest.time => {
+            let period = newest.time - oldest.time;
+            (
+                Some(compute_apr(oldest.exchange_rate, newest.exchange_rate, period)),
+                Some(compute_apr(oldest.lsd_exchange_rate, newest.lsd_exchange_rate, period)),
+            )
+        }
+        _ => (None, None),
+    };
+
+    Ok(ExchangeRatesResponse {
+        exchange_rates,
+        apr,
+        lsd_apr,
+    })
+}
+
+/// Yearly rate of increase from `old_rate` to `new_rate` over `period` seconds
+fn compute_apr(old_rate: Decimal, new_rate: Decimal, period: u64) -> Decimal {
+    if old_rate.is_zero() {
+        return Decimal::zero();
+    }
+    let rate_increase = new_rate.checked_sub(old_rate).unwrap_or_default();
+    rate_increase / old_rate * Decimal::from_ratio(SECONDS_PER_YEAR, period)
+}
+
+/// Returns the lsd tokens accounted for by the wrapper ledger and the lsd tokens the wrapper actually holds
+pub fn query_lsd_ledger<
+    I: Serialize + for<'b> Deserialize<'b>,
+    T: LSDHub<I> + Serialize + for<'a> Deserialize<'a>,
+>(
+    deps: Deps,
+    env: Env,
+) -> StdResult<LsdLedgerResponse> {
+    let lsd_config: T = read_lsd_config(deps.storage)?;
+    let ledger = LSD_LEDGER.load(deps.storage)?;
+    let lsd_balance = lsd_config.get_balance(deps, env.clone(), env.contract.address)?;
+
+    Ok(LsdLedgerResponse {
+        ledger,
+        lsd_balance,
+        surplus: lsd_balance.saturating_sub(ledger),
+        deficit: ledger.saturating_sub(lsd_balance),
+    })
+}
+
+/// Height snapshots are available from, fails for heights before the wrapper started recording snapshots
+fn assert_snapshot_height(deps: Deps, height: u64) -> StdResult<()> {
+    let start = SNAPSHOT_START.load(deps.storage)?;
+    if height < start {
+        return Err(StdError::generic_err(format!(
+            "Snapshots are only available from height {}",
+            start
+        )));
     }
-    Ok(Decimal::from_ratio(total_supply, total_lsd_balance))
-}
\ No newline at end of file
+    Ok(())
+}
+
+/// Returns the balance of `address` at the end of `height`, from the last snapshot recorded up to that height.
+/// Balances without snapshots up to that height didn't change since, so they are returned as is
+pub fn query_balance_at_height(
+    deps: Deps,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<BalanceResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let balance = match height {
+        Some(height) => {
+            assert_snapshot_height(deps, height)?;
+            BALANCE_SNAPSHOTS
+                .prefix(&address)
+                .range(deps.storage, None, Some(Bound::inclusive(height)), Order::Descending)
+                .next()
+                .transpose()?
+                .map(|(_, balance)| balance)
+        }
+        None => None,
+    };
+    let balance = match balance {
+        Some(balance) => balance,
+        None => BALANCES.may_load(deps.storage, &address)?.unwrap_or_default(),
+    };
+    Ok(BalanceResponse { balance })
+}
+
+/// Returns the total supply at the end of `height`, from the last snapshot recorded up to that height
+pub fn query_total_supply_at_height(deps: Deps, height: Option<u64>) -> StdResult<TotalSupplyResponse> {
+    let total_supply = match height {
+        Some(height) => {
+            assert_snapshot_height(deps, height)?;
+            SUPPLY_SNAPSHOTS
+                .range(deps.storage, None, Some(Bound::inclusive(height)), Order::Descending)
+                .next()
+                .transpose()?
+                .map(|(_, total_supply)| total_supply)
+        }
+        None => None,
+    };
+    let total_supply = match total_supply {
+        Some(total_supply) => total_supply,
+        None => query_token_info(deps)?.total_supply,
+    };
+    Ok(TotalSupplyResponse { total_supply })
+}
@@ -1,7 +1,10 @@
 use cosmwasm_std::MessageInfo;
 use serde::{Deserialize, Serialize};
 
+use basset::dex_router::AssetInfo;
+
 use cosmwasm_std::Addr;
+use cosmwasm_std::Binary;
 use cosmwasm_std::CosmosMsg;
 use cosmwasm_std::Decimal;
 use cosmwasm_std::Deps;
@@ -15,6 +18,11 @@ pub trait LSDHub<I: for<'a> Deserialize<'a> + Serialize> {
         Self: std::marker::Sized;
     fn query_exchange_rate(&self, deps: Deps, env: Env) -> StdResult<Decimal>;
     fn get_balance(&self, deps: Deps, env: Env, address: Addr) -> StdResult<Uint128>;
+    /// Address of the lsd token contract, if the lsd is a cw20 token.
+    /// Only this contract is allowed to mint wrapper tokens through the cw20 `Receive` hook
+    fn cw20_token(&self) -> Option<Addr>;
+    /// Lsd token held by the wrapper, it can't change once the wrapper is instantiated
+    fn lsd_asset_info(&self) -> AssetInfo;
     fn deposit_funds(
         &self,
         deps: Deps,
@@ -30,4 +38,14 @@ pub trait LSDHub<I: for<'a> Deserialize<'a> + Serialize> {
         amount: Uint128,
         to: Addr,
     ) -> StdResult<Vec<CosmosMsg>>;
+    /// Sends lsd tokens to the `contract` and executes `msg` on it.
+    /// cw20 lsds use a cw20 `Send`, native lsds attach the tokens to the contract call
+    fn send_funds_with_msg(
+        &self,
+        deps: Deps,
+        env: Env,
+        amount: Uint128,
+        contract: Addr,
+        msg: Binary,
+    ) -> StdResult<Vec<CosmosMsg>>;
 }
@@ -1,3 +1,5 @@
+use basset::dex_router::{AssetInfo, ExecuteMsg as RouterExecuteMsg, SwapOperation};
+use basset::math::{multiply_ratio, Rounding};
 use basset::price_querier::query_price;
 use cosmwasm_std::BalanceResponse;
 use cosmwasm_std::BankMsg;
@@ -7,12 +9,14 @@ use cosmwasm_std::Coin;
 use std::convert::TryInto;
 
 use basset::wrapper::ExecuteMsg;
+pub use cavern_lsd_wrapper_token::msg::MigrateMsg;
 use cavern_lsd_wrapper_token::msg::TokenInitMsg;
 use cosmwasm_std::DepsMut;
 use cosmwasm_std::MessageInfo;
+use cosmwasm_std::Reply;
 use cosmwasm_std::Response;
 use cosmwasm_std::StdError;
-use cw20_base::msg::QueryMsg;
+use basset::wrapper::QueryMsg;
 use cw20_base::ContractError;
 
 use cosmwasm_schema::cw_serde;
@@ -21,6 +25,8 @@ use cosmwasm_std::CosmosMsg;
 
 use cosmwasm_std::Env;
 use cosmwasm_std::QueryRequest;
+use cosmwasm_std::WasmMsg;
+use cosmwasm_std::to_binary;
 use cosmwasm_std::Uint128;
 
 use cosmwasm_std::Deps;
@@ -35,6 +41,10 @@ pub struct StrideLSDConfigRaw {
     pub denom: String,
     pub underlying_token_denom: String,
     pub oracle_contract: String,
+    /// Dex router used to mint from the underlying token, as stride tokens can't be bonded from Terra
+    pub swap_router: Option<String>,
+    /// Maximum spread from the oracle price accepted when swapping the underlying token, needed to mint from it
+    pub max_spread: Option<Decimal>,
 }
 
 #[cw_serde]
@@ -42,14 +52,29 @@ pub struct StrideLSDConfig {
     pub denom: String,
     pub underlying_token_denom: String,
     pub oracle_contract: Addr,
+    pub swap_router: Option<Addr>,
+    pub max_spread: Option<Decimal>,
 }
 
 impl LSDHub<StrideLSDConfigRaw> for StrideLSDConfig {
     fn instantiate_config(deps: Deps, config: StrideLSDConfigRaw) -> StdResult<Self> {
+        if let Some(max_spread) = config.max_spread {
+            if max_spread >= Decimal::one() {
+                return Err(StdError::generic_err(format!(
+                    "The maximum spread needs to be lower than 1, got {}",
+                    max_spread
+                )));
+            }
+        }
         Ok(Self {
             denom: config.denom,
             oracle_contract: deps.api.addr_validate(&config.oracle_contract)?,
             underlying_token_denom: config.underlying_token_denom,
+            swap_router: config
+                .swap_router
+                .map(|router| deps.api.addr_validate(&router))
+                .transpose()?,
+            max_spread: config.max_spread,
         })
     }
 
@@ -80,6 +105,17 @@ impl LSDHub<StrideLSDConfigRaw> for StrideLSDConfig {
         Ok(balance.amount.amount)
     }
 
+    fn cw20_token(&self) -> Option<Addr> {
+        // Stride tokens are native tokens, they are deposited along with the mint messages
+        None
+    }
+
+    fn lsd_asset_info(&self) -> AssetInfo {
+        AssetInfo::NativeToken {
+            denom: self.denom.clone(),
+        }
+    }
+
     fn deposit_funds(
         &self,
         _deps: Deps,
@@ -98,7 +134,18 @@ impl LSDHub<StrideLSDConfigRaw> for StrideLSDConfig {
             )));
         }
 
-        Ok(vec![])
+        // The funds sent in excess are refunded to the sender
+        let excess = info.funds[0].amount - amount;
+        if excess.is_zero() {
+            return Ok(vec![]);
+        }
+        Ok(vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: self.denom.clone(),
+                amount: excess,
+            }],
+        })])
     }
 
     fn send_funds(
@@ -116,6 +163,67 @@ impl LSDHub<StrideLSDConfigRaw> for StrideLSDConfig {
             }],
         })])
     }
+
+    fn send_funds_with_msg(
+        &self,
+        _deps: Deps,
+        _env: Env,
+        amount: Uint128,
+        contract: Addr,
+        msg: Binary,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg,
+            funds: vec![Coin {
+                denom: self.denom.clone(),
+                amount,
+            }],
+        })])
+    }
+
+    fn bond_funds(&self, deps: Deps, env: Env, funds: Vec<Coin>) -> StdResult<Vec<CosmosMsg>> {
+        // Stride tokens can't be bonded from Terra, the underlying tokens are swapped on a dex instead
+        let swap_router = self.swap_router.clone().ok_or_else(|| {
+            StdError::generic_err("No swap router is set to mint from the underlying token")
+        })?;
+        if funds.len() != 1 || funds[0].denom != self.underlying_token_denom {
+            return Err(StdError::generic_err(format!(
+                "You need to deposit {} tokens only, deposited {:?}",
+                self.underlying_token_denom, funds
+            )));
+        }
+        // The swap can't return less than the oracle price allows, minus the maximum spread
+        let max_spread = self.max_spread.ok_or_else(|| {
+            StdError::generic_err("No maximum spread is set to mint from the underlying token")
+        })?;
+        let exchange_rate = self.query_exchange_rate(deps, env)?;
+        let minimum_receive = multiply_ratio(
+            funds[0].amount,
+            (Decimal::one() - max_spread).atomics(),
+            exchange_rate.atomics(),
+            Rounding::Down,
+        )?;
+
+        Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: swap_router.to_string(),
+            msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations: vec![SwapOperation::AstroSwap {
+                    offer_asset_info: AssetInfo::NativeToken {
+                        denom: self.underlying_token_denom.clone(),
+                    },
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: self.denom.clone(),
+                    },
+                }],
+                // The minimum amount of wrapper tokens is checked once the swap is done
+                minimum_receive: Some(minimum_receive),
+                to: None,
+                max_spread: Some(max_spread),
+            })?,
+            funds,
+        })])
+    }
 }
 
 pub type StrideInitMsg = TokenInitMsg<StrideLSDConfigRaw>;
@@ -145,3 +253,23 @@ pub fn execute(
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     cavern_lsd_wrapper_token::contract::query::<StrideLSDConfigRaw, StrideLSDConfig>(deps, env, msg)
 }
+
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    cavern_lsd_wrapper_token::contract::reply::<StrideLSDConfigRaw, StrideLSDConfig>(deps, env, msg)
+}
+
+pub fn migrate(
+    deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+    contract_name: &str,
+    contract_version: &str,
+) -> Result<Response, ContractError> {
+    cavern_lsd_wrapper_token::contract::migrate::<StrideLSDConfigRaw, StrideLSDConfig>(
+        deps,
+        env,
+        msg,
+        contract_name,
+        contract_version,
+    )
+}
@@ -1,10 +1,12 @@
 use basset::wrapper::ExecuteMsg;
+pub use cavern_lsd_wrapper_token::msg::MigrateMsg;
 use cavern_lsd_wrapper_token::msg::TokenInitMsg;
 use cosmwasm_std::Binary;
 use cosmwasm_std::DepsMut;
 use cosmwasm_std::MessageInfo;
+use cosmwasm_std::Reply;
 use cosmwasm_std::Response;
-use cw20_base::msg::QueryMsg;
+use basset::wrapper::QueryMsg;
 use cw20_base::ContractError;
 
 use serde::Deserialize;
@@ -13,6 +15,7 @@ use std::marker::PhantomData;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::to_binary;
 use cosmwasm_std::Addr;
+use cosmwasm_std::Coin;
 use cosmwasm_std::CosmosMsg;
 use cosmwasm_std::Deps;
 use cosmwasm_std::Env;
@@ -26,6 +29,8 @@ use cw20::BalanceResponse;
 use cw20::Cw20ExecuteMsg;
 use cw20::Cw20QueryMsg;
 
+use basset::dex_router::AssetInfo;
+use basset::external::LSDExecuteMsg;
 use basset::external::LSDQueryMsg;
 use basset::external::LSDStateResponseTrait;
 use cavern_lsd_wrapper_token::trait_def::LSDHub;
@@ -95,6 +100,16 @@ impl<T: LSDStateResponseTrait + for<'a> Deserialize<'a>> LSDHub<LsdContractsRaw>
         Ok(balance.balance)
     }
 
+    fn cw20_token(&self) -> Option<Addr> {
+        Some(self.lsd_contracts.token.clone())
+    }
+
+    fn lsd_asset_info(&self) -> AssetInfo {
+        AssetInfo::Token {
+            contract_addr: self.lsd_contracts.token.clone(),
+        }
+    }
+
     fn deposit_funds(
         &self,
         _deps: Deps,
@@ -130,6 +145,35 @@ impl<T: LSDStateResponseTrait + for<'a> Deserialize<'a>> LSDHub<LsdContractsRaw>
             funds: vec![],
         })])
     }
+
+    fn send_funds_with_msg(
+        &self,
+        _deps: Deps,
+        _env: Env,
+        amount: Uint128,
+        contract: Addr,
+        msg: Binary,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.lsd_contracts.token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: contract.to_string(),
+                amount,
+                msg,
+            })?,
+            funds: vec![],
+        })])
+    }
+
+    fn bond_funds(&self, _deps: Deps, env: Env, funds: Vec<Coin>) -> StdResult<Vec<CosmosMsg>> {
+        Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.lsd_contracts.hub.to_string(),
+            msg: to_binary(&LSDExecuteMsg::Bond {
+                receiver: Some(env.contract.address.to_string()),
+            })?,
+            funds,
+        })])
+    }
 }
 
 pub type SteakInitMsg = TokenInitMsg<LsdContractsRaw>;
@@ -163,3 +207,27 @@ pub fn query<T: LSDStateResponseTrait + for<'a> Deserialize<'a>>(
 ) -> StdResult<Binary> {
     cavern_lsd_wrapper_token::contract::query::<LsdContractsRaw, SteakLSDHub<T>>(deps, env, msg)
 }
+
+pub fn reply<T: LSDStateResponseTrait + for<'a> Deserialize<'a>>(
+    deps: DepsMut,
+    env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    cavern_lsd_wrapper_token::contract::reply::<LsdContractsRaw, SteakLSDHub<T>>(deps, env, msg)
+}
+
+pub fn migrate<T: LSDStateResponseTrait + for<'a> Deserialize<'a>>(
+    deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+    contract_name: &str,
+    contract_version: &str,
+) -> Result<Response, ContractError> {
+    cavern_lsd_wrapper_token::contract::migrate::<LsdContractsRaw, SteakLSDHub<T>>(
+        deps,
+        env,
+        msg,
+        contract_name,
+        contract_version,
+    )
+}
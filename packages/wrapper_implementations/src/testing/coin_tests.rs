@@ -5,17 +5,20 @@ use std::str::FromStr;
 
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    coins, to_binary, Api, CosmosMsg, Decimal, DepsMut, OwnedDeps, Storage, SubMsg, Uint128,
+    coins, from_binary, to_binary, Api, BankMsg, CosmosMsg, Decimal, DepsMut, OwnedDeps, Reply, StdError,
+    Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 
 use cw20::{Cw20ReceiveMsg, MinterResponse, TokenInfoResponse};
-use cw20_base::contract::{query_minter, query_token_info};
+use cw20_base::contract::{query_balance, query_minter, query_token_info};
 
-use basset::wrapper::ExecuteMsg;
+use basset::dex_router::{AssetInfo, ExecuteMsg as RouterExecuteMsg, SwapOperation};
+use basset::math::{multiply_ratio, Rounding};
+use basset::wrapper::{AccruedRewardsResponse, ExecuteMsg, QueryMsg, WrapperStateResponse};
 
-use crate::testing::mock_querier::MOCK_ORACLE_CONTRACT_ADDR;
-use crate::testing::mock_querier::{mock_dependencies, MOCK_LSD_DENOM};
-use cavern_lsd_wrapper_token::contract::{execute, instantiate};
+use crate::testing::mock_querier::{MOCK_ORACLE_CONTRACT_ADDR, MOCK_SWAP_ROUTER_ADDR};
+use crate::testing::mock_querier::{mock_dependencies, MOCK_LSD_DENOM, MOCK_LSD_UNDERLYING_DENOM};
+use cavern_lsd_wrapper_token::contract::{execute, instantiate, query, reply, NATIVE_MINT_REPLY_ID};
 use cavern_lsd_wrapper_token::msg::TokenInitMsg;
 
 use crate::coin::{StrideLSDConfig, StrideLSDConfigRaw};
@@ -45,6 +48,8 @@ fn _do_init<S: Storage, A: Api>(
         denom: "stluna".to_string(),
         underlying_token_denom: "uluna".to_string(),
         oracle_contract: MOCK_ORACLE_CONTRACT_ADDR.to_string(),
+        swap_router: Some(MOCK_SWAP_ROUTER_ADDR.to_string()),
+        max_spread: Some(Decimal::percent(1)),
     };
 
     let init_msg = TokenInitMsg {
@@ -54,6 +59,8 @@ fn _do_init<S: Storage, A: Api>(
         decimals: 6,
         initial_balances: vec![],
         hub_contract: MOCK_HUB_CONTRACT_ADDR.to_string(),
+        guardian: None,
+        admin: None,
         lsd_config,
     };
 
@@ -91,13 +98,14 @@ pub fn do_mint(deps: DepsMut, addr: String, amount: Uint128, exchange_rate: Deci
     let msg = ExecuteMsg::Mint {
         recipient: addr,
         amount,
+        max_lsd_in: None,
     };
     let minter = "any_person_really";
     let info = mock_info(
         minter,
         &[Coin {
-            amount: Decimal::from_ratio(amount, 1u128) / exchange_rate * Uint128::one()
-                + Uint128::one(),
+            amount: multiply_ratio(amount, Decimal::one().atomics(), exchange_rate.atomics(), Rounding::Up)
+                .unwrap(),
             denom: MOCK_LSD_DENOM.to_string(),
         }],
     );
@@ -115,6 +123,8 @@ fn proper_initialization() {
         denom: "stluna".to_string(),
         underlying_token_denom: "uluna".to_string(),
         oracle_contract: MOCK_ORACLE_CONTRACT_ADDR.to_string(),
+        swap_router: None,
+        max_spread: None,
     };
 
     let init_msg = TokenInitMsg {
@@ -124,6 +134,8 @@ fn proper_initialization() {
         decimals: 6,
         initial_balances: vec![],
         hub_contract: MOCK_HUB_CONTRACT_ADDR.to_string(),
+        guardian: None,
+        admin: None,
         lsd_config,
     };
     let info = mock_info(&String::from("owner"), &[]);
@@ -227,12 +239,53 @@ fn mint() {
     let msg = ExecuteMsg::Mint {
         recipient: addr,
         amount: Uint128::new(1u128),
+        max_lsd_in: None,
     };
 
     let _res = execute::<StrideLSDConfigRaw, StrideLSDConfig>(deps.as_mut(), mock_env(), info, msg)
         .unwrap();
 }
 
+#[test]
+fn mint_refunds_excess_funds() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr = String::from("addr0000");
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+
+    // 15 wrapper tokens need 10 lsd tokens at 1.5, the 2 extra lsd tokens are sent back
+    let info = mock_info("minter", &coins(12u128, MOCK_LSD_DENOM));
+    let msg = ExecuteMsg::Mint {
+        recipient: addr.clone(),
+        amount: Uint128::new(15u128),
+        max_lsd_in: None,
+    };
+    let res = execute::<StrideLSDConfigRaw, StrideLSDConfig>(deps.as_mut(), mock_env(), info, msg)
+        .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "minter".to_string(),
+            amount: coins(2u128, MOCK_LSD_DENOM),
+        }))]
+    );
+    assert_eq!(
+        query_balance(deps.as_ref(), addr).unwrap().balance,
+        Uint128::new(15u128)
+    );
+
+    // The rounding favors the wrapper, 16 wrapper tokens need 11 lsd tokens
+    let info = mock_info("minter", &coins(11u128, MOCK_LSD_DENOM));
+    let msg = ExecuteMsg::Mint {
+        recipient: "minter".to_string(),
+        amount: Uint128::new(16u128),
+        max_lsd_in: None,
+    };
+    let res = execute::<StrideLSDConfigRaw, StrideLSDConfig>(deps.as_mut(), mock_env(), info, msg)
+        .unwrap();
+    assert_eq!(res.messages, vec![]);
+}
+
 #[test]
 fn mint_multiple_exchange_rates() {
     let mut deps = mock_dependencies(&coins(2, "token"));
@@ -244,6 +297,7 @@ fn mint_multiple_exchange_rates() {
     let msg = ExecuteMsg::Mint {
         recipient: addr.clone(),
         amount: Uint128::new(1u128),
+        max_lsd_in: None,
     };
 
     let _res = execute::<StrideLSDConfigRaw, StrideLSDConfig>(deps.as_mut(), mock_env(), info, msg)
@@ -278,6 +332,89 @@ fn mint_multiple_exchange_rates() {
     );
 }
 
+#[test]
+fn wrapper_state_queries() {
+    // The wrapper holds 1100 lsd tokens
+    let mut deps = mock_dependencies(&coins(1100, MOCK_LSD_DENOM));
+    let addr = String::from("addr0000");
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        addr,
+        Uint128::from(1500u128),
+        Decimal::from_str("1.5").unwrap(),
+    );
+    // The lsd tokens that were not deposited through a mint only back the token once allocated
+    execute::<StrideLSDConfigRaw, StrideLSDConfig>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::AddSurplusToRewards {},
+    )
+    .unwrap();
+
+    // The token info is a plain cw20 response
+    let token_info: TokenInfoResponse = from_binary(
+        &query::<StrideLSDConfigRaw, StrideLSDConfig>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TokenInfo {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(token_info.total_supply, Uint128::from(1500u128));
+
+    let state: WrapperStateResponse = from_binary(
+        &query::<StrideLSDConfigRaw, StrideLSDConfig>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::WrapperState {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        state,
+        WrapperStateResponse {
+            lsd_exchange_rate: Decimal::from_str("1.5").unwrap(),
+            lsd_balance: Uint128::from(1100u128),
+            backing_luna: Decimal::from_str("1650").unwrap(),
+            wlsd_supply: Uint128::from(1500u128),
+            exchange_rate: Decimal::from_str("1.1").unwrap(),
+            expected_exchange_rate: None,
+        }
+    );
+
+    // 1000 lsd tokens are needed to back the supply, the rest can be decompounded
+    let rewards: AccruedRewardsResponse = from_binary(
+        &query::<StrideLSDConfigRaw, StrideLSDConfig>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AccruedRewards {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        rewards,
+        AccruedRewardsResponse {
+            luna_rewards: Uint128::from(150u128),
+            lsd_rewards: Uint128::from(100u128),
+            rate_decrease: None,
+        }
+    );
+
+    // This wrapper has no decompound limit
+    query::<StrideLSDConfigRaw, StrideLSDConfig>(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::DecompoundState {},
+    )
+    .unwrap_err();
+}
+
 #[test]
 fn burn() {
     let mut deps = mock_dependencies(&coins(2, "token"));
@@ -295,6 +432,8 @@ fn burn() {
     let info = mock_info(addr.as_str(), &[]);
     let msg = ExecuteMsg::Burn {
         amount: Uint128::new(1234u128),
+        min_received: None,
+        recipient: None,
     };
 
     let res = execute::<StrideLSDConfigRaw, StrideLSDConfig>(deps.as_mut(), mock_env(), info, msg)
@@ -338,6 +477,8 @@ fn burn_from() {
     let msg = ExecuteMsg::BurnFrom {
         owner: addr,
         amount: Uint128::new(1234u128),
+        min_received: None,
+        recipient: None,
     };
 
     let res = execute::<StrideLSDConfigRaw, StrideLSDConfig>(deps.as_mut(), mock_env(), info, msg)
@@ -449,3 +590,85 @@ fn send_from() {
         .unwrap()
     );
 }
+
+#[test]
+fn mint_from_native() {
+    let mut deps = mock_dependencies(&[]);
+    let addr = String::from("addr0000");
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+
+    let mint_msg = ExecuteMsg::MintFromNative {
+        recipient: addr.clone(),
+        min_received: None,
+    };
+    let err = execute::<StrideLSDConfigRaw, StrideLSDConfig>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(addr.as_str(), &coins(1000, MOCK_LSD_DENOM)),
+        mint_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!(
+            "You need to deposit {} tokens only, deposited {:?}",
+            MOCK_LSD_UNDERLYING_DENOM,
+            coins(1000, MOCK_LSD_DENOM)
+        ))
+        .into()
+    );
+
+    // Stride tokens can't be bonded from here, the underlying tokens are swapped for them
+    // The swap has to return at least the oracle price minus the maximum spread of 1%
+    let res = execute::<StrideLSDConfigRaw, StrideLSDConfig>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(addr.as_str(), &coins(1000, MOCK_LSD_UNDERLYING_DENOM)),
+        mint_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_SWAP_ROUTER_ADDR.to_string(),
+                msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations: vec![SwapOperation::AstroSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: MOCK_LSD_UNDERLYING_DENOM.to_string(),
+                        },
+                        ask_asset_info: AssetInfo::NativeToken {
+                            denom: MOCK_LSD_DENOM.to_string(),
+                        },
+                    }],
+                    minimum_receive: Some(Uint128::from(660u128)),
+                    to: None,
+                    max_spread: Some(Decimal::percent(1)),
+                })
+                .unwrap(),
+                funds: coins(1000, MOCK_LSD_UNDERLYING_DENOM),
+            }),
+            NATIVE_MINT_REPLY_ID
+        )]
+    );
+
+    // The swap returned 660 stride tokens, minted at the 1.5 exchange rate
+    deps.querier
+        .with_native_balance(MOCK_CONTRACT_ADDR, coins(660, MOCK_LSD_DENOM));
+    reply::<StrideLSDConfigRaw, StrideLSDConfig>(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: NATIVE_MINT_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_balance(deps.as_ref(), addr).unwrap().balance,
+        Uint128::from(990u128)
+    );
+}
@@ -1,12 +1,16 @@
+use std::collections::HashMap;
 use std::marker::PhantomData;
 
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::Decimal;
 use cosmwasm_std::Decimal256;
 use cosmwasm_std::{
     from_binary, from_slice, to_binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
-    QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
+use cw20::{BalanceResponse, Cw20QueryMsg};
 
+use basset::dex_router::{QueryMsg as SwapQueryMsg, SimulateSwapOperationsResponse};
 use basset::external::{LSDQueryMsg, LSDStateResponse};
 use basset::oracle::PriceResponse;
 use basset::oracle::QueryMsg as OracleQueryMsg;
@@ -21,6 +25,12 @@ pub const MOCK_LSD_UNDERLYING_DENOM: &str = "uluna";
 
 pub const MOCK_ORACLE_CONTRACT_ADDR: &str = "oracle";
 
+pub const MOCK_SWAP_ROUTER_ADDR: &str = "swap_router";
+
+pub const MOCK_ASTROPORT_ADDR: &str = "astroport";
+pub const MOCK_PHOENIX_ADDR: &str = "phoenix";
+pub const MOCK_TERRASWAP_ADDR: &str = "terraswap";
+
 pub fn mock_dependencies(
     contract_balance: &[Coin],
 ) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
@@ -40,6 +50,31 @@ pub struct WasmMockQuerier {
     base: MockQuerier<Empty>,
     lsd_state_querier: LsdStateQuerier,
     oracle_price_querier: OraclePriceQuerier,
+    token_querier: TokenQuerier,
+    // Price of every swap simulated on each dex router, the other routers fail to simulate swaps
+    swap_prices: HashMap<String, Decimal>,
+}
+
+#[derive(Clone, Default)]
+pub struct TokenQuerier {
+    // this lets us iterate over all pairs that match the first string
+    balances: HashMap<String, HashMap<String, Uint128>>,
+}
+
+impl TokenQuerier {
+    pub fn new(balances: &[(&String, &[(&String, &Uint128)])]) -> Self {
+        let mut balances_map: HashMap<String, HashMap<String, Uint128>> = HashMap::new();
+        for (contract_addr, balances) in balances.iter() {
+            let mut contract_balances_map: HashMap<String, Uint128> = HashMap::new();
+            for (addr, balance) in balances.iter() {
+                contract_balances_map.insert(addr.to_string(), **balance);
+            }
+            balances_map.insert(contract_addr.to_string(), contract_balances_map);
+        }
+        TokenQuerier {
+            balances: balances_map,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -115,6 +150,34 @@ impl WasmMockQuerier {
                         }
                         _ => unimplemented!(),
                     }
+                } else if *contract_addr == MOCK_LSD_TOKEN_CONTRACT_ADDR {
+                    match from_binary(msg).unwrap() {
+                        Cw20QueryMsg::Balance { address } => {
+                            // Unknown balances are considered empty
+                            let balance = self
+                                .token_querier
+                                .balances
+                                .get(contract_addr)
+                                .and_then(|balances| balances.get(&address))
+                                .cloned()
+                                .unwrap_or_default();
+                            SystemResult::Ok(ContractResult::from(to_binary(&BalanceResponse {
+                                balance,
+                            })))
+                        }
+                        _ => unimplemented!(),
+                    }
+                } else if let Some(price) = self.swap_prices.get(contract_addr) {
+                    match from_binary(msg).unwrap() {
+                        SwapQueryMsg::SimulateSwapOperations { offer_amount, .. } => {
+                            SystemResult::Ok(ContractResult::from(to_binary(
+                                &SimulateSwapOperationsResponse {
+                                    amount: offer_amount * *price,
+                                },
+                            )))
+                        }
+                        _ => unimplemented!(),
+                    }
                 } else {
                     unimplemented!()
                 }
@@ -130,6 +193,8 @@ impl WasmMockQuerier {
             base,
             lsd_state_querier: LsdStateQuerier { lsd_state: None },
             oracle_price_querier: OraclePriceQuerier { price: None },
+            token_querier: TokenQuerier::default(),
+            swap_prices: HashMap::new(),
         }
     }
 
@@ -142,4 +207,22 @@ impl WasmMockQuerier {
     pub fn with_oracle_price(&mut self, price: Decimal256) {
         self.oracle_price_querier = OraclePriceQuerier::new(price);
     }
+
+    // configure the dex routers mock querier
+    pub fn with_swap_prices(&mut self, prices: &[(&str, Decimal)]) {
+        self.swap_prices = prices
+            .iter()
+            .map(|(router, price)| (router.to_string(), *price))
+            .collect();
+    }
+
+    // configure the native balances mock querier
+    pub fn with_native_balance(&mut self, address: &str, balance: Vec<Coin>) {
+        self.base.update_balance(address, balance);
+    }
+
+    // configure the cw20 token balances mock querier
+    pub fn with_token_balances(&mut self, balances: &[(&String, &[(&String, &Uint128)])]) {
+        self.token_querier = TokenQuerier::new(balances);
+    }
 }
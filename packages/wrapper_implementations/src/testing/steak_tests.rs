@@ -1,26 +1,46 @@
 use crate::steak::LsdContractsRaw;
 
-use crate::steak::SteakLSDHub;
+use crate::steak::{migrate, MigrateMsg, SteakLSDHub};
 use crate::testing::mock_querier::{WasmMockQuerier, MOCK_HUB_CONTRACT_ADDR};
-use basset::external::LSDStateResponse;
-use basset::wrapper::ExecuteMsg;
+use basset::dex_router::{AssetInfo, ExecuteMsg as RouterExecuteMsg, SwapOperation};
+use basset::external::{LSDExecuteMsg, LSDStateResponse};
+use basset::math::{multiply_ratio, Rounding};
+use basset::permit::{pubkey_to_address, PermitPayload};
+use basset::wrapper::{
+    AccruedRewardsResponse, AdminResponse, CircuitBreakerResponse, Cw20HookMsg, ExchangeRateEntry, ExchangeRatesResponse,
+    ExecuteMsg, FlashLoanFeeResponse, LsdLedgerResponse, MintLimitsResponse,
+    PauseStateResponse, PermitNonceResponse, RetentionResponse, RewardRecipient, RewardSplitResponse, SlashingLoss, SlashingLossResponse, TotalSupplyResponse, QueryMsg, SimulationResponse, WrapperStateResponse,
+};
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    coins, to_binary, Api, CosmosMsg, Decimal, DepsMut, OwnedDeps, Storage, SubMsg, Uint128,
-    WasmMsg,
+    coins, from_binary, to_binary, to_vec, Addr, Api, Binary, CosmosMsg, Decimal, DepsMut, OwnedDeps, Reply, StdError,
+    Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint256, WasmMsg,
 };
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use k256::ecdsa::{Signature, SigningKey};
+use k256::sha2::{Digest, Sha256};
 use std::str::FromStr;
 
-use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse, TokenInfoResponse};
-use cw20_base::contract::{query_minter, query_token_info};
+use cw20::{
+    AllowanceResponse, BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, Expiration, MinterResponse, TokenInfoResponse,
+};
+use cw20_base::contract::{query_balance, query_minter, query_token_info};
+use cw20_base::ContractError;
 
 use crate::testing::mock_querier::{
-    mock_dependencies, MOCK_LSD_HUB_CONTRACT_ADDR, MOCK_LSD_TOKEN_CONTRACT_ADDR,
+    mock_dependencies, MOCK_ASTROPORT_ADDR, MOCK_LSD_HUB_CONTRACT_ADDR,
+    MOCK_LSD_TOKEN_CONTRACT_ADDR, MOCK_PHOENIX_ADDR, MOCK_TERRASWAP_ADDR,
+};
+use cavern_lsd_wrapper_token::contract::{
+    execute, instantiate, query, reply, NATIVE_MINT_REPLY_ID, SECONDS_PER_YEAR,
 };
-use cavern_lsd_wrapper_token::contract::{execute, instantiate};
 use cavern_lsd_wrapper_token::msg::TokenInitMsg;
+use cavern_lsd_wrapper_token::state::read_lsd_config;
+use cw2::{get_contract_version, set_contract_version};
 use std::borrow::BorrowMut;
 
+const MOCK_GUARDIAN_ADDR: &str = "guardian";
+
 // this will set up the init for other tests
 fn do_init_with_minter<S: Storage, A: Api>(
     deps: &mut OwnedDeps<S, A, WasmMockQuerier>,
@@ -56,6 +76,8 @@ fn _do_init<S: Storage, A: Api>(
         decimals: 6,
         initial_balances: vec![],
         hub_contract: MOCK_HUB_CONTRACT_ADDR.to_string(),
+        guardian: Some(MOCK_GUARDIAN_ADDR.to_string()),
+        admin: None,
         lsd_config,
     };
 
@@ -97,6 +119,7 @@ pub fn do_mint(deps: DepsMut, addr: String, amount: Uint128, exchange_rate: Deci
     let msg = ExecuteMsg::Mint {
         recipient: addr,
         amount,
+        max_lsd_in: None,
     };
     let minter = "any_person_really";
     let info = mock_info(minter, &[]);
@@ -112,8 +135,8 @@ pub fn do_mint(deps: DepsMut, addr: String, amount: Uint128, exchange_rate: Deci
             msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
                 recipient: MOCK_CONTRACT_ADDR.to_string(),
                 owner: minter.to_string(),
-                amount: Decimal::from_ratio(amount, 1u128) / exchange_rate * Uint128::one()
-                    + Uint128::one()
+                amount: multiply_ratio(amount, Decimal::one().atomics(), exchange_rate.atomics(), Rounding::Up)
+                    .unwrap()
             })
             .unwrap(),
             funds: vec![]
@@ -139,6 +162,8 @@ fn proper_initialization() {
         decimals: 6,
         initial_balances: vec![],
         hub_contract: MOCK_HUB_CONTRACT_ADDR.to_string(),
+        guardian: None,
+        admin: None,
         lsd_config,
     };
     let info = mock_info(&String::from("owner"), &[]);
@@ -257,6 +282,7 @@ fn mint() {
     let msg = ExecuteMsg::Mint {
         recipient: addr,
         amount: Uint128::new(1u128),
+        max_lsd_in: None,
     };
 
     let _res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
@@ -279,6 +305,7 @@ fn mint_multiple_exchange_rates() {
     let msg = ExecuteMsg::Mint {
         recipient: addr.clone(),
         amount: Uint128::new(1u128),
+        max_lsd_in: None,
     };
 
     let _res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
@@ -324,6 +351,82 @@ fn mint_multiple_exchange_rates() {
     );
 }
 
+#[test]
+fn mint_through_receive() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr = String::from("addr0000");
+    let recipient = String::from("addr0001");
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+
+    // Only the lsd token can call the receive hook
+    let info = mock_info("another_token", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: addr.clone(),
+        amount: Uint128::new(1000u128),
+        msg: to_binary(&Cw20HookMsg::Mint {
+            recipient: None,
+            min_received: None,
+        })
+        .unwrap(),
+    });
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The lsd tokens are already in the contract, no transfer is needed
+    let info = mock_info(MOCK_LSD_TOKEN_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: addr.clone(),
+        amount: Uint128::new(1000u128),
+        msg: to_binary(&Cw20HookMsg::Mint {
+            recipient: None,
+            min_received: None,
+        })
+        .unwrap(),
+    });
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        query_balance(deps.as_ref(), addr.clone()).unwrap().balance,
+        Uint128::new(1500u128)
+    );
+
+    // Minting to another recipient
+    let info = mock_info(MOCK_LSD_TOKEN_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: addr,
+        amount: Uint128::new(1000u128),
+        msg: to_binary(&Cw20HookMsg::Mint {
+            recipient: Some(recipient.clone()),
+            min_received: None,
+        })
+        .unwrap(),
+    });
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        query_balance(deps.as_ref(), recipient).unwrap().balance,
+        Uint128::new(1500u128)
+    );
+}
+
 #[test]
 fn burn() {
     let mut deps = mock_dependencies(&coins(2, "token"));
@@ -341,6 +444,8 @@ fn burn() {
     let info = mock_info(addr.as_str(), &[]);
     let msg = ExecuteMsg::Burn {
         amount: Uint128::new(1234u128),
+        min_received: None,
+        recipient: None,
     };
 
     let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
@@ -367,10 +472,10 @@ fn burn() {
 }
 
 #[test]
-fn burn_from() {
+fn burn_to_recipient_and_unwrap() {
     let mut deps = mock_dependencies(&coins(2, "token"));
     let addr = String::from("addr0000");
-    let addr1 = String::from("addr0001");
+    let vault = String::from("vault0000");
     let amount1 = Uint128::from(12340000u128);
 
     do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
@@ -381,26 +486,47 @@ fn burn_from() {
         Decimal::from_str("1.5").unwrap(),
     );
 
+    // The lsd tokens can be sent to another recipient
     let info = mock_info(addr.as_str(), &[]);
-    let msg = ExecuteMsg::IncreaseAllowance {
-        spender: addr1.clone(),
+    let msg = ExecuteMsg::Burn {
         amount: Uint128::new(1234u128),
-        expires: None,
+        min_received: None,
+        recipient: Some(vault.clone()),
     };
-    let _ = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
         deps.as_mut(),
         mock_env(),
         info,
         msg,
     )
     .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: vault.clone(),
+                amount: Uint128::new(822u128)
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
 
-    let info = mock_info(addr1.as_str(), &[]);
-    let msg = ExecuteMsg::BurnFrom {
-        owner: addr,
+    // Sending wrapper tokens to the wrapper itself unwraps them
+    let vault_msg = to_binary(&"deposit").unwrap();
+    let hook_msg = to_binary(&Cw20HookMsg::Unwrap {
+        recipient: Some(vault.clone()),
+        msg: Some(vault_msg.clone()),
+        min_received: Some(Uint128::new(822u128)),
+    })
+    .unwrap();
+    let info = mock_info(addr.as_str(), &[]);
+    let msg = ExecuteMsg::Send {
+        contract: MOCK_CONTRACT_ADDR.to_string(),
         amount: Uint128::new(1234u128),
+        msg: hook_msg.clone(),
     };
-
     let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
         deps.as_mut(),
         mock_env(),
@@ -408,130 +534,2341 @@ fn burn_from() {
         msg,
     )
     .unwrap();
+    let receive_msg = Cw20ReceiveMsg {
+        sender: addr.clone(),
+        amount: Uint128::new(1234u128),
+        msg: hook_msg,
+    };
+    assert_eq!(
+        res.messages[0].msg,
+        receive_msg.clone().into_cosmos_msg(MOCK_CONTRACT_ADDR).unwrap()
+    );
 
-    // When you burn, you should get your lsd token back
+    // Only the wrapper token can trigger the unwrap hook
+    let info = mock_info(MOCK_LSD_TOKEN_CONTRACT_ADDR, &[]);
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Receive(receive_msg.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The received wrapper tokens are burned and the lsd tokens are forwarded to the vault with its message
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Receive(receive_msg),
+    )
+    .unwrap();
     assert_eq!(
         res.messages,
         vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: addr1,
-                amount: Uint128::new(822u128)
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: vault,
+                amount: Uint128::new(822u128),
+                msg: vault_msg,
             })
             .unwrap(),
             funds: vec![],
         }))]
-    )
+    );
+    assert_eq!(
+        query_balance(deps.as_ref(), MOCK_CONTRACT_ADDR.to_string())
+            .unwrap()
+            .balance,
+        Uint128::zero()
+    );
+    assert_eq!(
+        query_balance(deps.as_ref(), addr).unwrap().balance,
+        amount1 - Uint128::new(2468u128)
+    );
 }
 
 #[test]
-fn send() {
+fn burn_and_swap() {
     let mut deps = mock_dependencies(&coins(2, "token"));
-    let addr1 = String::from("addr0001");
-    let dummny_contract_addr = String::from("dummy");
+    let addr = String::from("addr0000");
+    let vault = String::from("vault0000");
     let amount1 = Uint128::from(12340000u128);
 
     do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
     do_mint(
         deps.as_mut(),
-        addr1.clone(),
+        addr.clone(),
         amount1,
         Decimal::from_str("1.5").unwrap(),
     );
 
-    let dummy_msg = ExecuteMsg::Transfer {
-        recipient: addr1.clone(),
-        amount: Uint128::new(1u128),
+    let burn_msg = |minimum_receive: u128| ExecuteMsg::BurnAndSwap {
+        amount: Uint128::new(1234u128),
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+        minimum_receive: Uint128::new(minimum_receive),
+        recipient: Some(vault.clone()),
     };
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(addr.as_str(), &[]),
+        burn_msg(1000),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "No dex routers are set to swap the lsd tokens"
+        ))
+    );
 
-    let info = mock_info(addr1.as_str(), &[]);
-    let msg = ExecuteMsg::Send {
-        contract: dummny_contract_addr.clone(),
-        amount: Uint128::new(1u128),
-        msg: to_binary(&dummy_msg).unwrap(),
+    // Only the admin can set the dex routers
+    let update_msg = ExecuteMsg::UpdateSwapConfig {
+        astroport_addr: MOCK_ASTROPORT_ADDR.to_string(),
+        phoenix_addr: MOCK_PHOENIX_ADDR.to_string(),
+        terraswap_addr: MOCK_TERRASWAP_ADDR.to_string(),
     };
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(addr.as_str(), &[]),
+        update_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg,
+    )
+    .unwrap();
+    deps.querier.with_swap_prices(&[
+        (MOCK_ASTROPORT_ADDR, Decimal::from_str("1.4").unwrap()),
+        (MOCK_PHOENIX_ADDR, Decimal::from_str("1.45").unwrap()),
+        (MOCK_TERRASWAP_ADDR, Decimal::from_str("1.3").unwrap()),
+    ]);
+
+    // The 822 lsd tokens released are swapped for 1191 uluna on the best dex
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(addr.as_str(), &[]),
+        burn_msg(1192),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The swap would return 1191, less than the minimum of 1192"
+        ))
+    );
 
     let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
         deps.as_mut(),
         mock_env(),
-        info,
-        msg,
+        mock_info(addr.as_str(), &[]),
+        burn_msg(1191),
     )
     .unwrap();
-    assert_eq!(res.messages.len(), 1);
-
     assert_eq!(
-        res.messages[0].msg,
-        Cw20ReceiveMsg {
-            sender: addr1,
-            amount: Uint128::new(1),
-            msg: to_binary(&dummy_msg).unwrap(),
-        }
-        .into_cosmos_msg(dummny_contract_addr)
-        .unwrap()
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: MOCK_PHOENIX_ADDR.to_string(),
+                amount: Uint128::new(822u128),
+                msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations: vec![SwapOperation::TokenSwap {
+                        offer_asset_info: AssetInfo::Token {
+                            contract_addr: Addr::unchecked(MOCK_LSD_TOKEN_CONTRACT_ADDR),
+                        },
+                        ask_asset_info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    }],
+                    minimum_receive: Some(Uint128::new(1191u128)),
+                    to: Some(vault),
+                    max_spread: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert_eq!(
+        query_balance(deps.as_ref(), addr).unwrap().balance,
+        amount1 - Uint128::new(1234u128)
     );
 }
 
 #[test]
-fn send_from() {
+fn slippage_bounds() {
     let mut deps = mock_dependencies(&coins(2, "token"));
-    let addr1 = String::from("addr0001");
-    let addr2 = String::from("addr0002");
-    let dummny_contract_addr = String::from("dummy");
-    let amount1 = Uint128::from(12340000u128);
+    let addr = String::from("addr0000");
 
     do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
-    do_mint(
+
+    // Minting 10 tokens at a 1.5 exchange rate needs 7 lsd tokens
+    let info = mock_info(addr.as_str(), &[]);
+    let msg = ExecuteMsg::Mint {
+        recipient: addr.clone(),
+        amount: Uint128::new(10u128),
+        max_lsd_in: Some(Uint128::new(6u128)),
+    };
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
         deps.as_mut(),
-        addr1.clone(),
-        amount1,
-        Decimal::from_str("1.5").unwrap(),
-    );
+        mock_env(),
+        info.clone(),
+        msg,
+    )
+    .unwrap_err();
 
-    let info = mock_info(addr1.as_str(), &[]);
-    let msg = ExecuteMsg::IncreaseAllowance {
-        spender: addr2.clone(),
-        amount: Uint128::new(1u128),
-        expires: None,
+    let msg = ExecuteMsg::Mint {
+        recipient: addr.clone(),
+        amount: Uint128::new(10u128),
+        max_lsd_in: Some(Uint128::new(7u128)),
     };
-    let _ = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
         deps.as_mut(),
         mock_env(),
-        info,
+        info.clone(),
         msg,
     )
     .unwrap();
 
-    let dummy_msg = ExecuteMsg::Transfer {
-        recipient: addr1.clone(),
-        amount: Uint128::new(1u128),
+    // Minting with 1000 lsd tokens yields 1500 tokens
+    let msg = ExecuteMsg::MintWith {
+        recipient: addr.clone(),
+        lsd_amount: Uint128::new(1000u128),
+        min_received: Some(Uint128::new(1501u128)),
     };
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        msg,
+    )
+    .unwrap_err();
 
-    let info = mock_info(addr2.as_str(), &[]);
-    let msg = ExecuteMsg::SendFrom {
-        owner: addr1,
-        contract: dummny_contract_addr.clone(),
-        amount: Uint128::new(1u128),
-        msg: to_binary(&dummy_msg).unwrap(),
+    let msg = ExecuteMsg::MintWith {
+        recipient: addr.clone(),
+        lsd_amount: Uint128::new(1000u128),
+        min_received: Some(Uint128::new(1500u128)),
+    };
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        msg,
+    )
+    .unwrap();
+
+    // The same bound applies when minting through the receive hook
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: addr.clone(),
+        amount: Uint128::new(1000u128),
+        msg: to_binary(&Cw20HookMsg::Mint {
+            recipient: None,
+            min_received: Some(Uint128::new(1501u128)),
+        })
+        .unwrap(),
+    });
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_LSD_TOKEN_CONTRACT_ADDR, &[]),
+        msg,
+    )
+    .unwrap_err();
+
+    // Burning 1234 tokens gives back 822 lsd tokens
+    let msg = ExecuteMsg::Burn {
+        amount: Uint128::new(1234u128),
+        min_received: Some(Uint128::new(823u128)),
+        recipient: None,
     };
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        msg,
+    )
+    .unwrap_err();
 
-    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+    let msg = ExecuteMsg::Burn {
+        amount: Uint128::new(1234u128),
+        min_received: Some(Uint128::new(822u128)),
+        recipient: None,
+    };
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
         deps.as_mut(),
         mock_env(),
         info,
         msg,
     )
     .unwrap();
-    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        query_balance(deps.as_ref(), addr).unwrap().balance,
+        Uint128::new(276u128)
+    );
+}
+
+#[test]
+fn simulate_mint_and_burn() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr = String::from("addr0000");
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+
+    let simulate = |deps: &OwnedDeps<_, _, WasmMockQuerier>, msg: QueryMsg| -> SimulationResponse {
+        from_binary(
+            &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(deps.as_ref(), mock_env(), msg)
+                .unwrap(),
+        )
+        .unwrap()
+    };
 
+    // Minting 10 tokens at a 1.5 exchange rate needs 7 lsd tokens (including the collateral pad)
+    let simulation = simulate(
+        &deps,
+        QueryMsg::SimulateMint {
+            amount: Uint128::new(10u128),
+        },
+    );
     assert_eq!(
-        res.messages[0].msg,
-        Cw20ReceiveMsg {
-            sender: addr2,
-            amount: Uint128::new(1),
-            msg: to_binary(&dummy_msg).unwrap(),
+        simulation,
+        SimulationResponse {
+            lsd_amount: Uint128::new(7u128),
+            wrapper_amount: Uint128::new(10u128),
         }
-        .into_cosmos_msg(dummny_contract_addr)
-        .unwrap()
     );
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(addr.as_str(), &[]),
+        ExecuteMsg::Mint {
+            recipient: addr.clone(),
+            amount: Uint128::new(10u128),
+            max_lsd_in: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: addr.clone(),
+                recipient: MOCK_CONTRACT_ADDR.to_string(),
+                amount: simulation.lsd_amount,
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // Minting with 1000 lsd tokens yields 1500 tokens
+    let simulation = simulate(
+        &deps,
+        QueryMsg::SimulateMintWith {
+            lsd_amount: Uint128::new(1000u128),
+        },
+    );
+    assert_eq!(
+        simulation,
+        SimulationResponse {
+            lsd_amount: Uint128::new(1000u128),
+            wrapper_amount: Uint128::new(1500u128),
+        }
+    );
+
+    // Burning 1234 tokens gives back 822 lsd tokens
+    let simulation = simulate(
+        &deps,
+        QueryMsg::SimulateBurn {
+            amount: Uint128::new(1234u128),
+        },
+    );
+    assert_eq!(
+        simulation,
+        SimulationResponse {
+            lsd_amount: Uint128::new(822u128),
+            wrapper_amount: Uint128::new(1234u128),
+        }
+    );
+}
+
+#[test]
+fn exchange_rate_history() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr = String::from("addr0000");
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+
+    // The first mint records the rates before any token is minted
+    let start_env = mock_env();
+    let msg = ExecuteMsg::MintWith {
+        recipient: addr.clone(),
+        lsd_amount: Uint128::new(1000u128),
+        min_received: None,
+    };
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        start_env.clone(),
+        mock_info(addr.as_str(), &[]),
+        msg,
+    )
+    .unwrap();
+    deps.querier.with_token_balances(&[(
+        &MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::new(1000u128))],
+    )]);
+
+    // One year later, the lsd has increased in value by 10%
+    deps.querier.with_lsd_state(LSDStateResponse {
+        exchange_rate: Decimal::from_str("1.65").unwrap(),
+        total_usteak: Uint128::from(1000000000u128),
+        total_uluna: Uint128::from(1650000000u128),
+        unlocked_coins: vec![],
+    });
+    let mut end_env = mock_env();
+    end_env.block.time = end_env.block.time.plus_seconds(SECONDS_PER_YEAR);
+    let msg = ExecuteMsg::Burn {
+        amount: Uint128::new(15u128),
+        min_received: None,
+        recipient: None,
+    };
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        end_env.clone(),
+        mock_info(addr.as_str(), &[]),
+        msg,
+    )
+    .unwrap();
+
+    let start_entry = ExchangeRateEntry {
+        time: start_env.block.time.seconds(),
+        exchange_rate: Decimal::one(),
+        lsd_exchange_rate: Decimal::from_str("1.5").unwrap(),
+    };
+    let end_entry = ExchangeRateEntry {
+        time: end_env.block.time.seconds(),
+        exchange_rate: Decimal::from_str("1.1").unwrap(),
+        lsd_exchange_rate: Decimal::from_str("1.65").unwrap(),
+    };
+
+    let res: ExchangeRatesResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ExchangeRates {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        ExchangeRatesResponse {
+            exchange_rates: vec![end_entry.clone(), start_entry.clone()],
+            apr: Some(Decimal::from_str("0.1").unwrap()),
+            lsd_apr: Some(Decimal::from_str("0.1").unwrap()),
+        }
+    );
+
+    // Pagination goes back in time
+    let res: ExchangeRatesResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ExchangeRates {
+                start_after: Some(end_entry.time),
+                limit: Some(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        ExchangeRatesResponse {
+            exchange_rates: vec![start_entry],
+            apr: None,
+            lsd_apr: None,
+        }
+    );
+}
+
+#[test]
+fn guardian_pause() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr = String::from("addr0000");
+    let addr1 = String::from("addr0001");
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        addr.clone(),
+        Uint128::from(1500u128),
+        Decimal::from_str("1.5").unwrap(),
+    );
+
+    let exec = |deps: &mut OwnedDeps<_, _, WasmMockQuerier>, sender: &str, msg: ExecuteMsg| {
+        execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            msg,
+        )
+    };
+    let pause_all = ExecuteMsg::SetPaused {
+        mint: Some(true),
+        burn: Some(true),
+        decompound: Some(true),
+    };
+
+    // Only the guardian can pause the contract
+    let err = exec(&mut deps, addr.as_str(), pause_all.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    exec(&mut deps, MOCK_GUARDIAN_ADDR, pause_all).unwrap();
+
+    let pause_state: PauseStateResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PauseState {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        pause_state,
+        PauseStateResponse {
+            guardian: Some(MOCK_GUARDIAN_ADDR.to_string()),
+            mint: true,
+            burn: true,
+            decompound: true,
+        }
+    );
+
+    // Mint, burn and decompound operations are stopped
+    let err = exec(
+        &mut deps,
+        addr.as_str(),
+        ExecuteMsg::MintWith {
+            recipient: addr.clone(),
+            lsd_amount: Uint128::new(1000u128),
+            min_received: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("Mint is paused")));
+    let err = exec(
+        &mut deps,
+        MOCK_LSD_TOKEN_CONTRACT_ADDR,
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: addr.clone(),
+            amount: Uint128::new(1000u128),
+            msg: to_binary(&Cw20HookMsg::Mint {
+                recipient: None,
+                min_received: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("Mint is paused")));
+    let err = exec(&mut deps, addr.as_str(), ExecuteMsg::BurnAll {}).unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("Burn is paused")));
+    let err = exec(
+        &mut deps,
+        MOCK_HUB_CONTRACT_ADDR,
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Decompound is paused"))
+    );
+
+    // Transfers keep working
+    exec(
+        &mut deps,
+        addr.as_str(),
+        ExecuteMsg::Transfer {
+            recipient: addr1.clone(),
+            amount: Uint128::new(100u128),
+        },
+    )
+    .unwrap();
+
+    // The guardian role can be handed over, and only the new guardian can resume operations
+    exec(
+        &mut deps,
+        MOCK_GUARDIAN_ADDR,
+        ExecuteMsg::UpdateGuardian {
+            guardian: Some(addr1.clone()),
+        },
+    )
+    .unwrap();
+    let resume_burn = ExecuteMsg::SetPaused {
+        mint: None,
+        burn: Some(false),
+        decompound: None,
+    };
+    let err = exec(&mut deps, MOCK_GUARDIAN_ADDR, resume_burn.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    exec(&mut deps, addr1.as_str(), resume_burn).unwrap();
+
+    exec(
+        &mut deps,
+        addr.as_str(),
+        ExecuteMsg::Burn {
+            amount: Uint128::new(150u128),
+            min_received: None,
+            recipient: None,
+        },
+    )
+    .unwrap();
+    let err = exec(
+        &mut deps,
+        addr.as_str(),
+        ExecuteMsg::Mint {
+            recipient: addr.clone(),
+            amount: Uint128::new(150u128),
+            max_lsd_in: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("Mint is paused")));
+}
+
+#[test]
+fn mint_limits() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr = String::from("addr0000");
+    let addr1 = String::from("addr0001");
+    let addr2 = String::from("addr0002");
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+
+    let exec = |deps: &mut OwnedDeps<_, _, WasmMockQuerier>,
+                env: cosmwasm_std::Env,
+                sender: &str,
+                msg: ExecuteMsg| {
+        execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_mut(),
+            env,
+            mock_info(sender, &[]),
+            msg,
+        )
+    };
+    let mint = |recipient: &String, amount: u128| ExecuteMsg::Mint {
+        recipient: recipient.clone(),
+        amount: Uint128::new(amount),
+        max_lsd_in: None,
+    };
+    let update_limits = ExecuteMsg::UpdateMintLimits {
+        max_supply: Some(Uint128::new(2500u128)),
+        max_mint_per_address: Some(Uint128::new(1500u128)),
+        mint_window: 3600,
+    };
+
+    // Only the admin (the instantiator by default) can update the limits
+    let err = exec(&mut deps, mock_env(), addr.as_str(), update_limits.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    exec(&mut deps, mock_env(), "owner", update_limits).unwrap();
+
+    let mint_limits: MintLimitsResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::MintLimits {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        mint_limits,
+        MintLimitsResponse {
+            admin: Some("owner".to_string()),
+            max_supply: Some(Uint128::new(2500u128)),
+            max_mint_per_address: Some(Uint128::new(1500u128)),
+            mint_window: 3600,
+        }
+    );
+    // The supply limit is also reported as the cw20 minter cap
+    assert_eq!(
+        query_minter(deps.as_ref()).unwrap().unwrap().cap,
+        Some(Uint128::new(2500u128))
+    );
+
+    // The per-address limit needs a mint window
+    let err = exec(
+        &mut deps,
+        mock_env(),
+        "owner",
+        ExecuteMsg::UpdateMintLimits {
+            max_supply: Some(Uint128::new(2500u128)),
+            max_mint_per_address: Some(Uint128::new(1500u128)),
+            mint_window: 0,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The mint window can't be 0 with a per-address mint limit"
+        ))
+    );
+
+    // An address can't mint more than the limit during the mint window, whatever the recipients
+    let limit_error = |remaining: u128| {
+        ContractError::Std(StdError::generic_err(format!(
+            "Mint limit per address exceeded: {} can mint at most 1500 wrapper tokens every 3600 seconds, {} left in the current window",
+            addr, remaining
+        )))
+    };
+    exec(&mut deps, mock_env(), addr.as_str(), mint(&addr, 1000)).unwrap();
+    let err = exec(&mut deps, mock_env(), addr.as_str(), mint(&addr1, 501)).unwrap_err();
+    assert_eq!(err, limit_error(500));
+    exec(&mut deps, mock_env(), addr.as_str(), mint(&addr1, 500)).unwrap();
+
+    // The total supply can't exceed the limit either
+    // (the mock storage is not reverted on errors, so we mint from another address afterwards)
+    // Minting to an address doesn't use its own limit
+    let err = exec(&mut deps, mock_env(), addr1.as_str(), mint(&addr, 1001)).unwrap_err();
+    assert_eq!(err, ContractError::CannotExceedCap {});
+    exec(&mut deps, mock_env(), addr2.as_str(), mint(&addr, 1000)).unwrap();
+
+    // Once the window is over, the address can mint again
+    exec(
+        &mut deps,
+        mock_env(),
+        "owner",
+        ExecuteMsg::UpdateMintLimits {
+            max_supply: None,
+            max_mint_per_address: Some(Uint128::new(1500u128)),
+            mint_window: 3600,
+        },
+    )
+    .unwrap();
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(3599);
+    let err = exec(&mut deps, env.clone(), addr.as_str(), mint(&addr, 1)).unwrap_err();
+    assert_eq!(err, limit_error(0));
+    env.block.time = env.block.time.plus_seconds(1);
+    exec(&mut deps, env, addr.as_str(), mint(&addr, 1500)).unwrap();
+
+    assert_eq!(
+        query_token_info(deps.as_ref()).unwrap().total_supply,
+        Uint128::new(4000u128)
+    );
+}
+
+#[test]
+fn admin_transfer_and_config_updates() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let new_admin = String::from("new_admin");
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+
+    let exec = |deps: &mut OwnedDeps<_, _, WasmMockQuerier>,
+                env: cosmwasm_std::Env,
+                sender: &str,
+                msg: ExecuteMsg| {
+        execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_mut(),
+            env,
+            mock_info(sender, &[]),
+            msg,
+        )
+    };
+    let query_admin = |deps: &OwnedDeps<_, _, WasmMockQuerier>| -> AdminResponse {
+        from_binary(
+            &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Admin {},
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let propose = ExecuteMsg::ProposeNewAdmin {
+        admin: new_admin.clone(),
+        expires_in: 100,
+    };
+
+    // Only the admin can propose a new admin, for at most 2 weeks
+    let err = exec(&mut deps, mock_env(), new_admin.as_str(), propose.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    exec(
+        &mut deps,
+        mock_env(),
+        "owner",
+        ExecuteMsg::ProposeNewAdmin {
+            admin: new_admin.clone(),
+            expires_in: 14 * 24 * 60 * 60 + 1,
+        },
+    )
+    .unwrap_err();
+    exec(&mut deps, mock_env(), "owner", propose.clone()).unwrap();
+    assert_eq!(
+        query_admin(&deps),
+        AdminResponse {
+            admin: Some("owner".to_string()),
+            proposed_admin: Some(new_admin.clone()),
+            proposal_expiry: Some(mock_env().block.time.seconds() + 100),
+            hub_contract: MOCK_HUB_CONTRACT_ADDR.to_string(),
+        }
+    );
+
+    // Only the proposed admin can claim, before the proposal expires
+    let err = exec(&mut deps, mock_env(), "addr0000", ExecuteMsg::ClaimAdmin {}).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(101);
+    let err = exec(&mut deps, env, new_admin.as_str(), ExecuteMsg::ClaimAdmin {}).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Admin proposal expired"))
+    );
+
+    // A dropped proposal can't be claimed
+    exec(&mut deps, mock_env(), "owner", ExecuteMsg::DropAdminProposal {}).unwrap();
+    let err = exec(&mut deps, mock_env(), new_admin.as_str(), ExecuteMsg::ClaimAdmin {}).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Admin proposal not found"))
+    );
+
+    exec(&mut deps, mock_env(), "owner", propose).unwrap();
+    exec(&mut deps, mock_env(), new_admin.as_str(), ExecuteMsg::ClaimAdmin {}).unwrap();
+
+    // The new admin can repoint the hub, the old one can't anymore
+    let update_hub = ExecuteMsg::UpdateHub {
+        hub_contract: "new_hub".to_string(),
+    };
+    let err = exec(&mut deps, mock_env(), "owner", update_hub.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    exec(&mut deps, mock_env(), new_admin.as_str(), update_hub).unwrap();
+    assert_eq!(
+        query_admin(&deps),
+        AdminResponse {
+            admin: Some(new_admin.clone()),
+            proposed_admin: None,
+            proposal_expiry: None,
+            hub_contract: "new_hub".to_string(),
+        }
+    );
+    let err = exec(
+        &mut deps,
+        mock_env(),
+        MOCK_HUB_CONTRACT_ADDR,
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The lsd config is validated before being stored
+    let err = exec(
+        &mut deps,
+        mock_env(),
+        new_admin.as_str(),
+        ExecuteMsg::UpdateLsdConfig {
+            lsd_config: to_binary(&LsdContractsRaw {
+                hub: "".to_string(),
+                token: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            })
+            .unwrap(),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+    // The lsd token held by the wrapper can't be swapped for another one
+    let err = exec(
+        &mut deps,
+        mock_env(),
+        new_admin.as_str(),
+        ExecuteMsg::UpdateLsdConfig {
+            lsd_config: to_binary(&LsdContractsRaw {
+                hub: "new_lsd_hub".to_string(),
+                token: "other_lsd_token".to_string(),
+            })
+            .unwrap(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The lsd token of the wrapper can't be changed"
+        ))
+    );
+    exec(
+        &mut deps,
+        mock_env(),
+        new_admin.as_str(),
+        ExecuteMsg::UpdateLsdConfig {
+            lsd_config: to_binary(&LsdContractsRaw {
+                hub: "new_lsd_hub".to_string(),
+                token: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            })
+            .unwrap(),
+        },
+    )
+    .unwrap();
+    let lsd_config: SteakLSDHub<LSDStateResponse> = read_lsd_config(&deps.storage).unwrap();
+    assert_eq!(lsd_config.lsd_contracts.hub.as_str(), "new_lsd_hub");
+    assert_eq!(
+        lsd_config.lsd_contracts.token.as_str(),
+        MOCK_LSD_TOKEN_CONTRACT_ADDR
+    );
+}
+
+#[test]
+fn burn_from() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr = String::from("addr0000");
+    let addr1 = String::from("addr0001");
+    let amount1 = Uint128::from(12340000u128);
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        addr.clone(),
+        amount1,
+        Decimal::from_str("1.5").unwrap(),
+    );
+
+    let info = mock_info(addr.as_str(), &[]);
+    let msg = ExecuteMsg::IncreaseAllowance {
+        spender: addr1.clone(),
+        amount: Uint128::new(1234u128),
+        expires: None,
+    };
+    let _ = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        msg,
+    )
+    .unwrap();
+
+    let info = mock_info(addr1.as_str(), &[]);
+    let msg = ExecuteMsg::BurnFrom {
+        owner: addr,
+        amount: Uint128::new(1234u128),
+        min_received: None,
+        recipient: None,
+    };
+
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        msg,
+    )
+    .unwrap();
+
+    // When you burn, you should get your lsd token back
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: addr1,
+                amount: Uint128::new(822u128)
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    )
+}
+
+#[test]
+fn permit() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let signing_key = SigningKey::from_slice(&[1u8; 32]).unwrap();
+    let pubkey = Binary::from(signing_key.verifying_key().to_encoded_point(true).as_bytes());
+    let owner = pubkey_to_address(&pubkey, "terra").unwrap();
+    let spender = String::from("addr0001");
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        owner.clone(),
+        Uint128::from(12340000u128),
+        Decimal::from_str("1.5").unwrap(),
+    );
+
+    let sign = |amount: u128, nonce: u64| -> Binary {
+        let payload = PermitPayload {
+            chain_id: mock_env().block.chain_id,
+            contract: MOCK_CONTRACT_ADDR.to_string(),
+            owner: owner.clone(),
+            spender: spender.clone(),
+            amount: Uint128::new(amount),
+            expires: Expiration::Never {},
+            nonce,
+        };
+        let message_hash = Sha256::digest(to_vec(&payload).unwrap());
+        let signature: Signature = signing_key.sign_prehash(&message_hash).unwrap();
+        Binary::from(signature.to_bytes().as_slice())
+    };
+    let permit_msg = |amount: u128, nonce: u64, pubkey: &Binary, signature: Binary| ExecuteMsg::Permit {
+        owner: owner.clone(),
+        spender: spender.clone(),
+        amount: Uint128::new(amount),
+        expires: Expiration::Never {},
+        nonce,
+        pubkey: pubkey.clone(),
+        signature,
+    };
+    let query_allowance = |deps: &OwnedDeps<_, _, WasmMockQuerier>| -> Uint128 {
+        let allowance: AllowanceResponse = from_binary(
+            &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Allowance {
+                    owner: owner.clone(),
+                    spender: spender.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        allowance.allowance
+    };
+
+    // Anyone can relay the permit signed by the owner
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("relayer", &[]),
+        permit_msg(1234, 0, &pubkey, sign(1234, 0)),
+    )
+    .unwrap();
+    assert_eq!(query_allowance(&deps), Uint128::new(1234u128));
+    let nonce: PermitNonceResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PermitNonce {
+                owner: owner.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(nonce.nonce, 1);
+
+    // The permit can't be replayed
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("relayer", &[]),
+        permit_msg(1234, 0, &pubkey, sign(1234, 0)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Invalid permit nonce, expected 1"))
+    );
+
+    // The signature has to match the permit and the owner
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("relayer", &[]),
+        permit_msg(5000, 1, &pubkey, sign(1234, 1)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Invalid permit signature"))
+    );
+    let other_key = SigningKey::from_slice(&[2u8; 32]).unwrap();
+    let other_pubkey = Binary::from(other_key.verifying_key().to_encoded_point(true).as_bytes());
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("relayer", &[]),
+        permit_msg(1234, 1, &other_pubkey, sign(1234, 1)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The public key doesn't match the permit owner"
+        ))
+    );
+
+    // The spender can use the allowance right away
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(spender.as_str(), &[]),
+        ExecuteMsg::TransferFrom {
+            owner: owner.clone(),
+            recipient: "addr0002".to_string(),
+            amount: Uint128::new(1000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(query_allowance(&deps), Uint128::new(234u128));
+
+    // A new permit sets the allowance, even lower than the current one
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("relayer", &[]),
+        permit_msg(100, 1, &pubkey, sign(100, 1)),
+    )
+    .unwrap();
+    assert_eq!(query_allowance(&deps), Uint128::new(100u128));
+}
+
+#[test]
+fn balance_snapshots() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr = String::from("addr0000");
+    let addr1 = String::from("addr0001");
+    let start = mock_env().block.height;
+    let env_at = |height: u64| {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    };
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        addr.clone(),
+        Uint128::from(1500u128),
+        Decimal::from_str("1.5").unwrap(),
+    );
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        env_at(start + 5),
+        mock_info(addr.as_str(), &[]),
+        ExecuteMsg::Transfer {
+            recipient: addr1.clone(),
+            amount: Uint128::from(500u128),
+        },
+    )
+    .unwrap();
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        env_at(start + 15),
+        mock_info(addr1.as_str(), &[]),
+        ExecuteMsg::Burn {
+            amount: Uint128::from(300u128),
+            min_received: None,
+            recipient: None,
+        },
+    )
+    .unwrap();
+
+    let balance_at = |deps: &OwnedDeps<_, _, WasmMockQuerier>, address: &str, height: Option<u64>| -> Uint128 {
+        let balance: BalanceResponse = from_binary(
+            &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::BalanceAtHeight {
+                    address: address.to_string(),
+                    height,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        balance.balance
+    };
+    let supply_at = |deps: &OwnedDeps<_, _, WasmMockQuerier>, height: Option<u64>| -> Uint128 {
+        let supply: TotalSupplyResponse = from_binary(
+            &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TotalSupplyAtHeight { height },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        supply.total_supply
+    };
+
+    assert_eq!(balance_at(&deps, &addr, Some(start)), Uint128::from(1500u128));
+    assert_eq!(balance_at(&deps, &addr, Some(start + 4)), Uint128::from(1500u128));
+    assert_eq!(balance_at(&deps, &addr, Some(start + 5)), Uint128::from(1000u128));
+    assert_eq!(balance_at(&deps, &addr, None), Uint128::from(1000u128));
+    assert_eq!(balance_at(&deps, &addr1, Some(start + 4)), Uint128::zero());
+    assert_eq!(balance_at(&deps, &addr1, Some(start + 10)), Uint128::from(500u128));
+    assert_eq!(balance_at(&deps, &addr1, Some(start + 15)), Uint128::from(200u128));
+    assert_eq!(balance_at(&deps, "addr0002", Some(start + 10)), Uint128::zero());
+
+    assert_eq!(supply_at(&deps, Some(start)), Uint128::from(1500u128));
+    assert_eq!(supply_at(&deps, Some(start + 14)), Uint128::from(1500u128));
+    assert_eq!(supply_at(&deps, Some(start + 15)), Uint128::from(1200u128));
+    assert_eq!(supply_at(&deps, None), Uint128::from(1200u128));
+
+    let err = query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TotalSupplyAtHeight {
+            height: Some(start - 1),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!("Snapshots are only available from height {}", start))
+    );
+}
+
+#[test]
+fn send() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr1 = String::from("addr0001");
+    let dummny_contract_addr = String::from("dummy");
+    let amount1 = Uint128::from(12340000u128);
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        addr1.clone(),
+        amount1,
+        Decimal::from_str("1.5").unwrap(),
+    );
+
+    let dummy_msg = ExecuteMsg::Transfer {
+        recipient: addr1.clone(),
+        amount: Uint128::new(1u128),
+    };
+
+    let info = mock_info(addr1.as_str(), &[]);
+    let msg = ExecuteMsg::Send {
+        contract: dummny_contract_addr.clone(),
+        amount: Uint128::new(1u128),
+        msg: to_binary(&dummy_msg).unwrap(),
+    };
+
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    assert_eq!(
+        res.messages[0].msg,
+        Cw20ReceiveMsg {
+            sender: addr1,
+            amount: Uint128::new(1),
+            msg: to_binary(&dummy_msg).unwrap(),
+        }
+        .into_cosmos_msg(dummny_contract_addr)
+        .unwrap()
+    );
+}
+
+#[test]
+fn send_from() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr1 = String::from("addr0001");
+    let addr2 = String::from("addr0002");
+    let dummny_contract_addr = String::from("dummy");
+    let amount1 = Uint128::from(12340000u128);
+
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        addr1.clone(),
+        amount1,
+        Decimal::from_str("1.5").unwrap(),
+    );
+
+    let info = mock_info(addr1.as_str(), &[]);
+    let msg = ExecuteMsg::IncreaseAllowance {
+        spender: addr2.clone(),
+        amount: Uint128::new(1u128),
+        expires: None,
+    };
+    let _ = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        msg,
+    )
+    .unwrap();
+
+    let dummy_msg = ExecuteMsg::Transfer {
+        recipient: addr1.clone(),
+        amount: Uint128::new(1u128),
+    };
+
+    let info = mock_info(addr2.as_str(), &[]);
+    let msg = ExecuteMsg::SendFrom {
+        owner: addr1,
+        contract: dummny_contract_addr.clone(),
+        amount: Uint128::new(1u128),
+        msg: to_binary(&dummy_msg).unwrap(),
+    };
+
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    assert_eq!(
+        res.messages[0].msg,
+        Cw20ReceiveMsg {
+            sender: addr2,
+            amount: Uint128::new(1),
+            msg: to_binary(&dummy_msg).unwrap(),
+        }
+        .into_cosmos_msg(dummny_contract_addr)
+        .unwrap()
+    );
+}
+
+#[test]
+fn migrate_versions() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+
+    // Legacy wrappers are registered as cw20-base and have no admin
+    cavern_lsd_wrapper_token::state::ADMIN.remove(&mut deps.storage);
+    assert_eq!(
+        get_contract_version(&deps.storage).unwrap().contract,
+        "crates.io:cw20-base"
+    );
+
+    let res = migrate::<LSDStateResponse>(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            admin: Some("new_admin".to_string()),
+            guardian: None,
+        },
+        "crates.io:b_luna-token",
+        "1.1.0",
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes[1],
+        cosmwasm_std::attr("previous_contract", "crates.io:cw20-base")
+    );
+    let version = get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.contract, "crates.io:b_luna-token");
+    assert_eq!(version.version, "1.1.0");
+
+    let admin: AdminResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Admin {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(admin.admin, Some("new_admin".to_string()));
+
+    // The same version can be migrated to again, an older one can't
+    let msg = MigrateMsg {
+        admin: None,
+        guardian: None,
+    };
+    migrate::<LSDStateResponse>(deps.as_mut(), mock_env(), msg.clone(), "crates.io:b_luna-token", "1.1.0").unwrap();
+    let err = migrate::<LSDStateResponse>(deps.as_mut(), mock_env(), msg.clone(), "crates.io:b_luna-token", "1.0.9")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Can't downgrade crates.io:b_luna-token from 1.1.0 to 1.0.9"
+        ))
+    );
+
+    // A wrapper can't be migrated to another contract
+    set_contract_version(&mut deps.storage, "crates.io:amp_luna-token", "1.0.0").unwrap();
+    let err = migrate::<LSDStateResponse>(deps.as_mut(), mock_env(), msg, "crates.io:b_luna-token", "1.1.0").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Can't migrate from crates.io:amp_luna-token to crates.io:b_luna-token"
+        ))
+    );
+}
+
+#[test]
+fn lsd_ledger_and_surplus() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        "addr0000".to_string(),
+        Uint128::from(1500u128),
+        Decimal::from_str("1.5").unwrap(),
+    );
+
+    // Someone sends 100 lsd tokens directly to the wrapper
+    deps.querier.with_token_balances(&[(
+        &MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1100u128))],
+    )]);
+
+    let query_ledger = |deps: &OwnedDeps<_, _, WasmMockQuerier>| -> LsdLedgerResponse {
+        from_binary(
+            &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::LsdLedger {},
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        query_ledger(&deps),
+        LsdLedgerResponse {
+            ledger: Uint128::from(1000u128),
+            lsd_balance: Uint128::from(1100u128),
+            surplus: Uint128::from(100u128),
+            deficit: Uint128::zero(),
+        }
+    );
+
+    // The donation doesn't change the exchange rate of the wrapper, there is nothing to decompound
+    let state: WrapperStateResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::WrapperState {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(state.lsd_balance, Uint128::from(1000u128));
+    let rewards: AccruedRewardsResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AccruedRewards {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(rewards.lsd_rewards, Uint128::zero());
+
+    // Only the admin can handle the surplus
+    for msg in [
+        ExecuteMsg::SweepSurplus { recipient: None },
+        ExecuteMsg::AddSurplusToRewards {},
+    ] {
+        let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::SweepSurplus {
+            recipient: Some("treasury".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "treasury".to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // Once added to the ledger, the surplus is released by the next decompound
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::AddSurplusToRewards {},
+    )
+    .unwrap();
+    assert_eq!(query_ledger(&deps).ledger, Uint128::from(1100u128));
+    assert_eq!(query_ledger(&deps).surplus, Uint128::zero());
+
+    let rewards: AccruedRewardsResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AccruedRewards {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(rewards.lsd_rewards, Uint128::from(100u128));
+
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_HUB_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap();
+    assert_eq!(query_ledger(&deps).ledger, Uint128::from(1000u128));
+
+    // Wrappers migrated from a version without ledger start from their lsd balance
+    cavern_lsd_wrapper_token::state::LSD_LEDGER.remove(&mut deps.storage);
+    migrate::<LSDStateResponse>(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            admin: None,
+            guardian: None,
+        },
+        "crates.io:b_luna-token",
+        "1.1.0",
+    )
+    .unwrap();
+    assert_eq!(query_ledger(&deps).ledger, Uint128::from(1100u128));
+}
+
+#[test]
+fn flash_loan() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        "addr0000".to_string(),
+        Uint128::from(1500u128),
+        Decimal::from_str("1.5").unwrap(),
+    );
+    deps.querier.with_token_balances(&[(
+        &MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+    )]);
+
+    let borrower = "borrower";
+    let loan_msg = ExecuteMsg::FlashLoan {
+        amount: Uint128::from(500u128),
+        msg: to_binary(&"arbitrage").unwrap(),
+    };
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(borrower, &[]),
+        loan_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Flash loans are disabled"))
+    );
+
+    // Only the admin can set the flash loan fee
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(borrower, &[]),
+        ExecuteMsg::UpdateFlashLoanFee {
+            fee: Some(Decimal::percent(1)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateFlashLoanFee {
+            fee: Some(Decimal::percent(1)),
+        },
+    )
+    .unwrap();
+    let fee: FlashLoanFeeResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FlashLoanFee {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(fee.fee, Some(Decimal::percent(1)));
+
+    // Flash loans are paused along with burns
+    let set_burn_paused = |deps: &mut OwnedDeps<_, _, WasmMockQuerier>, paused: bool| {
+        execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MOCK_GUARDIAN_ADDR, &[]),
+            ExecuteMsg::SetPaused {
+                mint: None,
+                burn: Some(paused),
+                decompound: None,
+            },
+        )
+        .unwrap();
+    };
+    set_burn_paused(&mut deps, true);
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(borrower, &[]),
+        loan_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Flash loan is paused"))
+    );
+    set_burn_paused(&mut deps, false);
+
+    // The lsd tokens are sent to the borrower, the wrapper then checks they were paid back
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(borrower, &[]),
+        loan_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: borrower.to_string(),
+                    amount: Uint128::from(500u128),
+                    msg: to_binary(&"arbitrage").unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::FinishFlashLoan {}).unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(borrower, &[]),
+        loan_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("A flash loan is already in progress"))
+    );
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(borrower, &[]),
+        ExecuteMsg::FinishFlashLoan {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The borrower paid back the lent tokens but only part of the fee
+    deps.querier.with_token_balances(&[(
+        &MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1004u128))],
+    )]);
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::FinishFlashLoan {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The flash loan was not paid back, expected 1 more lsd tokens"
+        ))
+    );
+
+    // The fee is released by the next decompound
+    deps.querier.with_token_balances(&[(
+        &MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1005u128))],
+    )]);
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::FinishFlashLoan {},
+    )
+    .unwrap();
+    let ledger: LsdLedgerResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LsdLedger {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(ledger.ledger, Uint128::from(1005u128));
+    assert_eq!(ledger.surplus, Uint128::zero());
+    let rewards: AccruedRewardsResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AccruedRewards {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(rewards.lsd_rewards, Uint128::from(5u128));
+
+    // The fee is rounded up
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(borrower, &[]),
+        ExecuteMsg::FlashLoan {
+            amount: Uint128::from(150u128),
+            msg: to_binary(&"arbitrage").unwrap(),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[3], cosmwasm_std::attr("fee", "2"));
+}
+
+fn set_lsd_exchange_rate<S: Storage, A: Api>(
+    deps: &mut OwnedDeps<S, A, WasmMockQuerier>,
+    exchange_rate: &str,
+) {
+    deps.querier.with_lsd_state(LSDStateResponse {
+        exchange_rate: Decimal::from_str(exchange_rate).unwrap(),
+        total_usteak: Uint128::from(1000000000u128),
+        total_uluna: Uint128::from(1500000000u128),
+        unlocked_coins: vec![],
+    });
+}
+
+#[test]
+fn circuit_breaker() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    let addr = String::from("addr0000");
+    let mint_msg = ExecuteMsg::Mint {
+        recipient: addr.clone(),
+        amount: Uint128::from(100u128),
+        max_lsd_in: None,
+    };
+    let burn_msg = ExecuteMsg::Burn {
+        amount: Uint128::from(10u128),
+        min_received: None,
+        recipient: None,
+    };
+
+    // The circuit breaker is disabled by default
+    set_lsd_exchange_rate(&mut deps, "3");
+    do_mint(deps.as_mut(), addr.clone(), Uint128::from(1000u128), Decimal::from_str("3").unwrap());
+    set_lsd_exchange_rate(&mut deps, "1.5");
+
+    let update_msg = ExecuteMsg::UpdateCircuitBreaker {
+        max_change_per_hour: Some(Decimal::percent(1)),
+    };
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        update_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg.clone(),
+    )
+    .unwrap();
+
+    // Small moves are accepted
+    set_lsd_exchange_rate(&mut deps, "1.51");
+    do_mint(deps.as_mut(), addr.clone(), Uint128::from(100u128), Decimal::from_str("1.51").unwrap());
+
+    // Abrupt moves are rejected
+    set_lsd_exchange_rate(&mut deps, "1.6");
+    for msg in [mint_msg.clone(), burn_msg.clone()] {
+        let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Std(StdError::generic_err(
+                "Lsd exchange rate moved from 1.5 to 1.6, more than the allowed 0.01"
+            ))
+        );
+    }
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_HUB_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Decompound { recipient: None },
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("Lsd exchange rate moved"));
+
+    // Anyone can trip the circuit breaker, which then holds even if the rate comes back
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::CheckLsdExchangeRate {},
+    )
+    .unwrap();
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(res.events[0].ty, "circuit_breaker_tripped");
+    assert_eq!(res.attributes[2], cosmwasm_std::attr("tripped", "true"));
+
+    set_lsd_exchange_rate(&mut deps, "1.5");
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        mint_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Circuit breaker tripped, the lsd exchange rate has to be reviewed by the admin"
+        ))
+    );
+
+    let breaker: CircuitBreakerResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::CircuitBreaker {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        breaker,
+        CircuitBreakerResponse {
+            max_change_per_hour: Some(Decimal::percent(1)),
+            reference_rate: Some(Decimal::from_str("1.5").unwrap()),
+            reference_time: mock_env().block.time.seconds(),
+            tripped: true,
+        }
+    );
+
+    // The admin resets the circuit breaker
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg,
+    )
+    .unwrap();
+    do_mint(deps.as_mut(), addr.clone(), Uint128::from(100u128), Decimal::from_str("1.5").unwrap());
+
+    // The allowed move grows with the time elapsed since the reference rate
+    set_lsd_exchange_rate(&mut deps, "1.6");
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10 * 60 * 60);
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        env,
+        mock_info("addr0000", &[]),
+        mint_msg,
+    )
+    .unwrap();
+}
+
+#[test]
+fn slashing_loss_socialized_on_burn() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr = String::from("addr0000");
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        addr.clone(),
+        Uint128::from(1500u128),
+        Decimal::from_str("1.5").unwrap(),
+    );
+
+    let query_loss = |deps: &OwnedDeps<_, _, WasmMockQuerier>| -> SlashingLossResponse {
+        from_binary(
+            &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SlashingLoss {},
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        query_loss(&deps),
+        SlashingLossResponse {
+            under_collateralized: false,
+            redemption_rate: Decimal::one(),
+            luna_loss: Uint128::zero(),
+            last_loss: None,
+        }
+    );
+
+    // A slashing leaves the 1000 lsd tokens of the wrapper backing 1200 Luna for 1500 wrapper tokens
+    set_lsd_exchange_rate(&mut deps, "1.2");
+    assert_eq!(
+        query_loss(&deps),
+        SlashingLossResponse {
+            under_collateralized: true,
+            redemption_rate: Decimal::from_str("0.8").unwrap(),
+            luna_loss: Uint128::from(300u128),
+            last_loss: None,
+        }
+    );
+
+    // The burner only redeems its share of the backing, 400 Luna instead of 500, rounded down
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(addr.as_str(), &[]),
+        ExecuteMsg::Burn {
+            amount: Uint128::from(500u128),
+            min_received: None,
+            recipient: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: addr,
+                amount: Uint128::from(333u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // The remaining holders keep at least the same share of the backing
+    let loss = query_loss(&deps);
+    assert_eq!(loss.redemption_rate, Decimal::from_str("0.8004").unwrap());
+    assert_eq!(loss.luna_loss, Uint128::from(200u128));
+    assert_eq!(
+        loss.last_loss,
+        Some(SlashingLoss {
+            time: mock_env().block.time.seconds(),
+            exchange_rate: Decimal::from_str("0.8").unwrap(),
+            luna_loss: Uint128::from(300u128),
+        })
+    );
+}
+
+#[test]
+fn reward_split() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        "addr0000".to_string(),
+        Uint128::from(1500u128),
+        Decimal::from_str("1.5").unwrap(),
+    );
+
+    let update_split = |ratios: &[(&str, &str)]| ExecuteMsg::UpdateRewardSplit {
+        recipients: ratios
+            .iter()
+            .map(|(address, ratio)| RewardRecipient {
+                address: address.to_string(),
+                ratio: Decimal::from_str(ratio).unwrap(),
+            })
+            .collect(),
+    };
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        update_split(&[("treasury", "0.07")]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_split(&[("treasury", "0.7"), ("insurance", "0.4")]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The reward shares add up to 1.1, more than all the rewards"
+        ))
+    );
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_split(&[("treasury", "0.07"), ("insurance", "0.03")]),
+    )
+    .unwrap();
+
+    let split: RewardSplitResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardSplit {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(split.recipients.len(), 2);
+    assert_eq!(split.decompound_recipient_ratio, Decimal::percent(90));
+
+    // The 62 lsd tokens of rewards are split between the treasury, the insurance and the reward contract
+    set_lsd_exchange_rate(&mut deps, "1.6");
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_HUB_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Decompound {
+            recipient: Some("reward".to_string()),
+        },
+    )
+    .unwrap();
+    let transfer = |recipient: &str, amount: u128| {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: Uint128::new(amount),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))
+    };
+    assert_eq!(
+        res.messages,
+        vec![
+            transfer("treasury", 4),
+            transfer("insurance", 1),
+            transfer("reward", 57),
+        ]
+    );
+}
+
+#[test]
+fn retention_ratio() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    do_mint(
+        deps.as_mut(),
+        "addr0000".to_string(),
+        Uint128::from(1500u128),
+        Decimal::from_str("1.5").unwrap(),
+    );
+
+    let update_retention = |retention_ratio: &str| ExecuteMsg::UpdateRetentionRatio {
+        retention_ratio: Decimal::from_str(retention_ratio).unwrap(),
+    };
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        update_retention("0.5"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_retention("1.1"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "The retention ratio can't be more than 100%"
+        ))
+    );
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_retention("0.5"),
+    )
+    .unwrap();
+
+    // Half of the 0.0667 luna of rewards per wrapper token stays in the wrapper, only 31 of the 62 lsd tokens are released
+    set_lsd_exchange_rate(&mut deps, "1.6");
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_HUB_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Decompound {
+            recipient: Some("reward".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "reward".to_string(),
+                amount: Uint128::new(31u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let retention: RetentionResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Retention {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(retention.retention_ratio, Decimal::percent(50));
+    assert_eq!(
+        retention.target_exchange_rate,
+        Decimal::from_str("1.033333333333333333").unwrap()
+    );
+
+    // Mints and burns now happen at the target exchange rate instead of 1 luna per wrapper token
+    let simulate = |msg: QueryMsg| -> SimulationResponse {
+        from_binary(
+            &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(deps.as_ref(), mock_env(), msg)
+                .unwrap(),
+        )
+        .unwrap()
+    };
+    let mint = simulate(QueryMsg::SimulateMint {
+        amount: Uint128::new(1000u128),
+    });
+    assert_eq!(mint.lsd_amount, Uint128::new(646u128));
+    let mint_with = simulate(QueryMsg::SimulateMintWith {
+        lsd_amount: Uint128::new(1000u128),
+    });
+    assert_eq!(mint_with.wrapper_amount, Uint128::new(1548u128));
+    let burn = simulate(QueryMsg::SimulateBurn {
+        amount: Uint128::new(1000u128),
+    });
+    assert_eq!(burn.lsd_amount, Uint128::new(645u128));
+}
+
+#[test]
+fn mint_from_native() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    let addr = String::from("addr0000");
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+
+    let mint_msg = ExecuteMsg::MintFromNative {
+        recipient: addr.clone(),
+        min_received: Some(Uint128::from(1500u128)),
+    };
+    let err = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(addr.as_str(), &[]),
+        mint_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidZeroAmount {});
+
+    // The native tokens are bonded at the lsd hub on behalf of the wrapper
+    let res = execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(addr.as_str(), &coins(1000, "uluna")),
+        mint_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_LSD_HUB_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&LSDExecuteMsg::Bond {
+                    receiver: Some(MOCK_CONTRACT_ADDR.to_string()),
+                })
+                .unwrap(),
+                funds: coins(1000, "uluna"),
+            }),
+            NATIVE_MINT_REPLY_ID
+        )]
+    );
+
+    // The wrapper tokens are minted for the lsd tokens received from the hub
+    deps.querier.with_token_balances(&[(
+        &MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+    )]);
+    let bond_reply = Reply {
+        id: NATIVE_MINT_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    reply::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        bond_reply.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        query_balance(deps.as_ref(), addr.clone()).unwrap().balance,
+        Uint128::from(1500u128)
+    );
+    let ledger: LsdLedgerResponse = from_binary(
+        &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LsdLedger {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(ledger.ledger, Uint128::from(1000u128));
+
+    // Nothing is minted if no lsd token was received
+    execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(addr.as_str(), &coins(1000, "uluna")),
+        mint_msg,
+    )
+    .unwrap();
+    let err = reply::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+        deps.as_mut(),
+        mock_env(),
+        bond_reply,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidZeroAmount {});
+}
+
+#[test]
+fn backing_covers_supply_after_random_operations() {
+    let mut deps = mock_dependencies(&coins(2, "token"));
+    do_init_with_minter(deps.borrow_mut(), &String::from(MOCK_CONTRACT_ADDR), None);
+    let addr = String::from("addr0000");
+
+    // Deterministic pseudo-random sequence, so that failures can be reproduced
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    let mut next = |max: u128| -> u128 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed as u128 % max
+    };
+
+    let mut lsd_rate = Decimal::from_str("1.5").unwrap();
+    for _ in 0..500 {
+        let msg = match next(4) {
+            0 => ExecuteMsg::Mint {
+                recipient: addr.clone(),
+                amount: Uint128::new(next(1_000_000_000_000_000_000_000) + 1),
+                max_lsd_in: None,
+            },
+            1 => ExecuteMsg::MintWith {
+                recipient: addr.clone(),
+                lsd_amount: Uint128::new(next(1_000_000) + 1),
+                min_received: None,
+            },
+            2 => {
+                let balance = query_balance(deps.as_ref(), addr.clone()).unwrap().balance;
+                if balance.is_zero() {
+                    continue;
+                }
+                ExecuteMsg::Burn {
+                    amount: Uint128::new(next(balance.u128()) + 1),
+                    min_received: None,
+                    recipient: None,
+                }
+            }
+            _ => {
+                // The lsd only accrues rewards
+                lsd_rate += Decimal::from_ratio(next(1_000_000), 1_000_000_000_000u128);
+                set_lsd_exchange_rate(&mut deps, &lsd_rate.to_string());
+                ExecuteMsg::Decompound { recipient: None }
+            }
+        };
+        let sender = match msg {
+            ExecuteMsg::Decompound { .. } => MOCK_HUB_CONTRACT_ADDR,
+            _ => addr.as_str(),
+        };
+        execute::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            msg,
+        )
+        .unwrap();
+
+        let ledger: LsdLedgerResponse = from_binary(
+            &query::<LsdContractsRaw, SteakLSDHub<LSDStateResponse>>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::LsdLedger {},
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        deps.querier.with_token_balances(&[(
+            &MOCK_LSD_TOKEN_CONTRACT_ADDR.to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &ledger.ledger)],
+        )]);
+        let supply = query_token_info(deps.as_ref()).unwrap().total_supply;
+        assert!(
+            Uint256::from(ledger.ledger) * Uint256::from(lsd_rate.atomics())
+                >= Uint256::from(supply) * Uint256::from(Decimal::one().atomics()),
+            "{} lsd tokens at {} don't back {} wrapper tokens",
+            ledger.ledger,
+            lsd_rate,
+            supply
+        );
+    }
 }